@@ -0,0 +1,63 @@
+use axum_web_types::{
+    auth::{CleanupResponse, LoginUser, RevokeUser, TokensResponse},
+    users::UserContract,
+};
+use reqwest::Method;
+use uuid::Uuid;
+
+use super::{bearer, Client, Result, Tokens};
+
+impl<C: UserContract> Client<C> {
+    // the tokens are kept by the client for the next calls
+    pub async fn login(&self, username: &str, password_hash: &str) -> Result<Tokens> {
+        let login = LoginUser {
+            username: username.to_string(),
+            password_hash: password_hash.to_string(),
+        };
+        let request = self.request(Method::POST, "/auth/login").json(&login);
+        self.store_tokens(self.send(request).await?.json().await?)
+    }
+
+    pub async fn refresh(&self) -> Result<Tokens> {
+        let request = self.request(Method::POST, "/auth/refresh");
+        let request = bearer(request, Some(self.refresh_token()?));
+        self.store_tokens(self.send(request).await?.json().await?)
+    }
+
+    pub async fn logout(&self) -> Result<()> {
+        let request = self.request(Method::POST, "/auth/logout");
+        self.send(bearer(request, Some(self.refresh_token()?)))
+            .await?;
+        self.set_tokens(None);
+        Ok(())
+    }
+
+    // revokes all the tokens issued until now, admins only
+    pub async fn revoke_all(&self) -> Result<()> {
+        let request = self.request(Method::POST, "/auth/revoke-all");
+        self.send_authorized(request).await?;
+        Ok(())
+    }
+
+    // revokes the tokens of the user issued until now, admins can revoke any user
+    pub async fn revoke_user(&self, user_id: Uuid) -> Result<()> {
+        let request = self
+            .request(Method::POST, "/auth/revoke-user")
+            .json(&RevokeUser { user_id });
+        self.send_authorized(request).await?;
+        Ok(())
+    }
+
+    // removes the expired revoked tokens, returns their count
+    pub async fn cleanup(&self) -> Result<usize> {
+        let request = self.request(Method::POST, "/auth/cleanup");
+        let response: CleanupResponse = self.send_authorized(request).await?.json().await?;
+        Ok(response.deleted_tokens)
+    }
+
+    fn store_tokens(&self, response: TokensResponse) -> Result<Tokens> {
+        let tokens = Tokens::from(response);
+        self.set_tokens(Some(tokens.clone()));
+        Ok(tokens)
+    }
+}
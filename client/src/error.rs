@@ -0,0 +1,71 @@
+use axum_web_types::api_error::{ErrorCode, ProblemDetails};
+use reqwest::StatusCode;
+
+pub type Result<T> = std::result::Result<T, Error>;
+
+#[derive(Debug)]
+pub enum Error {
+    // the request could not be sent or the response could not be read
+    Http(reqwest::Error),
+    // the api answered with an error status, the problem details if the body carried them
+    Api {
+        status: StatusCode,
+        problem: Option<Box<ProblemDetails>>,
+    },
+    // the call needs the tokens of a login
+    NotAuthenticated,
+}
+
+impl Error {
+    pub fn status(&self) -> Option<StatusCode> {
+        match self {
+            Error::Http(e) => e.status(),
+            Error::Api { status, .. } => Some(*status),
+            Error::NotAuthenticated => None,
+        }
+    }
+
+    pub fn problem(&self) -> Option<&ProblemDetails> {
+        match self {
+            Error::Api { problem, .. } => problem.as_deref(),
+            _ => None,
+        }
+    }
+
+    pub fn code(&self) -> Option<ErrorCode> {
+        self.problem().map(|problem| problem.code)
+    }
+}
+
+impl std::fmt::Display for Error {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Error::Http(e) => write!(f, "http error: {}", e),
+            Error::Api {
+                status,
+                problem: Some(problem),
+            } => write!(
+                f,
+                "api error: {}, {}: {}",
+                status, problem.code, problem.detail
+            ),
+            Error::Api { status, .. } => write!(f, "api error: {}", status),
+            Error::NotAuthenticated => write!(f, "not authenticated"),
+        }
+    }
+}
+
+impl std::error::Error for Error {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            Error::Http(e) => Some(e),
+            _ => None,
+        }
+    }
+}
+
+impl From<reqwest::Error> for Error {
+    fn from(e: reqwest::Error) -> Self {
+        Error::Http(e)
+    }
+}
@@ -0,0 +1,162 @@
+// typed async client of the axum-web api, the request and response types are shared with the server
+use axum_web_types::app_const::HEADER_API_VERSION;
+use reqwest::{Method, RequestBuilder, Response, StatusCode};
+use std::{
+    marker::PhantomData,
+    sync::{Arc, Mutex},
+};
+
+mod auth;
+mod error;
+mod users;
+
+pub use axum_web_types::{
+    api_error::{ErrorCode, FieldError, ProblemDetails},
+    auth::{CleanupResponse, TokensResponse},
+    service::{Greeting, Heartbeat},
+    users::{v1, v2, ListUsersParams, UserContract},
+};
+pub use error::{Error, Result};
+pub use users::{Page, Tagged};
+
+use v1::V1;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Tokens {
+    pub access_token: String,
+    pub refresh_token: String,
+}
+
+impl From<TokensResponse> for Tokens {
+    fn from(response: TokensResponse) -> Self {
+        Tokens {
+            access_token: response.access_token,
+            refresh_token: response.refresh_token,
+        }
+    }
+}
+
+// a client of one api version, the clones share the tokens
+pub struct Client<C = V1> {
+    http: reqwest::Client,
+    base_url: String,
+    tokens: Arc<Mutex<Option<Tokens>>>,
+    // renew the tokens once an access token is reported as expired
+    auto_refresh: bool,
+    contract: PhantomData<fn() -> C>,
+}
+
+impl<C> Clone for Client<C> {
+    fn clone(&self) -> Self {
+        Client {
+            http: self.http.clone(),
+            base_url: self.base_url.clone(),
+            tokens: self.tokens.clone(),
+            auto_refresh: self.auto_refresh,
+            contract: PhantomData,
+        }
+    }
+}
+
+impl Client<V1> {
+    // a client of the default v1 api, e.g. Client::new("http://127.0.0.1:3000")
+    pub fn new(base_url: impl Into<String>) -> Self {
+        Client {
+            http: reqwest::Client::new(),
+            base_url: base_url.into().trim_end_matches('/').to_string(),
+            tokens: Arc::new(Mutex::new(None)),
+            auto_refresh: true,
+            contract: PhantomData,
+        }
+    }
+}
+
+impl<C: UserContract> Client<C> {
+    // the client of another api version sharing the tokens, e.g. client.with_contract::<V2>()
+    pub fn with_contract<D: UserContract>(self) -> Client<D> {
+        Client {
+            http: self.http,
+            base_url: self.base_url,
+            tokens: self.tokens,
+            auto_refresh: self.auto_refresh,
+            contract: PhantomData,
+        }
+    }
+
+    pub fn with_auto_refresh(mut self, auto_refresh: bool) -> Self {
+        self.auto_refresh = auto_refresh;
+        self
+    }
+
+    pub fn tokens(&self) -> Option<Tokens> {
+        self.tokens.lock().unwrap().clone()
+    }
+
+    pub fn set_tokens(&self, tokens: Option<Tokens>) {
+        *self.tokens.lock().unwrap() = tokens;
+    }
+
+    pub async fn root(&self) -> Result<Greeting> {
+        let request = self
+            .http
+            .get(format!("{}/", self.base_url))
+            .header(HEADER_API_VERSION, C::VERSION);
+        Ok(self.send_authorized(request).await?.json().await?)
+    }
+
+    pub async fn heartbeat(&self, id: &str) -> Result<Heartbeat> {
+        let request = self.request(Method::GET, &format!("/heartbeat/{}", id));
+        Ok(self.send(request).await?.json().await?)
+    }
+
+    // a request to a path of the api version
+    fn request(&self, method: Method, path: &str) -> RequestBuilder {
+        let url = format!("{}/{}{}", self.base_url, C::VERSION, path);
+        self.http.request(method, url)
+    }
+
+    async fn send(&self, request: RequestBuilder) -> Result<Response> {
+        check(request.send().await?).await
+    }
+
+    // sends the request with the access token, renews the tokens and retries once if it has expired
+    async fn send_authorized(&self, request: RequestBuilder) -> Result<Response> {
+        let retry = request.try_clone();
+        let access_token = self.tokens().map(|tokens| tokens.access_token);
+        let result = self.send(bearer(request, access_token)).await;
+        match (result, retry) {
+            (Err(e), Some(retry))
+                if self.auto_refresh
+                    && e.code() == Some(ErrorCode::ExpiredToken)
+                    && self.tokens().is_some() =>
+            {
+                let tokens = self.refresh().await?;
+                self.send(bearer(retry, Some(tokens.access_token))).await
+            }
+            (result, _) => result,
+        }
+    }
+
+    fn refresh_token(&self) -> Result<String> {
+        self.tokens()
+            .map(|tokens| tokens.refresh_token)
+            .ok_or(Error::NotAuthenticated)
+    }
+}
+
+fn bearer(request: RequestBuilder, token: Option<String>) -> RequestBuilder {
+    match token {
+        Some(token) => request.bearer_auth(token),
+        None => request,
+    }
+}
+
+// turns the error statuses into errors with their problem details
+async fn check(response: Response) -> Result<Response> {
+    let status = response.status();
+    if status.is_success() || status == StatusCode::NOT_MODIFIED {
+        return Ok(response);
+    }
+    let problem = response.json::<ProblemDetails>().await.ok().map(Box::new);
+    Err(Error::Api { status, problem })
+}
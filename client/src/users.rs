@@ -0,0 +1,121 @@
+use axum_web_types::{
+    app_const::{HEADER_NEXT_CURSOR, HEADER_TOTAL_COUNT},
+    users::{ListUsersParams, UserContract},
+};
+use reqwest::{header, Method, Response};
+use serde::{de::DeserializeOwned, Serialize};
+use uuid::Uuid;
+
+use super::{Client, Result};
+
+// a page of users, the cursor and the total count are taken from the headers in every version
+#[derive(Debug, Clone, PartialEq)]
+pub struct Page<T> {
+    pub body: T,
+    pub next_cursor: Option<String>,
+    pub total_count: Option<u64>,
+}
+
+// a user with its entity tag for the conditional requests
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tagged<T> {
+    pub value: T,
+    pub etag: Option<String>,
+}
+
+impl<C> Client<C>
+where
+    C: UserContract,
+    C::CreateUserRequest: Serialize,
+    C::UpdateUserRequest: Serialize,
+    C::UserResponse: DeserializeOwned,
+    C::UserListResponse: DeserializeOwned,
+{
+    pub async fn list_users(&self, params: &ListUsersParams) -> Result<Page<C::UserListResponse>> {
+        let request = self.request(Method::GET, "/users").query(params);
+        let response = self.send_authorized(request).await?;
+        let next_cursor = header(&response, HEADER_NEXT_CURSOR);
+        let total_count = header(&response, HEADER_TOTAL_COUNT).and_then(|v| v.parse().ok());
+        Ok(Page {
+            body: response.json().await?,
+            next_cursor,
+            total_count,
+        })
+    }
+
+    pub async fn get_user(&self, id: Uuid) -> Result<Tagged<C::UserResponse>> {
+        let request = self.request(Method::GET, &format!("/users/{}", id));
+        tagged(self.send_authorized(request).await?).await
+    }
+
+    pub async fn add_user(&self, user: &C::CreateUserRequest) -> Result<C::UserResponse> {
+        let request = self.request(Method::POST, "/users").json(user);
+        Ok(self.send_authorized(request).await?.json().await?)
+    }
+
+    // if_match is the entity tag of the user or *
+    pub async fn update_user(
+        &self,
+        id: Uuid,
+        user: &C::UpdateUserRequest,
+        if_match: &str,
+    ) -> Result<Tagged<C::UserResponse>> {
+        let request = self
+            .request(Method::PUT, &format!("/users/{}", id))
+            .header(header::IF_MATCH, if_match)
+            .json(user);
+        tagged(self.send_authorized(request).await?).await
+    }
+
+    // applies a json merge patch to the representation of the user in the api version
+    pub async fn patch_user(
+        &self,
+        id: Uuid,
+        patch: &serde_json::Value,
+        if_match: &str,
+    ) -> Result<Tagged<C::UserResponse>> {
+        let request = self
+            .request(Method::PATCH, &format!("/users/{}", id))
+            .header(header::IF_MATCH, if_match)
+            .header(header::CONTENT_TYPE, "application/merge-patch+json")
+            .body(patch.to_string());
+        tagged(self.send_authorized(request).await?).await
+    }
+
+    // soft deletes the user and revokes its tokens
+    pub async fn delete_user(&self, id: Uuid, if_match: &str) -> Result<()> {
+        let request = self
+            .request(Method::DELETE, &format!("/users/{}", id))
+            .header(header::IF_MATCH, if_match);
+        self.send_authorized(request).await?;
+        Ok(())
+    }
+
+    pub async fn restore_user(&self, id: Uuid) -> Result<Tagged<C::UserResponse>> {
+        let request = self.request(Method::POST, &format!("/users/{}/restore", id));
+        tagged(self.send_authorized(request).await?).await
+    }
+
+    // removes a soft deleted user permanently
+    pub async fn purge_user(&self, id: Uuid) -> Result<()> {
+        let request = self.request(Method::DELETE, &format!("/users/{}/purge", id));
+        self.send_authorized(request).await?;
+        Ok(())
+    }
+}
+
+fn header(response: &Response, name: &str) -> Option<String> {
+    response
+        .headers()
+        .get(name)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string())
+}
+
+async fn tagged<T: DeserializeOwned>(response: Response) -> Result<Tagged<T>> {
+    let etag = header(&response, header::ETAG.as_str());
+    Ok(Tagged {
+        value: response.json().await?,
+        etag,
+    })
+}
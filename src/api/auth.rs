@@ -1,31 +1,35 @@
-use axum::{extract::State, http::StatusCode, response::IntoResponse, routing::post, Json, Router};
-use serde::{Deserialize, Serialize};
-use serde_json::json;
-use uuid::Uuid;
+use axum::{extract::State, response::IntoResponse, routing::post, Json, Router};
+use utoipa::OpenApi;
+
+pub use axum_web_types::auth::{CleanupResponse, LoginUser, RevokeUser, TokensResponse};
 
 use crate::application::{
-    api_error::ApiError,
+    api_error::{ApiError, ProblemDetails},
     api_version::ApiVersion,
     redis_service,
-    repository::user_repo,
+    repository::repository_error::RepositoryError,
     security::{
         auth_error::AuthError,
         jwt_auth::{self, JwtTokens},
         jwt_claims::{AccessClaims, ClaimsMethods, RefreshClaims},
     },
     state::SharedState,
+    validation::ValidatedJson,
 };
 
-#[derive(Debug, Serialize, Deserialize)]
-struct LoginUser {
-    username: String,
-    password_hash: String,
-}
-
-#[derive(Debug, Serialize, Deserialize)]
-struct RevokeUser {
-    user_id: Uuid,
-}
+#[derive(OpenApi)]
+#[openapi(
+    paths(
+        login_handler,
+        logout_handler,
+        refresh_handler,
+        revoke_all_handler,
+        revoke_user_handler,
+        cleanup_handler
+    ),
+    tags((name = "auth", description = "Authentication and token revoking"))
+)]
+pub struct AuthApi;
 
 pub fn routes() -> Router<SharedState> {
     Router::new()
@@ -37,26 +41,59 @@ pub fn routes() -> Router<SharedState> {
         .route("/cleanup", post(cleanup_handler))
 }
 
+#[utoipa::path(
+    post,
+    path = "/{version}/auth/login",
+    tag = "auth",
+    params(
+        ("version" = String, Path, description = "The api version"),
+    ),
+    request_body = LoginUser,
+    responses(
+        (status = 200, description = "The issued tokens", body = TokensResponse),
+        (status = 401, description = "Wrong credentials", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+)]
 #[tracing::instrument(level = tracing::Level::TRACE, name = "login", skip_all, fields(username=login.username))]
 async fn login_handler(
     api_version: ApiVersion,
     State(state): State<SharedState>,
-    Json(login): Json<LoginUser>,
+    ValidatedJson(login): ValidatedJson<LoginUser>,
 ) -> Result<impl IntoResponse, ApiError> {
     tracing::trace!("api version: {}", api_version);
-    if let Ok(user) = user_repo::get_user_by_username(&login.username, &state).await {
-        if user.active && user.password_hash == login.password_hash {
-            tracing::trace!("access granted, user: {}", user.id);
-            let tokens = jwt_auth::generate_tokens(user);
-            let response = tokens_to_response(tokens);
-            return Ok(response);
+    match state.user_repo.get_by_username(&login.username).await {
+        Ok(user) => {
+            if user.active && user.password_hash == login.password_hash {
+                tracing::trace!("access granted, user: {}", user.id);
+                let tokens = jwt_auth::generate_tokens(user);
+                let response = tokens_to_response(tokens);
+                return Ok(response);
+            }
         }
+        Err(RepositoryError::NotFound) => {}
+        // storage failures must not look like wrong credentials
+        Err(e) => return Err(e.into()),
     }
 
     tracing::error!("access denied: {:#?}", login);
     Err(AuthError::WrongCredentials.into())
 }
 
+#[utoipa::path(
+    post,
+    path = "/{version}/auth/logout",
+    tag = "auth",
+    params(
+        ("version" = String, Path, description = "The api version"),
+    ),
+    responses(
+        (status = 200, description = "The refresh token is revoked"),
+        (status = 401, description = "Missing or invalid refresh token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "The refresh token could not be revoked", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn logout_handler(
     api_version: ApiVersion,
     State(state): State<SharedState>,
@@ -67,6 +104,21 @@ async fn logout_handler(
     jwt_auth::logout(refresh_claims, state).await
 }
 
+#[utoipa::path(
+    post,
+    path = "/{version}/auth/refresh",
+    tag = "auth",
+    params(
+        ("version" = String, Path, description = "The api version"),
+    ),
+    responses(
+        (status = 200, description = "The issued tokens", body = TokensResponse),
+        (status = 401, description = "Missing or invalid refresh token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "The user of the token was not found", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "The storage or the revocation store is unavailable", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn refresh_handler(
     api_version: ApiVersion,
     State(state): State<SharedState>,
@@ -78,6 +130,21 @@ async fn refresh_handler(
 }
 
 // revoke all issued tokens until now
+#[utoipa::path(
+    post,
+    path = "/{version}/auth/revoke-all",
+    tag = "auth",
+    params(
+        ("version" = String, Path, description = "The api version"),
+    ),
+    responses(
+        (status = 200, description = "The tokens are revoked"),
+        (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "The tokens could not be revoked", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn revoke_all_handler(
     api_version: ApiVersion,
     State(state): State<SharedState>,
@@ -86,17 +153,34 @@ async fn revoke_all_handler(
     tracing::trace!("api version: {}", api_version);
     access_claims.validate_role_admin()?;
     if !redis_service::revoke_global(&state).await {
-        return Err(ApiError::from(StatusCode::INTERNAL_SERVER_ERROR));
+        return Err(AuthError::RevocationFailed.into());
     }
     Ok(())
 }
 
 // revoke tokens issued to user until now
+#[utoipa::path(
+    post,
+    path = "/{version}/auth/revoke-user",
+    tag = "auth",
+    params(
+        ("version" = String, Path, description = "The api version"),
+    ),
+    request_body = RevokeUser,
+    responses(
+        (status = 200, description = "The tokens of the user are revoked"),
+        (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 422, description = "Invalid request", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "The tokens could not be revoked", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn revoke_user_handler(
     api_version: ApiVersion,
     State(state): State<SharedState>,
     access_claims: AccessClaims,
-    Json(revoke_user): Json<RevokeUser>,
+    ValidatedJson(revoke_user): ValidatedJson<RevokeUser>,
 ) -> Result<impl IntoResponse, ApiError> {
     tracing::trace!("api version: {}", api_version);
     if access_claims.sub != revoke_user.user_id.to_string() {
@@ -105,11 +189,26 @@ async fn revoke_user_handler(
     }
     tracing::trace!("revoke_user: {:?}", revoke_user);
     if !redis_service::revoke_user_tokens(&revoke_user.user_id.to_string(), &state).await {
-        return Err(ApiError::from(StatusCode::INTERNAL_SERVER_ERROR));
+        return Err(AuthError::RevocationFailed.into());
     }
     Ok(())
 }
 
+#[utoipa::path(
+    post,
+    path = "/{version}/auth/cleanup",
+    tag = "auth",
+    params(
+        ("version" = String, Path, description = "The api version"),
+    ),
+    responses(
+        (status = 200, description = "The number of the removed revoked tokens", body = CleanupResponse),
+        (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+        (status = 503, description = "The revocation store is unavailable", body = ProblemDetails, content_type = "application/problem+json"),
+    ),
+    security(("bearer_auth" = []))
+)]
 async fn cleanup_handler(
     api_version: ApiVersion,
     State(state): State<SharedState>,
@@ -119,19 +218,18 @@ async fn cleanup_handler(
     access_claims.validate_role_admin()?;
     tracing::trace!("authentication details: {:#?}", access_claims);
     let deleted = jwt_auth::cleanup_revoked_and_expired(&access_claims, &state).await?;
-    let json = json!({
-        "deleted_tokens": deleted,
-    });
-    Ok(Json(json))
+    Ok(Json(CleanupResponse {
+        deleted_tokens: deleted,
+    }))
 }
 
 fn tokens_to_response(jwt_tokens: JwtTokens) -> impl IntoResponse {
-    let json = json!({
-        "access_token": jwt_tokens.access_token,
-        "refresh_token": jwt_tokens.refresh_token,
-        "token_type": "Bearer"
-    });
+    let response = TokensResponse {
+        access_token: jwt_tokens.access_token,
+        refresh_token: jwt_tokens.refresh_token,
+        token_type: "Bearer".to_string(),
+    };
 
-    tracing::trace!("JWT: generated response {:#?}", json);
-    Json(json)
+    tracing::trace!("JWT: generated response {:#?}", response);
+    Json(response)
 }
@@ -0,0 +1,118 @@
+use axum::{response::IntoResponse, routing::get, Json, Router};
+use utoipa::{
+    openapi::{
+        path::{Operation, PathItem},
+        schema::{ObjectBuilder, Type},
+        security::{HttpAuthScheme, HttpBuilder, SecurityScheme},
+        Deprecated, OpenApi as OpenApiDocument,
+    },
+    Modify, OpenApi,
+};
+
+use super::{auth, users};
+
+use crate::application::{api_version::ApiVersion, app_const::*, state::SharedState};
+
+#[derive(OpenApi)]
+#[openapi(
+    info(
+        title = "axum-web",
+        description = "REST API of the axum-web service. The version can also be negotiated \
+            on the unversioned paths by the API-Version header or a vendor media type."
+    ),
+    modifiers(&BearerAuth)
+)]
+struct ApiDoc;
+
+// the access and refresh tokens are sent as bearer tokens
+struct BearerAuth;
+
+impl Modify for BearerAuth {
+    fn modify(&self, openapi: &mut OpenApiDocument) {
+        let components = openapi.components.get_or_insert_with(Default::default);
+        components.add_security_scheme(
+            "bearer_auth",
+            SecurityScheme::Http(
+                HttpBuilder::new()
+                    .scheme(HttpAuthScheme::Bearer)
+                    .bearer_format("JWT")
+                    .build(),
+            ),
+        );
+    }
+}
+
+pub fn routes() -> Router<SharedState> {
+    let router = Router::new()
+        .route("/openapi.json", get(openapi_handler))
+        .route("/:version/openapi.json", get(openapi_handler));
+
+    #[cfg(feature = "swagger-ui")]
+    let router = router.merge(swagger_ui::routes());
+
+    router
+}
+
+// the openapi document of the negotiated version
+async fn openapi_handler(api_version: ApiVersion) -> impl IntoResponse {
+    Json(spec(api_version))
+}
+
+// the openapi 3.1 document of an api version
+pub fn spec(version: ApiVersion) -> OpenApiDocument {
+    let mut spec = ApiDoc::openapi();
+    spec.info.version = format!("{} ({} {})", version, SERVICE_NAME, SERVICE_VERSION);
+    spec.merge(auth::AuthApi::openapi());
+    match version {
+        ApiVersion::V1 => spec.merge(users::v1::UsersApi::openapi()),
+        ApiVersion::V2 => spec.merge(users::v2::UsersApi::openapi()),
+    }
+
+    let deprecated = version.deprecation().is_some();
+    for operation in spec.paths.paths.values_mut().flat_map(operations) {
+        if deprecated {
+            operation.deprecated = Some(Deprecated::True);
+        }
+        // the version path parameter accepts only the version of the document
+        for parameter in operation.parameters.iter_mut().flatten() {
+            if parameter.name == "version" {
+                parameter.schema = Some(
+                    ObjectBuilder::new()
+                        .schema_type(Type::String)
+                        .enum_values(Some([version.as_str()]))
+                        .into(),
+                );
+            }
+        }
+    }
+    spec
+}
+
+fn operations(path_item: &mut PathItem) -> impl Iterator<Item = &mut Operation> {
+    [
+        path_item.get.as_mut(),
+        path_item.put.as_mut(),
+        path_item.post.as_mut(),
+        path_item.delete.as_mut(),
+        path_item.patch.as_mut(),
+    ]
+    .into_iter()
+    .flatten()
+}
+
+#[cfg(feature = "swagger-ui")]
+mod swagger_ui {
+    use axum::Router;
+    use utoipa_swagger_ui::{Config, SwaggerUi, Url};
+
+    use crate::application::state::SharedState;
+
+    // the page and its assets are served from the binary, it loads the documents of every version
+    pub fn routes() -> Router<SharedState> {
+        let urls = [
+            Url::new("v1", "/v1/openapi.json"),
+            Url::with_primary("v2", "/v2/openapi.json", true),
+        ];
+        SwaggerUi::new("/docs").config(Config::new(urls)).into()
+    }
+}
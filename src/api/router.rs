@@ -7,17 +7,21 @@ use axum::{
     routing::{any, get},
     Json, Router,
 };
-use serde_json::json;
-use std::collections::HashMap;
+use std::{collections::HashMap, sync::Arc};
+use tower_http::request_id::{MakeRequestUuid, PropagateRequestIdLayer, SetRequestIdLayer};
 
-use super::{auth, users};
+pub use axum_web_types::service::{Greeting, Heartbeat, Readiness};
+
+use super::{auth, openapi, users};
 
 use crate::application::{
-    api_error::ApiError,
-    api_version::{self, ApiVersion},
+    api_error::{self, ApiError, ErrorCode},
+    api_version::{self, ApiVersion, DeprecationPolicy},
     app_const::*,
     security::jwt_claims::AccessClaims,
+    shutdown,
     state::SharedState,
+    tls::ClientPrincipal,
 };
 
 pub fn routes(state: SharedState) -> Router {
@@ -27,13 +31,32 @@ pub fn routes(state: SharedState) -> Router {
         .route("/head", get(head_request_handler))
         .route("/any", any(any_request_handler))
         .route("/:version/heartbeat/:id", get(heartbeat_handler))
+        .route("/ready", get(readiness_handler))
         // nesting the authentication related routes
         .nest("/:version/auth", auth::routes())
         // nesting the user related routes
         .nest("/:version/users", users::routes())
+        // the unversioned routes negotiate the version by the request headers
+        .nest("/auth", auth::routes())
+        .nest("/users", users::routes())
+        // the openapi documents of the versions
+        .merge(openapi::routes())
         // add a fallback service for handling routes to unknown paths
         .fallback(error_404_handler)
         .with_state(state)
+        // announce the deprecated versions and routes
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(DeprecationPolicy::schedule()),
+            api_version::deprecation_middleware,
+        ))
+        // resolve the api version of the requests
+        .layer(axum::middleware::from_fn(
+            api_version::api_version_middleware,
+        ))
+        // render the errors as problem details with the request id
+        .layer(axum::middleware::from_fn(api_error::problem_middleware))
+        .layer(PropagateRequestIdLayer::x_request_id())
+        .layer(SetRequestIdLayer::x_request_id(MakeRequestUuid))
 }
 
 #[tracing::instrument(level = tracing::Level::TRACE, name = "axum", skip_all, fields(method=request.method().to_string(), uri=request.uri().to_string()))]
@@ -43,6 +66,9 @@ pub async fn logging_middleware(request: Request<Body>, next: Next) -> Response
         request.method(),
         request.uri()
     );
+    if let Some(ClientPrincipal(principal)) = request.extensions().get() {
+        tracing::trace!("client certificate principal: {}", principal);
+    }
     next.run(request).await
 }
 
@@ -52,16 +78,29 @@ async fn heartbeat_handler(
     let api_version: ApiVersion = api_version::parse_version(&version)?;
     tracing::trace!("heartbeat: api version: {}", api_version);
     tracing::trace!("heartbeat: received id: {}", id);
-    let map = HashMap::from([
-        ("service".to_string(), SERVICE_NAME.to_string()),
-        ("version".to_string(), SERVICE_VERSION.to_string()),
-        ("heartbeat-id".to_string(), id),
-    ]);
-    Ok(Json(map))
+    Ok(Json(Heartbeat {
+        service: SERVICE_NAME.to_string(),
+        version: SERVICE_VERSION.to_string(),
+        heartbeat_id: id,
+    }))
 }
 
-async fn root_handler(access_claims: AccessClaims) -> Result<impl IntoResponse, ApiError> {
+async fn readiness_handler() -> impl IntoResponse {
+    let ready = shutdown::is_ready();
+    let status = if ready {
+        StatusCode::OK
+    } else {
+        StatusCode::SERVICE_UNAVAILABLE
+    };
+    (status, Json(Readiness { ready }))
+}
+
+async fn root_handler(
+    api_version: ApiVersion,
+    access_claims: AccessClaims,
+) -> Result<impl IntoResponse, ApiError> {
     if tracing::enabled!(tracing::Level::TRACE) {
+        tracing::trace!("api version: {}", api_version);
         tracing::trace!(
             "current timestamp, chrono::Utc {}",
             chrono::Utc::now().timestamp() as usize
@@ -74,7 +113,9 @@ async fn root_handler(access_claims: AccessClaims) -> Result<impl IntoResponse,
         tracing::trace!("current timestamp, std::time {}", validation_timestamp);
         tracing::trace!("authentication details: {:#?}", access_claims);
     }
-    Ok(Json(json!({"message": "Hello from Axum-Web!"})))
+    Ok(Json(Greeting {
+        message: "Hello from Axum-Web!".to_string(),
+    }))
 }
 
 async fn head_request_handler(method: Method) -> Response {
@@ -106,5 +147,9 @@ async fn any_request_handler(
 
 async fn error_404_handler(request: Request) -> impl IntoResponse {
     tracing::error!("route not found: {:?}", request);
-    StatusCode::NOT_FOUND
+    ApiError::new(
+        StatusCode::NOT_FOUND,
+        ErrorCode::RouteNotFound,
+        format!("Route not found: {}", request.uri().path()),
+    )
 }
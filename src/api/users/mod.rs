@@ -0,0 +1,658 @@
+use axum::{
+    extract::{Path, Query, State},
+    http::{header, HeaderMap, HeaderName, HeaderValue, StatusCode},
+    response::{IntoResponse, Response},
+    routing::{delete, get, patch, post, put},
+    Json, Router,
+};
+use serde::Deserialize;
+use serde_json::Value;
+use sqlx::types::Uuid;
+use validator::Validate;
+
+use crate::{
+    application::{
+        api_error::{ApiError, ErrorCode},
+        api_version::versioned,
+        app_const::*,
+        etag, merge_patch, redis_service,
+        repository::{
+            repository_error::RepositoryError,
+            user_repo::{UserCursor, UserFilter, UserListQuery, UserPage},
+        },
+        security::{
+            auth_error::AuthError,
+            jwt_claims::{AccessClaims, ClaimsMethods},
+        },
+        state::SharedState,
+        validation::{self, ValidatedJson},
+    },
+    domain::models::user::User,
+};
+
+pub use axum_web_types::users::{CreateUserRequest, ListUsersParams};
+
+pub mod v1;
+pub mod v2;
+
+pub fn routes() -> Router<SharedState> {
+    Router::new()
+        .route("/", get(versioned(v1::list_users, v2::list_users)))
+        .route("/", post(versioned(v1::add_user, v2::add_user)))
+        .route("/:id", get(versioned(v1::get_user, v2::get_user)))
+        .route("/:id", put(versioned(v1::update_user, v2::update_user)))
+        .route("/:id", patch(versioned(v1::patch_user, v2::patch_user)))
+        .route("/:id", delete(versioned(v1::delete_user, v2::delete_user)))
+        .route(
+            "/:id/restore",
+            post(versioned(v1::restore_user, v2::restore_user)),
+        )
+        .route(
+            "/:id/purge",
+            delete(versioned(v1::purge_user, v2::purge_user)),
+        )
+}
+
+// the server side of the wire format of the users api, the bounds of the request and response types
+pub trait UserContract:
+    axum_web_types::users::UserContract<
+    CreateUserRequest: Validate + Into<User> + Send,
+    UpdateUserRequest: Validate + UpdateUser + Send,
+    UserResponse: From<User> + Send,
+    UserListResponse: From<UserPage> + Send,
+>
+{
+}
+
+// maps an update request onto the stored user
+pub trait UpdateUser {
+    // the id sent in the body, it must match the path
+    fn id(&self) -> Option<Uuid>;
+    fn apply(self, user: User) -> User;
+}
+
+#[derive(Debug, Deserialize)]
+pub struct UserPath {
+    id: Uuid,
+}
+
+impl From<CreateUserRequest> for User {
+    fn from(request: CreateUserRequest) -> Self {
+        User {
+            id: request.id,
+            username: request.username,
+            email: request.email,
+            password_hash: request.password_hash,
+            password_salt: request.password_salt,
+            active: true,
+            roles: USER_ROLE_GUEST.to_string(),
+            created_at: None,
+            updated_at: None,
+            version: 0,
+            deleted_at: None,
+        }
+    }
+}
+
+// user fields that can be changed with a merge patch
+const USER_PATCH_FIELDS: [&str; 4] = ["username", "email", "active", "roles"];
+
+impl TryFrom<ListUsersParams> for UserListQuery {
+    type Error = ApiError;
+
+    fn try_from(params: ListUsersParams) -> Result<Self, Self::Error> {
+        let limit = params.limit.unwrap_or(USERS_LIST_DEFAULT_LIMIT);
+        if limit == 0 || limit > USERS_LIST_MAX_LIMIT {
+            return Err(ApiError::new(
+                StatusCode::BAD_REQUEST,
+                ErrorCode::BadRequest,
+                format!("limit must be between 1 and {}", USERS_LIST_MAX_LIMIT),
+            ));
+        }
+
+        // the cursor is valid only for the same sorting it was issued for
+        let cursor = match params.cursor {
+            Some(cursor) => match UserCursor::decode(&cursor) {
+                Some(cursor) if cursor.sort == params.sort && cursor.order == params.order => {
+                    Some(cursor)
+                }
+                _ => {
+                    return Err(ApiError::new(
+                        StatusCode::BAD_REQUEST,
+                        ErrorCode::BadRequest,
+                        format!("Invalid cursor: {}", cursor),
+                    ))
+                }
+            },
+            None => None,
+        };
+
+        Ok(UserListQuery {
+            filter: UserFilter {
+                active: params.active,
+                role: params.role,
+                username_prefix: params.username_prefix,
+                email_prefix: params.email_prefix,
+                created_after: params.created_after,
+                created_before: params.created_before,
+            },
+            sort: params.sort,
+            order: params.order,
+            limit,
+            cursor,
+            include_total: params.include_total,
+        })
+    }
+}
+
+async fn list_users_handler<C: UserContract>(
+    access_claims: AccessClaims,
+    State(state): State<SharedState>,
+    Query(params): Query<ListUsersParams>,
+) -> Result<impl IntoResponse, ApiError> {
+    tracing::trace!("api version: {}", C::VERSION);
+    tracing::trace!("authentication details: {:#?}", access_claims);
+    tracing::trace!("params: {:?}", params);
+    access_claims.validate_role_admin()?;
+    let query = UserListQuery::try_from(params)?;
+    match state.user_repo.list(&query).await {
+        Ok(page) => {
+            // the headers are sent in every version, the body may carry them too
+            let mut headers = HeaderMap::new();
+            if let Some(cursor) = &page.next_cursor {
+                if let Ok(value) = HeaderValue::from_str(&cursor.encode()) {
+                    headers.insert(HEADER_NEXT_CURSOR, value);
+                }
+            }
+            if let Some(total_count) = page.total_count {
+                headers.insert(HEADER_TOTAL_COUNT, HeaderValue::from(total_count));
+            }
+            Ok((headers, Json(C::UserListResponse::from(page))))
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn add_user_handler<C: UserContract>(
+    access_claims: AccessClaims,
+    State(state): State<SharedState>,
+    ValidatedJson(request): ValidatedJson<C::CreateUserRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    tracing::trace!("api version: {}", C::VERSION);
+    tracing::trace!("authentication details: {:#?}", access_claims);
+    access_claims.validate_role_admin()?;
+    match state.user_repo.add(request.into()).await {
+        Ok(user) => Ok((StatusCode::CREATED, Json(C::UserResponse::from(user)))),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn get_user_handler<C: UserContract>(
+    access_claims: AccessClaims,
+    Path(UserPath { id }): Path<UserPath>,
+    headers: HeaderMap,
+    State(state): State<SharedState>,
+) -> Result<Response, ApiError> {
+    tracing::trace!("api version: {}", C::VERSION);
+    tracing::trace!("authentication details: {:#?}", access_claims);
+    tracing::trace!("id: {}", id);
+    access_claims.validate_role_admin()?;
+    match state.user_repo.get_by_id(id).await {
+        Ok(user) => {
+            if etag::if_none_match(&headers, user.version) {
+                return Ok((StatusCode::NOT_MODIFIED, etag_header(&user)).into_response());
+            }
+            Ok((etag_header(&user), Json(C::UserResponse::from(user))).into_response())
+        }
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn update_user_handler<C: UserContract>(
+    access_claims: AccessClaims,
+    Path(UserPath { id }): Path<UserPath>,
+    headers: HeaderMap,
+    State(state): State<SharedState>,
+    ValidatedJson(request): ValidatedJson<C::UpdateUserRequest>,
+) -> Result<impl IntoResponse, ApiError> {
+    tracing::trace!("api version: {}", C::VERSION);
+    tracing::trace!("authentication details: {:#?}", access_claims);
+    tracing::trace!("id: {}", id);
+    access_claims.validate_role_admin()?;
+    if let Some(body_id) = request.id().filter(|body_id| *body_id != id) {
+        return Err(ApiError::new(
+            StatusCode::BAD_REQUEST,
+            ErrorCode::BadRequest,
+            format!("User id in the body does not match the path: {}", body_id),
+        ));
+    }
+    let current = get_user_if_match(id, &headers, &state).await?;
+    let version = current.version;
+    update_user::<C>(id, request.apply(current), version, &state).await
+}
+
+async fn patch_user_handler<C: UserContract>(
+    access_claims: AccessClaims,
+    Path(UserPath { id }): Path<UserPath>,
+    headers: HeaderMap,
+    State(state): State<SharedState>,
+    Json(patch): Json<Value>,
+) -> Result<impl IntoResponse, ApiError> {
+    tracing::trace!("api version: {}", C::VERSION);
+    tracing::trace!("authentication details: {:#?}", access_claims);
+    tracing::trace!("id: {}, patch: {}", id, patch);
+    access_claims.validate_role_admin()?;
+    let current = get_user_if_match(id, &headers, &state).await?;
+    let version = current.version;
+    let user = apply_user_patch::<C>(current, &patch)?;
+    update_user::<C>(id, user, version, &state).await
+}
+
+// loads the user and checks its current version against the If-Match header
+async fn get_user_if_match(
+    id: Uuid,
+    headers: &HeaderMap,
+    state: &SharedState,
+) -> Result<User, ApiError> {
+    let if_match = etag::if_match(headers)?;
+    let user = state.user_repo.get_by_id(id).await?;
+    if !if_match.matches(user.version) {
+        return Err(etag::precondition_failed());
+    }
+    Ok(user)
+}
+
+async fn update_user<C: UserContract>(
+    id: Uuid,
+    user: User,
+    version: i64,
+    state: &SharedState,
+) -> Result<impl IntoResponse, ApiError> {
+    match state.user_repo.update(id, user, version).await {
+        Ok(user) => Ok((etag_header(&user), Json(C::UserResponse::from(user)))),
+        // the user was modified or deleted since it was loaded
+        Err(RepositoryError::NotFound) => Err(etag::precondition_failed()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+fn etag_header(user: &User) -> [(HeaderName, HeaderValue); 1] {
+    [(header::ETAG, etag::etag(user.version))]
+}
+
+// the patch is applied to the representation of the user in the api version
+fn apply_user_patch<C: UserContract>(user: User, patch: &Value) -> Result<User, ApiError> {
+    let invalid_patch = |error_message: String| {
+        ApiError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::ValidationFailed,
+            error_message,
+        )
+    };
+
+    let Some(fields) = patch.as_object() else {
+        return Err(invalid_patch(
+            "Merge patch must be a JSON object".to_string(),
+        ));
+    };
+    for (field, value) in fields {
+        if !USER_PATCH_FIELDS.contains(&field.as_str()) {
+            return Err(invalid_patch(format!("Field cannot be patched: {}", field)));
+        }
+        // none of the patchable fields is optional
+        if value.is_null() {
+            return Err(invalid_patch(format!("Field cannot be removed: {}", field)));
+        }
+    }
+
+    let mut document = serde_json::to_value(C::UserResponse::from(user.clone()))
+        .map_err(|e| invalid_patch(format!("Invalid user: {}", e)))?;
+    merge_patch::apply(&mut document, patch);
+    // the patched user must pass the same rules as a full update
+    let request: C::UpdateUserRequest = serde_json::from_value(document)
+        .map_err(|e| invalid_patch(format!("Invalid patch: {}", e)))?;
+    request.validate().map_err(validation::validation_failed)?;
+    Ok(request.apply(user))
+}
+
+async fn delete_user_handler<C: UserContract>(
+    access_claims: AccessClaims,
+    Path(UserPath { id }): Path<UserPath>,
+    headers: HeaderMap,
+    State(state): State<SharedState>,
+) -> Result<impl IntoResponse, ApiError> {
+    tracing::trace!("api version: {}", C::VERSION);
+    tracing::trace!("authentication details: {:#?}", access_claims);
+    tracing::trace!("id: {}", id);
+    access_claims.validate_role_admin()?;
+    let current = get_user_if_match(id, &headers, &state).await?;
+    // the tokens are revoked first, a deleted user must never keep live tokens
+    if !redis_service::revoke_user_tokens(&id.to_string(), &state).await {
+        return Err(AuthError::RevocationFailed.into());
+    }
+    match state.user_repo.delete(id, current.version).await {
+        Ok(true) => Ok(StatusCode::OK),
+        Ok(false) => Err(etag::precondition_failed()),
+        Err(e) => Err(e.into()),
+    }
+}
+
+async fn restore_user_handler<C: UserContract>(
+    access_claims: AccessClaims,
+    Path(UserPath { id }): Path<UserPath>,
+    State(state): State<SharedState>,
+) -> Result<impl IntoResponse, ApiError> {
+    tracing::trace!("api version: {}", C::VERSION);
+    tracing::trace!("authentication details: {:#?}", access_claims);
+    tracing::trace!("id: {}", id);
+    access_claims.validate_role_admin()?;
+    match state.user_repo.restore(id).await {
+        Ok(user) => Ok((etag_header(&user), Json(C::UserResponse::from(user)))),
+        Err(RepositoryError::NotFound) => Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            ErrorCode::NotFound,
+            format!("Deleted user not found for restoring: {}", id),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// removes a soft deleted user permanently
+async fn purge_user_handler<C: UserContract>(
+    access_claims: AccessClaims,
+    Path(UserPath { id }): Path<UserPath>,
+    State(state): State<SharedState>,
+) -> Result<impl IntoResponse, ApiError> {
+    tracing::trace!("api version: {}", C::VERSION);
+    tracing::trace!("authentication details: {:#?}", access_claims);
+    tracing::trace!("id: {}", id);
+    access_claims.validate_role_admin()?;
+    match state.user_repo.purge(id).await {
+        Ok(true) => Ok(StatusCode::OK),
+        Ok(false) => Err(ApiError::new(
+            StatusCode::NOT_FOUND,
+            ErrorCode::NotFound,
+            format!("Deleted user not found for purging: {}", id),
+        )),
+        Err(e) => Err(e.into()),
+    }
+}
+
+// the routed handlers of a contract with their openapi document, expanded in every version module,
+// the operations are documented on the handlers with the request and response types of the version
+macro_rules! user_api {
+    ($contract:ty) => {
+        use axum::{
+            extract::{Path, Query, State},
+            http::HeaderMap,
+            response::{IntoResponse, Response},
+            Json,
+        };
+        use serde_json::Value;
+
+        use super::{ListUsersParams, UserPath};
+        use crate::application::{
+            api_error::{ApiError, ProblemDetails},
+            security::jwt_claims::AccessClaims,
+            state::SharedState,
+            validation::ValidatedJson,
+        };
+
+        #[derive(utoipa::OpenApi)]
+        #[openapi(
+            paths(
+                list_users,
+                add_user,
+                get_user,
+                update_user,
+                patch_user,
+                delete_user,
+                restore_user,
+                purge_user
+            ),
+            components(schemas(UserPatchRequest)),
+            tags((name = "users", description = "User management, admins only"))
+        )]
+        pub struct UsersApi;
+
+        #[utoipa::path(
+            get,
+            path = "/{version}/users",
+            tag = "users",
+            params(
+                ("version" = String, Path, description = "The api version"),
+                ListUsersParams
+            ),
+            responses(
+                (
+                    status = 200,
+                    description = "A page of users",
+                    body = UserListResponse,
+                    headers(
+                        ("x-next-cursor" = String, description = "The cursor of the next page"),
+                        ("x-total-count" = i64, description = "The total count if requested")
+                    )
+                ),
+                (status = 400, description = "Invalid query, limit or cursor", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+            ),
+            security(("bearer_auth" = []))
+        )]
+        pub async fn list_users(
+            access_claims: AccessClaims,
+            state: State<SharedState>,
+            params: Query<ListUsersParams>,
+        ) -> Result<impl IntoResponse, ApiError> {
+            super::list_users_handler::<$contract>(access_claims, state, params).await
+        }
+
+        #[utoipa::path(
+            post,
+            path = "/{version}/users",
+            tag = "users",
+            params(("version" = String, Path, description = "The api version")),
+            request_body = CreateUserRequest,
+            responses(
+                (status = 201, description = "The created user", body = UserResponse),
+                (status = 400, description = "Malformed request", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 409, description = "The email is taken by another user", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 422, description = "Invalid request", body = ProblemDetails, content_type = "application/problem+json"),
+            ),
+            security(("bearer_auth" = []))
+        )]
+        pub async fn add_user(
+            access_claims: AccessClaims,
+            state: State<SharedState>,
+            request: ValidatedJson<CreateUserRequest>,
+        ) -> Result<impl IntoResponse, ApiError> {
+            super::add_user_handler::<$contract>(access_claims, state, request).await
+        }
+
+        #[utoipa::path(
+            get,
+            path = "/{version}/users/{id}",
+            tag = "users",
+            params(
+                ("version" = String, Path, description = "The api version"),
+                ("id" = Uuid, Path, description = "The user id"),
+                ("If-None-Match" = Option<String>, Header, description = "The ETag of a cached user")
+            ),
+            responses(
+                (status = 200, description = "The user", body = UserResponse, headers(("etag" = String))),
+                (status = 304, description = "The cached user is current"),
+                (status = 400, description = "Invalid id", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+            ),
+            security(("bearer_auth" = []))
+        )]
+        pub async fn get_user(
+            access_claims: AccessClaims,
+            path: Path<UserPath>,
+            headers: HeaderMap,
+            state: State<SharedState>,
+        ) -> Result<Response, ApiError> {
+            super::get_user_handler::<$contract>(access_claims, path, headers, state).await
+        }
+
+        #[utoipa::path(
+            put,
+            path = "/{version}/users/{id}",
+            tag = "users",
+            params(
+                ("version" = String, Path, description = "The api version"),
+                ("id" = Uuid, Path, description = "The user id"),
+                ("If-Match" = String, Header, description = "The ETag of the user or *")
+            ),
+            request_body = UpdateUserRequest,
+            responses(
+                (status = 200, description = "The updated user", body = UserResponse, headers(("etag" = String))),
+                (status = 400, description = "Invalid id, malformed request or an id not matching the path", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 409, description = "The email is taken by another user", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 412, description = "The user was modified", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 422, description = "Invalid request", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 428, description = "Missing If-Match header", body = ProblemDetails, content_type = "application/problem+json"),
+            ),
+            security(("bearer_auth" = []))
+        )]
+        pub async fn update_user(
+            access_claims: AccessClaims,
+            path: Path<UserPath>,
+            headers: HeaderMap,
+            state: State<SharedState>,
+            request: ValidatedJson<UpdateUserRequest>,
+        ) -> Result<impl IntoResponse, ApiError> {
+            super::update_user_handler::<$contract>(access_claims, path, headers, state, request)
+                .await
+        }
+
+        #[utoipa::path(
+            patch,
+            path = "/{version}/users/{id}",
+            tag = "users",
+            params(
+                ("version" = String, Path, description = "The api version"),
+                ("id" = Uuid, Path, description = "The user id"),
+                ("If-Match" = String, Header, description = "The ETag of the user or *")
+            ),
+            request_body(
+                content(
+                    (UserPatchRequest = "application/merge-patch+json"),
+                    (UserPatchRequest = "application/json")
+                ),
+                description = "A JSON merge patch of the user, the absent fields are kept"
+            ),
+            responses(
+                (status = 200, description = "The patched user", body = UserResponse, headers(("etag" = String))),
+                (status = 400, description = "Invalid id or malformed patch", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 409, description = "The email is taken by another user", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 412, description = "The user was modified", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 422, description = "Invalid patch", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 428, description = "Missing If-Match header", body = ProblemDetails, content_type = "application/problem+json"),
+            ),
+            security(("bearer_auth" = []))
+        )]
+        pub async fn patch_user(
+            access_claims: AccessClaims,
+            path: Path<UserPath>,
+            headers: HeaderMap,
+            state: State<SharedState>,
+            patch: Json<Value>,
+        ) -> Result<impl IntoResponse, ApiError> {
+            super::patch_user_handler::<$contract>(access_claims, path, headers, state, patch).await
+        }
+
+        #[utoipa::path(
+            delete,
+            path = "/{version}/users/{id}",
+            tag = "users",
+            params(
+                ("version" = String, Path, description = "The api version"),
+                ("id" = Uuid, Path, description = "The user id"),
+                ("If-Match" = String, Header, description = "The ETag of the user or *")
+            ),
+            responses(
+                (status = 200, description = "The user is soft deleted and its tokens revoked"),
+                (status = 400, description = "Invalid id", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 404, description = "User not found", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 412, description = "The user was modified", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 428, description = "Missing If-Match header", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 503, description = "The tokens could not be revoked", body = ProblemDetails, content_type = "application/problem+json"),
+            ),
+            security(("bearer_auth" = []))
+        )]
+        pub async fn delete_user(
+            access_claims: AccessClaims,
+            path: Path<UserPath>,
+            headers: HeaderMap,
+            state: State<SharedState>,
+        ) -> Result<impl IntoResponse, ApiError> {
+            super::delete_user_handler::<$contract>(access_claims, path, headers, state).await
+        }
+
+        #[utoipa::path(
+            post,
+            path = "/{version}/users/{id}/restore",
+            tag = "users",
+            params(
+                ("version" = String, Path, description = "The api version"),
+                ("id" = Uuid, Path, description = "The user id")
+            ),
+            responses(
+                (status = 200, description = "The restored user", body = UserResponse, headers(("etag" = String))),
+                (status = 400, description = "Invalid id", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 404, description = "Deleted user not found", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 409, description = "The email is taken by another user", body = ProblemDetails, content_type = "application/problem+json"),
+            ),
+            security(("bearer_auth" = []))
+        )]
+        pub async fn restore_user(
+            access_claims: AccessClaims,
+            path: Path<UserPath>,
+            state: State<SharedState>,
+        ) -> Result<impl IntoResponse, ApiError> {
+            super::restore_user_handler::<$contract>(access_claims, path, state).await
+        }
+
+        #[utoipa::path(
+            delete,
+            path = "/{version}/users/{id}/purge",
+            tag = "users",
+            params(
+                ("version" = String, Path, description = "The api version"),
+                ("id" = Uuid, Path, description = "The user id")
+            ),
+            responses(
+                (status = 200, description = "The deleted user is removed permanently"),
+                (status = 400, description = "Invalid id", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 401, description = "Missing or invalid access token", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 403, description = "Not an admin", body = ProblemDetails, content_type = "application/problem+json"),
+                (status = 404, description = "Deleted user not found", body = ProblemDetails, content_type = "application/problem+json"),
+            ),
+            security(("bearer_auth" = []))
+        )]
+        pub async fn purge_user(
+            access_claims: AccessClaims,
+            path: Path<UserPath>,
+            state: State<SharedState>,
+        ) -> Result<impl IntoResponse, ApiError> {
+            super::purge_user_handler::<$contract>(access_claims, path, state).await
+        }
+    };
+}
+
+pub(crate) use user_api;
@@ -0,0 +1,52 @@
+use uuid::Uuid;
+
+pub use axum_web_types::users::v1::{
+    UpdateUserRequest, UserListResponse, UserPatchRequest, UserResponse, V1,
+};
+
+pub use super::CreateUserRequest;
+use super::{user_api, UpdateUser, UserContract};
+use crate::{application::repository::user_repo::UserPage, domain::models::user::User};
+
+impl UserContract for V1 {}
+
+user_api!(V1);
+
+impl UpdateUser for UpdateUserRequest {
+    fn id(&self) -> Option<Uuid> {
+        Some(self.id)
+    }
+
+    fn apply(self, user: User) -> User {
+        User {
+            username: self.username,
+            email: self.email,
+            password_hash: self.password_hash.unwrap_or(user.password_hash),
+            password_salt: self.password_salt.unwrap_or(user.password_salt),
+            active: self.active,
+            roles: self.roles,
+            ..user
+        }
+    }
+}
+
+impl From<User> for UserResponse {
+    fn from(user: User) -> Self {
+        UserResponse {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            active: user.active,
+            roles: user.roles,
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            deleted_at: user.deleted_at,
+        }
+    }
+}
+
+impl From<UserPage> for UserListResponse {
+    fn from(page: UserPage) -> Self {
+        UserListResponse(page.users.into_iter().map(UserResponse::from).collect())
+    }
+}
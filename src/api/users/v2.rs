@@ -0,0 +1,61 @@
+use uuid::Uuid;
+
+pub use axum_web_types::users::v2::{
+    UpdateUserRequest, UserListResponse, UserPatchRequest, UserResponse, V2,
+};
+
+pub use super::CreateUserRequest;
+use super::{user_api, UpdateUser, UserContract};
+use crate::{application::repository::user_repo::UserPage, domain::models::user::User};
+
+impl UserContract for V2 {}
+
+user_api!(V2);
+
+impl UpdateUser for UpdateUserRequest {
+    fn id(&self) -> Option<Uuid> {
+        None
+    }
+
+    fn apply(self, user: User) -> User {
+        User {
+            username: self.username,
+            email: self.email,
+            password_hash: self.password_hash.unwrap_or(user.password_hash),
+            password_salt: self.password_salt.unwrap_or(user.password_salt),
+            active: self.active,
+            roles: self.roles.join(","),
+            ..user
+        }
+    }
+}
+
+impl From<User> for UserResponse {
+    fn from(user: User) -> Self {
+        UserResponse {
+            id: user.id,
+            username: user.username,
+            email: user.email,
+            active: user.active,
+            roles: user
+                .roles
+                .split(',')
+                .map(|role| role.trim().to_string())
+                .filter(|role| !role.is_empty())
+                .collect(),
+            created_at: user.created_at,
+            updated_at: user.updated_at,
+            deleted_at: user.deleted_at,
+        }
+    }
+}
+
+impl From<UserPage> for UserListResponse {
+    fn from(page: UserPage) -> Self {
+        UserListResponse {
+            items: page.users.into_iter().map(UserResponse::from).collect(),
+            next_cursor: page.next_cursor.map(|cursor| cursor.encode()),
+            total_count: page.total_count,
+        }
+    }
+}
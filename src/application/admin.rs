@@ -0,0 +1,199 @@
+// the operational tasks of the admin tool, working on the repositories directly
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use rand::RngCore;
+use uuid::Uuid;
+use validator::Validate;
+
+use super::{
+    app_const::*,
+    redis_service,
+    repository::repository_error::RepositoryError,
+    security::{password, roles},
+    state::SharedState,
+    validation,
+};
+use crate::domain::models::user::User;
+
+#[derive(Debug)]
+pub enum AdminError {
+    InvalidUser(String),
+    UserNotFound(String),
+    Repository(RepositoryError),
+    // the revocation store could not be updated
+    Revocation,
+}
+
+impl std::fmt::Display for AdminError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            AdminError::InvalidUser(error_message) => write!(f, "invalid user: {}", error_message),
+            AdminError::UserNotFound(user) => write!(f, "user not found: {}", user),
+            AdminError::Repository(e) => write!(f, "repository error: {}", e),
+            AdminError::Revocation => write!(f, "could not update the revoked tokens"),
+        }
+    }
+}
+
+impl std::error::Error for AdminError {}
+
+impl From<RepositoryError> for AdminError {
+    fn from(e: RepositoryError) -> Self {
+        AdminError::Repository(e)
+    }
+}
+
+// the fields of a new admin given on the command line, the password is hashed here
+#[derive(Validate)]
+struct NewAdmin {
+    #[validate(
+        length(min = 1, max = USER_USERNAME_MAX_LENGTH),
+        custom(function = "validation::not_blank")
+    )]
+    username: String,
+    #[validate(email, length(max = USER_EMAIL_MAX_LENGTH))]
+    email: String,
+    #[validate(length(min = USER_SALT_MIN_LENGTH, max = USER_SALT_MAX_LENGTH))]
+    password_salt: String,
+}
+
+// the salt of a password reset, given on the command line or generated
+#[derive(Validate)]
+struct NewPassword {
+    #[validate(length(min = USER_SALT_MIN_LENGTH, max = USER_SALT_MAX_LENGTH))]
+    password_salt: String,
+}
+
+// the new admins pass the same rules as the users created by the api,
+// the salt is generated if not given, the clients need it to log in
+pub async fn create_admin(
+    state: &SharedState,
+    username: &str,
+    email: &str,
+    password: &str,
+    password_salt: Option<&str>,
+) -> Result<User, AdminError> {
+    let admin = NewAdmin {
+        username: username.to_string(),
+        email: email.to_string(),
+        password_salt: password_salt
+            .map(str::to_string)
+            .unwrap_or_else(password::generate_salt),
+    };
+    admin
+        .validate()
+        .map_err(|e| AdminError::InvalidUser(e.to_string()))?;
+    let password_salt = admin.password_salt;
+    let user = User {
+        id: Uuid::new_v4(),
+        username: admin.username,
+        email: admin.email,
+        password_hash: password::hash_password(password, &password_salt),
+        password_salt,
+        active: true,
+        roles: USER_ROLE_ADMIN.to_string(),
+        created_at: None,
+        updated_at: None,
+        version: 0,
+        deleted_at: None,
+    };
+    Ok(state.user_repo.add(user).await?)
+}
+
+// adds the admin role to the roles of the user
+pub async fn promote(state: &SharedState, user: &str) -> Result<User, AdminError> {
+    let user = find_user(state, user).await?;
+    if roles::is_role_admin(&user.roles) {
+        return Ok(user);
+    }
+    let roles = match user.roles.trim() {
+        "" => USER_ROLE_ADMIN.to_string(),
+        roles => format!("{},{}", roles, USER_ROLE_ADMIN),
+    };
+    let (id, version) = (user.id, user.version);
+    Ok(state
+        .user_repo
+        .update(id, User { roles, ..user }, version)
+        .await?)
+}
+
+// sets a new password with a new salt, generated if not given,
+// the issued tokens of the user are revoked
+pub async fn reset_password(
+    state: &SharedState,
+    user: &str,
+    password: &str,
+    password_salt: Option<&str>,
+) -> Result<User, AdminError> {
+    let new_password = NewPassword {
+        password_salt: password_salt
+            .map(str::to_string)
+            .unwrap_or_else(password::generate_salt),
+    };
+    new_password
+        .validate()
+        .map_err(|e| AdminError::InvalidUser(e.to_string()))?;
+    let password_salt = new_password.password_salt;
+    let user = find_user(state, user).await?;
+    let password_hash = password::hash_password(password, &password_salt);
+    let (id, version) = (user.id, user.version);
+    let user = state
+        .user_repo
+        .update(
+            id,
+            User {
+                password_hash,
+                password_salt,
+                ..user
+            },
+            version,
+        )
+        .await?;
+    revoke_user_tokens(state, &user.id.to_string()).await?;
+    Ok(user)
+}
+
+pub async fn revoke_user(state: &SharedState, user: &str) -> Result<User, AdminError> {
+    let user = find_user(state, user).await?;
+    revoke_user_tokens(state, &user.id.to_string()).await?;
+    Ok(user)
+}
+
+pub async fn revoke_all(state: &SharedState) -> Result<(), AdminError> {
+    if !redis_service::revoke_global(state).await {
+        return Err(AdminError::Revocation);
+    }
+    Ok(())
+}
+
+// removes the expired tokens from the revoked list, returns their count
+pub async fn cleanup(state: &SharedState) -> Result<usize, AdminError> {
+    redis_service::cleanup_expired(state)
+        .await
+        .ok_or(AdminError::Revocation)
+}
+
+// a random secret for signing the tokens, JWT_SECRET
+pub fn generate_jwt_secret() -> String {
+    let mut secret = [0u8; 48];
+    rand::thread_rng().fill_bytes(&mut secret);
+    URL_SAFE_NO_PAD.encode(secret)
+}
+
+// the user by its id or username
+async fn find_user(state: &SharedState, user: &str) -> Result<User, AdminError> {
+    let found = match user.parse::<Uuid>() {
+        Ok(id) => state.user_repo.get_by_id(id).await,
+        Err(_) => state.user_repo.get_by_username(user).await,
+    };
+    match found {
+        Err(RepositoryError::NotFound) => Err(AdminError::UserNotFound(user.to_string())),
+        found => Ok(found?),
+    }
+}
+
+async fn revoke_user_tokens(state: &SharedState, user_id: &str) -> Result<(), AdminError> {
+    if !redis_service::revoke_user_tokens(user_id, state).await {
+        return Err(AdminError::Revocation);
+    }
+    Ok(())
+}
@@ -1,37 +1,145 @@
 use axum::{
-    http::StatusCode,
+    body::Body,
+    extract::Request,
+    http::{header, HeaderName, HeaderValue, StatusCode},
+    middleware::Next,
     response::{IntoResponse, Response},
+    Json,
 };
 
+use super::app_const::{HEADER_REQUEST_ID, PROBLEM_JSON_CONTENT_TYPE, PROBLEM_TYPE_URI_PREFIX};
+
+pub use axum_web_types::api_error::{ErrorCode, FieldError, ProblemDetails};
+
 // TODO: get rid of boilerplate handlers
-// TODO: support for structured (detailed) errors
 pub struct ApiError {
     pub status_code: StatusCode,
+    pub error_code: ErrorCode,
     pub error_message: String,
+    pub field_errors: Vec<FieldError>,
+    pub headers: Vec<(HeaderName, HeaderValue)>,
+}
+
+impl ApiError {
+    pub fn new(
+        status_code: StatusCode,
+        error_code: ErrorCode,
+        error_message: impl Into<String>,
+    ) -> Self {
+        ApiError {
+            status_code,
+            error_code,
+            error_message: error_message.into(),
+            field_errors: Vec::new(),
+            headers: Vec::new(),
+        }
+    }
+
+    pub fn with_field_errors(mut self, field_errors: Vec<FieldError>) -> Self {
+        self.field_errors = field_errors;
+        self
+    }
+
+    pub fn with_header(mut self, name: HeaderName, value: HeaderValue) -> Self {
+        self.headers.push((name, value));
+        self
+    }
 }
 
 impl std::fmt::Display for ApiError {
     fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
         write!(
             f,
-            "{{status_code: {}, error_message: {}}}",
-            self.status_code, self.error_message
+            "{{status_code: {}, error_code: {}, error_message: {}}}",
+            self.status_code, self.error_code, self.error_message
         )
     }
 }
 
+impl From<&ApiError> for ProblemDetails {
+    fn from(error: &ApiError) -> Self {
+        ProblemDetails {
+            problem_type: format!("{}{}", PROBLEM_TYPE_URI_PREFIX, error.error_code),
+            title: error
+                .status_code
+                .canonical_reason()
+                .unwrap_or_default()
+                .to_string(),
+            status: error.status_code.as_u16(),
+            detail: error.error_message.clone(),
+            instance: None,
+            code: error.error_code,
+            errors: error.field_errors.clone(),
+            request_id: None,
+        }
+    }
+}
+
 impl IntoResponse for ApiError {
     fn into_response(self) -> Response {
         tracing::error!("Error response: {}", self.to_string());
-        (self.status_code, self.error_message).into_response()
+        let problem = ProblemDetails::from(&self);
+        let mut response = (self.status_code, Json(&problem)).into_response();
+        let headers = response.headers_mut();
+        headers.extend(self.headers);
+        headers.insert(
+            header::CONTENT_TYPE,
+            HeaderValue::from_static(PROBLEM_JSON_CONTENT_TYPE),
+        );
+        response.extensions_mut().insert(problem);
+        response
     }
 }
 
 impl From<StatusCode> for ApiError {
     fn from(status_code: StatusCode) -> Self {
-        ApiError {
+        ApiError::new(
             status_code,
-            error_message: status_code.to_string(),
-        }
+            error_code_from_status(status_code),
+            status_code.to_string(),
+        )
     }
 }
+
+// the generic code for errors created from a bare status code
+fn error_code_from_status(status_code: StatusCode) -> ErrorCode {
+    match status_code {
+        StatusCode::BAD_REQUEST => ErrorCode::BadRequest,
+        StatusCode::UNAUTHORIZED => ErrorCode::Unauthorized,
+        StatusCode::NOT_FOUND => ErrorCode::NotFound,
+        StatusCode::NOT_ACCEPTABLE => ErrorCode::NotAcceptable,
+        StatusCode::CONFLICT => ErrorCode::UniqueViolation,
+        StatusCode::GONE => ErrorCode::Sunset,
+        StatusCode::PRECONDITION_FAILED => ErrorCode::PreconditionFailed,
+        StatusCode::UNPROCESSABLE_ENTITY => ErrorCode::ValidationFailed,
+        StatusCode::PRECONDITION_REQUIRED => ErrorCode::PreconditionRequired,
+        StatusCode::SERVICE_UNAVAILABLE => ErrorCode::StorageUnavailable,
+        _ => ErrorCode::InternalError,
+    }
+}
+
+// completes the problem details of the error responses with the request path and id
+pub async fn problem_middleware(request: Request<Body>, next: Next) -> Response {
+    let instance = request.uri().path().to_string();
+    let request_id = request
+        .headers()
+        .get(HEADER_REQUEST_ID)
+        .and_then(|value| value.to_str().ok())
+        .map(|value| value.to_string());
+
+    let response = next.run(request).await;
+    let Some(problem) = response.extensions().get::<ProblemDetails>().cloned() else {
+        return response;
+    };
+
+    let problem = ProblemDetails {
+        instance: Some(instance),
+        request_id,
+        ..problem
+    };
+    let (mut parts, _) = response.into_parts();
+    parts.headers.remove(header::CONTENT_LENGTH);
+    let body = serde_json::to_vec(&problem).unwrap_or_default();
+    parts.extensions.insert(problem);
+    Response::from_parts(parts, Body::from(body))
+}
@@ -1,15 +1,24 @@
 use axum::{
     async_trait,
-    extract::{FromRequestParts, Path},
-    http::{request::Parts, StatusCode},
+    extract::{FromRequestParts, MatchedPath, Path, Request, State},
+    handler::Handler,
+    http::{header, request::Parts, HeaderMap, HeaderValue, Method, StatusCode},
+    middleware::Next,
+    response::{IntoResponse, Response},
     RequestPartsExt,
 };
-use std::collections::HashMap;
+use chrono::{DateTime, Utc};
+use std::{collections::HashMap, future::Future, pin::Pin, sync::Arc};
 
-use super::api_error::ApiError;
+use super::{
+    api_error::{ApiError, ErrorCode},
+    app_const::*,
+    config,
+};
 
-#[derive(Debug, Clone, Copy)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
 pub enum ApiVersion {
+    #[default]
     V1,
     V2,
 }
@@ -25,13 +34,32 @@ impl std::str::FromStr for ApiVersion {
     }
 }
 
-impl std::fmt::Display for ApiVersion {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        let v: &str = match self {
+impl ApiVersion {
+    pub fn as_str(&self) -> &'static str {
+        match self {
             ApiVersion::V1 => "v1",
             ApiVersion::V2 => "v2",
-        };
-        write!(f, "{}", v)
+        }
+    }
+
+    // the deprecation schedule of the versions, none is deprecated yet; e.g.
+    // ApiVersion::V1 => Some(Deprecation { deprecated_at: .., sunset: Some(..), successor: Some(ApiVersion::V2), .. })
+    pub fn deprecation(&self) -> Option<Deprecation> {
+        match self {
+            ApiVersion::V1 | ApiVersion::V2 => None,
+        }
+    }
+}
+
+// the deprecated routes, a route deprecation takes precedence over its version one
+const DEPRECATED_ROUTES: &[DeprecatedRoute] = &[];
+
+// the client ids, sent by the x-client-id header, counted by name in the usage of the deprecations
+const KNOWN_CLIENTS: &[&str] = &[];
+
+impl std::fmt::Display for ApiVersion {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
     }
 }
 
@@ -46,6 +74,93 @@ pub fn parse_version(version: &str) -> Result<ApiVersion, ApiError> {
     }
 }
 
+// where the version of a request is taken from, in the configured order of precedence
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub enum ApiVersionSource {
+    // the /:version/ path segment
+    #[default]
+    Path,
+    // the API-Version header
+    Header,
+    // Accept: application/vnd.axum-web.v2+json
+    MediaType,
+}
+
+impl ApiVersionSource {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApiVersionSource::Path => "path",
+            ApiVersionSource::Header => "header",
+            ApiVersionSource::MediaType => "media_type",
+        }
+    }
+}
+
+impl std::str::FromStr for ApiVersionSource {
+    type Err = ();
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        match s {
+            "path" => Ok(ApiVersionSource::Path),
+            "header" => Ok(ApiVersionSource::Header),
+            "media_type" => Ok(ApiVersionSource::MediaType),
+            _ => Err(()),
+        }
+    }
+}
+
+// resolves the version of the request as configured
+pub async fn resolve_version(parts: &mut Parts) -> Result<ApiVersion, ApiError> {
+    let config = config::get();
+    negotiate_version(
+        parts,
+        &config.api.version_precedence,
+        config.api.version_default,
+    )
+    .await
+}
+
+// takes the version from the first present source, falls back to the default version
+pub async fn negotiate_version(
+    parts: &mut Parts,
+    precedence: &[ApiVersionSource],
+    default: ApiVersion,
+) -> Result<ApiVersion, ApiError> {
+    for source in precedence {
+        let version = match source {
+            ApiVersionSource::Path => parts
+                .extract::<Path<HashMap<String, String>>>()
+                .await
+                .ok()
+                .and_then(|params| params.get("version").cloned()),
+            ApiVersionSource::Header => parts
+                .headers
+                .get(HEADER_API_VERSION)
+                .and_then(|value| value.to_str().ok())
+                .map(|value| value.trim().to_string()),
+            ApiVersionSource::MediaType => parts
+                .headers
+                .get_all(header::ACCEPT)
+                .iter()
+                .filter_map(|value| value.to_str().ok())
+                .flat_map(|value| value.split(','))
+                .find_map(media_type_version),
+        };
+        if let Some(version) = version {
+            return parse_version(&version);
+        }
+    }
+    Ok(default)
+}
+
+// the version of a vendor media type, e.g. application/vnd.axum-web.v2+json; q=0.9
+fn media_type_version(media_range: &str) -> Option<String> {
+    let media_type = media_range.split(';').next()?.trim();
+    media_type
+        .strip_prefix(API_VERSION_MEDIA_TYPE_PREFIX)?
+        .strip_suffix("+json")
+        .map(|version| version.to_string())
+}
+
 #[async_trait]
 impl<S> FromRequestParts<S> for ApiVersion
 where
@@ -54,44 +169,248 @@ where
     type Rejection = ApiError;
 
     async fn from_request_parts(parts: &mut Parts, _state: &S) -> Result<Self, Self::Rejection> {
-        let params: Path<HashMap<String, String>> = parts
-            .extract()
-            .await
-            .map_err(|_| ApiVersionError::VersionExtractError)?;
+        // already resolved by the version middleware
+        if let Some(version) = parts.extensions.get::<ApiVersion>() {
+            return Ok(*version);
+        }
+        resolve_version(parts).await
+    }
+}
 
-        let version = params
-            .get("version")
-            .ok_or(ApiVersionError::ParameterMissing)?;
+// resolves the version once per request and echoes it in the API-Version response header
+pub async fn api_version_middleware(request: Request, next: Next) -> Response {
+    let (mut parts, body) = request.into_parts();
+    // the invalid versions are rejected by the handlers using them
+    let version = resolve_version(&mut parts).await.ok();
+    if let Some(version) = version {
+        parts.extensions.insert(version);
+    }
 
-        parse_version(version)
+    let mut response = next.run(Request::from_parts(parts, body)).await;
+    if let Some(version) = version {
+        response.headers_mut().insert(
+            HEADER_API_VERSION,
+            HeaderValue::from_static(version.as_str()),
+        );
     }
+    response
+}
+
+// a handler dispatching the requests to the handler of their api version
+#[derive(Clone)]
+pub struct Versioned<H1, H2> {
+    v1: H1,
+    v2: H2,
+}
+
+pub fn versioned<H1, H2>(v1: H1, v2: H2) -> Versioned<H1, H2> {
+    Versioned { v1, v2 }
+}
+
+impl<H1, T1, H2, T2, S> Handler<(T1, T2), S> for Versioned<H1, H2>
+where
+    H1: Handler<T1, S>,
+    H2: Handler<T2, S>,
+    T1: 'static,
+    T2: 'static,
+    S: Clone + Send + Sync + 'static,
+{
+    type Future = Pin<Box<dyn Future<Output = Response> + Send>>;
+
+    fn call(self, request: Request, state: S) -> Self::Future {
+        Box::pin(async move {
+            let (mut parts, body) = request.into_parts();
+            let api_version = match ApiVersion::from_request_parts(&mut parts, &state).await {
+                Ok(api_version) => api_version,
+                Err(e) => return e.into_response(),
+            };
+            let request = Request::from_parts(parts, body);
+            match api_version {
+                ApiVersion::V1 => self.v1.call(request, state).await,
+                ApiVersion::V2 => self.v2.call(request, state).await,
+            }
+        })
+    }
+}
+
+// the deprecation of a version or a route, announced by the
+// Deprecation (RFC 9745), Sunset (RFC 8594) and Link headers
+#[derive(Debug, Clone, PartialEq)]
+pub struct Deprecation {
+    // unix timestamps
+    pub deprecated_at: i64,
+    pub sunset: Option<i64>,
+    // documentation of the deprecation
+    pub link: Option<&'static str>,
+    pub successor: Option<ApiVersion>,
+    // answer with 410 Gone once the sunset has passed
+    pub gone_after_sunset: bool,
+}
+
+impl Deprecation {
+    fn is_sunset(&self, now: DateTime<Utc>) -> bool {
+        self.sunset.is_some_and(|sunset| now.timestamp() >= sunset)
+    }
+
+    fn headers(&self, version: Option<ApiVersion>, path: &str) -> HeaderMap {
+        let mut headers = HeaderMap::new();
+        let mut insert = |name: header::HeaderName, value: String| {
+            if let Ok(value) = HeaderValue::from_str(&value) {
+                headers.append(name, value);
+            }
+        };
+        insert(
+            header::HeaderName::from_static(HEADER_DEPRECATION),
+            format!("@{}", self.deprecated_at),
+        );
+        if let Some(sunset) = self
+            .sunset
+            .and_then(|sunset| DateTime::from_timestamp(sunset, 0))
+        {
+            insert(
+                header::HeaderName::from_static(HEADER_SUNSET),
+                sunset.format("%a, %d %b %Y %H:%M:%S GMT").to_string(),
+            );
+        }
+        if let Some(link) = self.link {
+            insert(header::LINK, format!("<{}>; rel=\"deprecation\"", link));
+        }
+        // the same path in the successor version
+        if let (Some(version), Some(successor)) = (version, self.successor) {
+            if let Some(rest) = path.strip_prefix(&format!("/{}", version)) {
+                insert(
+                    header::LINK,
+                    format!("</{}{}>; rel=\"successor-version\"", successor, rest),
+                );
+            }
+        }
+        headers
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct DeprecatedRoute {
+    // any method if not set
+    pub method: Option<Method>,
+    // the route as registered in the router, e.g. /:version/users/:id
+    pub path: &'static str,
+    pub deprecation: Deprecation,
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct DeprecationPolicy {
+    pub versions: Vec<(ApiVersion, Deprecation)>,
+    pub routes: Vec<DeprecatedRoute>,
+    // the other clients are counted as `other`, keeping the metric labels bounded
+    pub clients: Vec<&'static str>,
+}
+
+impl DeprecationPolicy {
+    // the policy of the service as configured above
+    pub fn schedule() -> Self {
+        DeprecationPolicy {
+            versions: [ApiVersion::V1, ApiVersion::V2]
+                .into_iter()
+                .filter_map(|version| version.deprecation().map(|d| (version, d)))
+                .collect(),
+            routes: DEPRECATED_ROUTES.to_vec(),
+            clients: KNOWN_CLIENTS.to_vec(),
+        }
+    }
+
+    fn find(
+        &self,
+        method: &Method,
+        route: Option<&str>,
+        version: Option<ApiVersion>,
+    ) -> Option<&Deprecation> {
+        let by_route = route.and_then(|route| {
+            self.routes.iter().find(|deprecated| {
+                deprecated.path == route && deprecated.method.as_ref().is_none_or(|m| m == method)
+            })
+        });
+        by_route
+            .map(|deprecated| &deprecated.deprecation)
+            .or_else(|| {
+                version.and_then(|version| {
+                    self.versions
+                        .iter()
+                        .find(|(deprecated, _)| *deprecated == version)
+                        .map(|(_, deprecation)| deprecation)
+                })
+            })
+    }
+}
+
+// announces the deprecations, counts their usage per client and rejects the calls after the sunset
+pub async fn deprecation_middleware(
+    State(policy): State<Arc<DeprecationPolicy>>,
+    request: Request,
+    next: Next,
+) -> Response {
+    let path = request.uri().path().to_string();
+    let route = request
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|matched| matched.as_str().to_string());
+    // the negotiated version if resolved, otherwise the path segment
+    let version = request
+        .extensions()
+        .get::<ApiVersion>()
+        .copied()
+        .or_else(|| {
+            path.trim_start_matches('/')
+                .split('/')
+                .next()
+                .and_then(|segment| segment.parse::<ApiVersion>().ok())
+        });
+    let Some(deprecation) = policy.find(request.method(), route.as_deref(), version) else {
+        return next.run(request).await;
+    };
+
+    let client = request
+        .headers()
+        .get(HEADER_CLIENT_ID)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|client| policy.clients.iter().find(|known| **known == client))
+        .copied()
+        .unwrap_or("other");
+    metrics::counter!(
+        METRIC_DEPRECATED_REQUESTS,
+        "version" => version.map(|v| v.to_string()).unwrap_or_default(),
+        "route" => route.unwrap_or_else(|| path.clone()),
+        "client" => client,
+    )
+    .increment(1);
+
+    let headers = deprecation.headers(version, &path);
+    let mut response = if deprecation.gone_after_sunset && deprecation.is_sunset(Utc::now()) {
+        ApiError::new(
+            StatusCode::GONE,
+            ErrorCode::Sunset,
+            format!("The endpoint has been sunset: {}", path),
+        )
+        .into_response()
+    } else {
+        next.run(request).await
+    };
+    response.headers_mut().extend(headers);
+    response
 }
 
 #[derive(Debug)]
 pub enum ApiVersionError {
     InvalidApiVersion(String),
-    ParameterMissing,
-    VersionExtractError,
 }
 
 impl From<ApiVersionError> for ApiError {
     fn from(err: ApiVersionError) -> Self {
-        let (status_code, error_message) = match err {
-            ApiVersionError::InvalidApiVersion(error_message) => {
-                (StatusCode::NOT_ACCEPTABLE, error_message)
-            }
-            ApiVersionError::ParameterMissing => (
+        match err {
+            ApiVersionError::InvalidApiVersion(error_message) => ApiError::new(
                 StatusCode::NOT_ACCEPTABLE,
-                "parameter is missing: version".to_owned(),
+                ErrorCode::InvalidApiVersion,
+                error_message,
             ),
-            ApiVersionError::VersionExtractError => (
-                StatusCode::BAD_REQUEST,
-                "Could not extract api version".to_owned(),
-            ),
-        };
-        ApiError {
-            status_code,
-            error_message,
         }
     }
 }
@@ -1,76 +1,187 @@
 use crate::{
     api::router,
-    application::{config, state::AppState},
-    infrastructure::{postgres, redis},
+    application::{
+        config::{self, Config, DatabaseBackend, RevocationStore, ServiceConfig},
+        cors,
+        listener::{self, Listener},
+        repository::{revocation_repo::RevocationRepository, user_repo::UserRepository},
+        shutdown::{self, Shutdown},
+        state::AppState,
+        tls::{self, TlsServer},
+    },
+    infrastructure::{
+        memory::InMemoryRevocationRepository,
+        postgres::{self, PostgresUserRepository},
+        redis::{self, RedisRevocationRepository},
+    },
 };
 use std::sync::Arc;
-use tokio::{
-    signal,
-    sync::{oneshot, Mutex},
-};
-use tower_http::cors::{Any, CorsLayer};
+use tokio::{signal, sync::oneshot};
+
+// the options of the serve command
+#[derive(Debug, Clone, Default)]
+pub struct ServeOptions {
+    // the migrations are left to a separate `migrate up` step,
+    // e.g. when several replicas start at once against a shared database
+    pub skip_migrations: bool,
+}
 
 pub async fn start_server(api_ready: oneshot::Sender<()>) {
+    serve(ServeOptions::default(), api_ready).await
+}
+
+pub async fn serve(options: ServeOptions, api_ready: oneshot::Sender<()>) {
     // load configuration
-    config::load();
+    config::load().await;
     let config = config::get();
 
-    // connect to redis
-    let redis = redis::open(config).await;
-
-    // connect to postgres
-    let pgpool = postgres::pgpool(config).await;
-
-    // run migrations
-    sqlx::migrate!("src/infrastructure/postgres/migrations")
-        .run(&pgpool)
-        .await
-        .unwrap();
-
-    // build a CORS layer
-    // see https://docs.rs/tower-http/latest/tower_http/cors/index.html
-    // for more details
-    let cors_layer = CorsLayer::new().allow_origin(Any);
-    // let cors_header_value = config.service_http_addr().parse::<HeaderValue>().unwrap();
-    // let cors_layer = CorsLayer::new()
-    //      .allow_origin(cors_header_value)
-    //      .allow_methods([
-    //          Method::HEAD,
-    //          Method::GET,
-    //          Method::POST,
-    //          Method::PATCH,
-    //          Method::DELETE,
-    //      ])
-    //      .allow_credentials(true)
-    //      .allow_headers([AUTHORIZATION, ACCEPT, CONTENT_TYPE]);
-
-    // get the listening address
-    let addr = config.service_socket_addr();
+    // connect to the revoked tokens storage
+    let revocation_repo = revocation_repo(&config).await;
+
+    // connect to the database and run migrations
+    if options.skip_migrations {
+        tracing::info!("database migrations skipped");
+    }
+    let user_repo = user_repo(&config, !options.skip_migrations).await;
 
     // build the state
     let shared_state = Arc::new(AppState {
-        pgpool,
-        redis: Mutex::new(redis),
+        user_repo,
+        revocation_repo,
     });
 
     // build the app
-    let app = router::routes(shared_state)
-        .layer(cors_layer)
+    let app = router::routes(shared_state.clone())
+        .layer(axum::middleware::from_fn(cors::cors_middleware))
         .layer(axum::middleware::from_fn(router::logging_middleware));
 
-    // build the listener
-    let listener = tokio::net::TcpListener::bind(&addr).await.unwrap();
-    tracing::info!("listening on {}", addr);
+    // load the certificates of https
+    let tls_server = config.tls.enabled.then(|| {
+        TlsServer::load(&config.tls).unwrap_or_else(|e| {
+            tracing::error!("{}", e);
+            panic!("{e}");
+        })
+    });
+
+    // bind the listeners
+    #[cfg(unix)]
+    let activated = !listener::listen_fds(|key| std::env::var(key).ok()).is_empty();
+    #[cfg(not(unix))]
+    let activated = false;
+    let listeners = listener::bind(&config.service).await.unwrap_or_else(|e| {
+        tracing::error!("could not listen: {}", e);
+        panic!("{e}");
+    });
 
+    // every listener stops accepting once the shutdown drains the requests
+    let shutdown = Shutdown::new();
+
+    let mut servers = Vec::new();
+    for listener in listeners {
+        // the unix sockets are local, TLS is terminated on the tcp listeners
+        let tls = match listener {
+            Listener::Tcp(_) => tls_server.clone(),
+            #[cfg(unix)]
+            Listener::Unix(_) => None,
+        };
+        tracing::info!("listening on {}", listener.url(tls.is_some()));
+        servers.push(tokio::spawn(listener::serve(
+            listener,
+            app.clone(),
+            tls,
+            shutdown.stopped(),
+            shutdown.deadline(),
+        )));
+    }
+
+    // redirect the plain http requests to https
+    if let Some(port) = config.tls.redirect_port {
+        let redirect_addr = ServiceConfig {
+            port,
+            ..config.service.clone()
+        }
+        .socket_addr();
+        let redirect_listener = tokio::net::TcpListener::bind(&redirect_addr)
+            .await
+            .unwrap_or_else(|e| {
+                tracing::error!("could not listen: {}: {}", redirect_addr, e);
+                panic!("{}: {}", redirect_addr, e);
+            });
+        tracing::info!("redirecting http://{} to https", redirect_addr);
+        servers.push(tokio::spawn(listener::serve(
+            Listener::Tcp(redirect_listener),
+            tls::redirect_routes(config.service.port),
+            None,
+            shutdown.stopped(),
+            shutdown.deadline(),
+        )));
+    }
+
+    if let Some(tls_server) = tls_server {
+        shutdown::spawn_job(
+            "TLS certificates watch",
+            tls::watch(tls_server, config.tls.clone()),
+        );
+    }
+
+    shutdown::set_ready(true);
     api_ready.send(()).expect("Couild not send a ready signal");
 
     // start the service
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal())
-        .await
-        .unwrap();
+    shutdown_signal().await;
+    let summary = shutdown
+        .run(&config::get().shutdown, servers, &shared_state)
+        .await;
+
+    // the socket file is removed, the sockets of systemd are kept by systemd
+    if let Some(path) = config.service.unix_socket.as_ref().filter(|_| !activated) {
+        let _ = std::fs::remove_file(path);
+    }
+
+    tracing::info!("{}", summary);
+}
+
+pub async fn revocation_repo(config: &Config) -> Arc<dyn RevocationRepository> {
+    match config.jwt.revocation_store {
+        RevocationStore::Redis => {
+            let redis = redis::open(config).await;
+            Arc::new(RedisRevocationRepository::new(redis))
+        }
+        RevocationStore::Memory => {
+            tracing::info!("Using in-memory store for revoked tokens");
+            Arc::new(InMemoryRevocationRepository::new())
+        }
+    }
+}
+
+// connects to the database, applies the pending migrations if requested
+pub async fn user_repo(config: &Config, migrate: bool) -> Arc<dyn UserRepository> {
+    match config.database_backend() {
+        DatabaseBackend::Postgres => {
+            let pgpool = postgres::pgpool(config).await;
+            if migrate {
+                postgres::MIGRATOR.run(&pgpool).await.unwrap();
+            }
+            Arc::new(PostgresUserRepository::new(pgpool))
+        }
+        #[cfg(feature = "sqlite")]
+        DatabaseBackend::Sqlite => {
+            use crate::infrastructure::sqlite::{self, SqliteUserRepository};
+            let pool = sqlite::sqlite_pool(config).await;
+            if migrate {
+                sqlite::MIGRATOR.run(&pool).await.unwrap();
+            }
+            Arc::new(SqliteUserRepository::new(pool))
+        }
+        #[cfg(not(feature = "sqlite"))]
+        DatabaseBackend::Sqlite => sqlite_disabled(),
+    }
+}
 
-    tracing::info!("server shutdown successfully.");
+#[cfg(not(feature = "sqlite"))]
+fn sqlite_disabled() -> ! {
+    tracing::error!("SQLite support is not enabled, build with the `sqlite` feature");
+    std::process::exit(1);
 }
 
 async fn shutdown_signal() {
@@ -1,9 +1,47 @@
 pub const SERVICE_NAME: &str = "axum-web";
 pub const SERVICE_VERSION: &str = "1.0.0";
 
-// user roles
-pub const USER_ROLE_ADMIN: &str = "admin";
-pub const USER_ROLE_GUEST: &str = "guest";
+// user roles and input validation, shared with the client
+pub use axum_web_types::app_const::{
+    USER_EMAIL_MAX_LENGTH, USER_ROLE_ADMIN, USER_ROLE_GUEST, USER_SALT_MAX_LENGTH,
+    USER_SALT_MIN_LENGTH, USER_USERNAME_MAX_LENGTH,
+};
+
+// users list pagination
+pub const USERS_LIST_DEFAULT_LIMIT: u32 = 100;
+pub const USERS_LIST_MAX_LIMIT: u32 = 1000;
+pub use axum_web_types::app_const::{HEADER_NEXT_CURSOR, HEADER_TOTAL_COUNT};
+
+// error responses
+pub const PROBLEM_JSON_CONTENT_TYPE: &str = "application/problem+json";
+pub const PROBLEM_TYPE_URI_PREFIX: &str = "urn:axum-web:problem:";
+pub const HEADER_REQUEST_ID: &str = "x-request-id";
+pub const REPOSITORY_RETRY_AFTER_SECONDS: u64 = 5;
+
+// api versioning
+pub use axum_web_types::app_const::HEADER_API_VERSION;
+pub const API_VERSION_MEDIA_TYPE_PREFIX: &str = "application/vnd.axum-web.";
+
+// api deprecation
+pub const HEADER_DEPRECATION: &str = "deprecation";
+pub const HEADER_SUNSET: &str = "sunset";
+pub const HEADER_CLIENT_ID: &str = "x-client-id";
+pub const METRIC_DEPRECATED_REQUESTS: &str = "api_deprecated_requests_total";
+
+// configuration reload
+pub const CONFIG_WATCH_INTERVAL_SECONDS: u64 = 2;
+pub const METRIC_CONFIG_RELOADS: &str = "config_reloads_total";
+
+// tls
+pub const TLS_HANDSHAKE_TIMEOUT_SECONDS: u64 = 10;
+pub const METRIC_TLS_RELOADS: &str = "tls_certificate_reloads_total";
+
+// graceful shutdown
+pub const SHUTDOWN_CLOSE_TIMEOUT_SECONDS: u64 = 5;
+
+// secret providers
+pub const HEADER_VAULT_TOKEN: &str = "x-vault-token";
+pub const SECRETS_REQUEST_TIMEOUT_SECONDS: u64 = 5;
 
 // JWT related constants
 pub const JWT_REDIS_REVOKE_GLOBAL_BEFORE_KEY: &str = "jwt.revoke.global.before";
@@ -0,0 +1,95 @@
+// the connectivity checks of the check-config command
+use std::{future::Future, time::Duration};
+
+use super::{
+    config::{Config, RevocationStore},
+    tls::TlsServer,
+};
+use crate::infrastructure::{database::DatabasePool, redis as redis_connection};
+
+// how long a service may take to answer a check
+const CHECK_TIMEOUT: Duration = Duration::from_secs(5);
+
+#[derive(Debug)]
+pub struct Check {
+    pub name: &'static str,
+    // a short description of the checked service or of the failure
+    pub result: Result<String, String>,
+}
+
+pub async fn check_services(config: &Config) -> Vec<Check> {
+    let mut checks = vec![
+        Check {
+            name: "database",
+            result: timeout(check_database(config)).await,
+        },
+        Check {
+            name: "revoked tokens store",
+            result: timeout(check_revocation_store(config)).await,
+        },
+    ];
+    if config.tls.enabled {
+        checks.push(Check {
+            name: "tls certificates",
+            result: check_tls(config),
+        });
+    }
+    checks
+}
+
+fn check_tls(config: &Config) -> Result<String, String> {
+    TlsServer::load(&config.tls)
+        .map(|_| {
+            format!(
+                "{}, client certificates {}",
+                config.tls.cert_path.as_deref().unwrap_or_default(),
+                config.tls.client_auth
+            )
+        })
+        .map_err(|e| e.to_string())
+}
+
+async fn check_database(config: &Config) -> Result<String, String> {
+    let url = config.database_url_masked();
+    let pool = DatabasePool::connect(config)
+        .await
+        .map_err(|e| format!("{}: {}", url, e))?;
+    pool.ping().await.map_err(|e| format!("{}: {}", url, e))?;
+    let migrations = pool
+        .migration_status()
+        .await
+        .map_err(|e| format!("{}: {}", url, e))?;
+    let pending = migrations.iter().filter(|m| !m.applied).count();
+    Ok(format!(
+        "{}, {} applied, {} pending migrations",
+        url,
+        migrations.len() - pending,
+        pending
+    ))
+}
+
+async fn check_revocation_store(config: &Config) -> Result<String, String> {
+    match config.jwt.revocation_store {
+        RevocationStore::Memory => Ok("in-memory".to_string()),
+        RevocationStore::Redis => {
+            let description = format!(
+                "redis ({}) {}:{}",
+                config.redis.mode, config.redis.host, config.redis.port
+            );
+            let mut redis = redis_connection::connect(config)
+                .await
+                .map_err(|e| format!("{}: {}", description, e))?;
+            let _: String = redis::cmd("PING")
+                .query_async(&mut redis)
+                .await
+                .map_err(|e| format!("{}: {}", description, e))?;
+            Ok(description)
+        }
+    }
+}
+
+async fn timeout(check: impl Future<Output = Result<String, String>>) -> Result<String, String> {
+    tokio::time::timeout(CHECK_TIMEOUT, check)
+        .await
+        .unwrap_or_else(|_| Err(format!("no answer in {} seconds", CHECK_TIMEOUT.as_secs())))
+}
@@ -0,0 +1,846 @@
+// the layered configuration: the defaults, a TOML or YAML file, the environment and the overrides
+use axum::http::{HeaderName, HeaderValue, Method};
+use std::{
+    collections::{BTreeMap, HashMap},
+    path::Path,
+    str::FromStr,
+};
+
+use super::*;
+
+// a setting by its key in the configuration file, its environment variable and its default
+struct Setting {
+    key: &'static str,
+    env: &'static str,
+    default: Option<&'static str>,
+    // read from the file of the `{env}_FILE` variable too, and from the secret provider
+    secret: bool,
+}
+
+const fn setting(key: &'static str, env: &'static str, default: Option<&'static str>) -> Setting {
+    Setting {
+        key,
+        env,
+        default,
+        secret: false,
+    }
+}
+
+const fn secret(key: &'static str, env: &'static str, default: Option<&'static str>) -> Setting {
+    Setting {
+        key,
+        env,
+        default,
+        secret: true,
+    }
+}
+
+const SETTINGS: &[Setting] = &[
+    setting("service.host", "SERVICE_HOST", Some("127.0.0.1")),
+    setting("service.port", "SERVICE_PORT", Some("3000")),
+    setting("service.tcp", "SERVICE_TCP", Some("true")),
+    setting("service.unix_socket", "SERVICE_UNIX_SOCKET", None),
+    setting(
+        "service.unix_socket_mode",
+        "SERVICE_UNIX_SOCKET_MODE",
+        Some("660"),
+    ),
+    setting("tls.enabled", "TLS_ENABLED", Some("false")),
+    setting("tls.cert_path", "TLS_CERT_PATH", None),
+    setting("tls.key_path", "TLS_KEY_PATH", None),
+    setting("tls.client_ca_path", "TLS_CLIENT_CA_PATH", None),
+    setting("tls.client_auth", "TLS_CLIENT_AUTH", Some("none")),
+    setting("tls.client_principals", "TLS_CLIENT_PRINCIPALS", Some("")),
+    setting("tls.redirect_port", "TLS_REDIRECT_PORT", None),
+    setting(
+        "shutdown.pre_stop_delay_seconds",
+        "SHUTDOWN_PRE_STOP_DELAY_SECONDS",
+        Some("0"),
+    ),
+    setting(
+        "shutdown.drain_timeout_seconds",
+        "SHUTDOWN_DRAIN_TIMEOUT_SECONDS",
+        Some("30"),
+    ),
+    setting("api.version_default", "API_VERSION_DEFAULT", Some("v1")),
+    setting(
+        "api.version_precedence",
+        "API_VERSION_PRECEDENCE",
+        Some("path,header,media_type"),
+    ),
+    secret("database.url", "DATABASE_URL", None),
+    setting("postgres.user", "POSTGRES_USER", Some("postgres")),
+    secret("postgres.password", "POSTGRES_PASSWORD", Some("")),
+    setting("postgres.host", "POSTGRES_HOST", Some("127.0.0.1")),
+    setting("postgres.port", "POSTGRES_PORT", Some("5432")),
+    setting("postgres.db", "POSTGRES_DB", Some("axum_web")),
+    setting(
+        "postgres.connection_pool",
+        "POSTGRES_CONNECTION_POOL",
+        Some("5"),
+    ),
+    setting("redis.host", "REDIS_HOST", Some("127.0.0.1")),
+    setting("redis.port", "REDIS_PORT", Some("6379")),
+    setting("redis.username", "REDIS_USERNAME", None),
+    secret("redis.password", "REDIS_PASSWORD", None),
+    setting("redis.tls", "REDIS_TLS", Some("false")),
+    setting("redis.db", "REDIS_DB", Some("0")),
+    setting("redis.mode", "REDIS_MODE", Some("standalone")),
+    setting("redis.nodes", "REDIS_NODES", Some("")),
+    setting(
+        "redis.sentinel_master",
+        "REDIS_SENTINEL_MASTER",
+        Some("mymaster"),
+    ),
+    secret("redis.sentinel_password", "REDIS_SENTINEL_PASSWORD", None),
+    secret("jwt.secret", "JWT_SECRET", None),
+    setting(
+        "jwt.expire_access_token_seconds",
+        "JWT_EXPIRE_ACCESS_TOKEN_SECONDS",
+        Some("3600"),
+    ),
+    setting(
+        "jwt.expire_refresh_token_seconds",
+        "JWT_EXPIRE_REFRESH_TOKEN_SECONDS",
+        Some("7776000"),
+    ),
+    setting(
+        "jwt.validation_leeway_seconds",
+        "JWT_VALIDATION_LEEWAY_SECONDS",
+        Some("60"),
+    ),
+    setting(
+        "jwt.enable_revoked_tokens",
+        "JWT_ENABLE_REVOKED_TOKENS",
+        Some("true"),
+    ),
+    setting("jwt.revocation_store", "REVOCATION_STORE", Some("redis")),
+    setting("cors.allowed_origins", "CORS_ALLOWED_ORIGINS", Some("*")),
+    setting(
+        "cors.allowed_methods",
+        "CORS_ALLOWED_METHODS",
+        Some("GET,POST,PUT,PATCH,DELETE"),
+    ),
+    setting(
+        "cors.allowed_headers",
+        "CORS_ALLOWED_HEADERS",
+        Some("authorization,content-type,if-match,if-none-match,api-version,x-client-id,x-request-id"),
+    ),
+    setting(
+        "cors.exposed_headers",
+        "CORS_EXPOSED_HEADERS",
+        Some("etag,location,link,deprecation,sunset,x-request-id,x-next-cursor,x-total-count"),
+    ),
+    setting("cors.allow_credentials", "CORS_ALLOW_CREDENTIALS", Some("false")),
+    setting("cors.max_age_seconds", "CORS_MAX_AGE_SECONDS", Some("600")),
+    setting("cors.auth.allowed_origins", "CORS_AUTH_ALLOWED_ORIGINS", None),
+    setting("cors.auth.allowed_methods", "CORS_AUTH_ALLOWED_METHODS", None),
+    setting("cors.auth.allowed_headers", "CORS_AUTH_ALLOWED_HEADERS", None),
+    setting("cors.auth.exposed_headers", "CORS_AUTH_EXPOSED_HEADERS", None),
+    setting("cors.auth.allow_credentials", "CORS_AUTH_ALLOW_CREDENTIALS", None),
+    setting("cors.auth.max_age_seconds", "CORS_AUTH_MAX_AGE_SECONDS", None),
+    setting("cors.users.allowed_origins", "CORS_USERS_ALLOWED_ORIGINS", None),
+    setting("cors.users.allowed_methods", "CORS_USERS_ALLOWED_METHODS", None),
+    setting("cors.users.allowed_headers", "CORS_USERS_ALLOWED_HEADERS", None),
+    setting("cors.users.exposed_headers", "CORS_USERS_EXPOSED_HEADERS", None),
+    setting("cors.users.allow_credentials", "CORS_USERS_ALLOW_CREDENTIALS", None),
+    setting("cors.users.max_age_seconds", "CORS_USERS_MAX_AGE_SECONDS", None),
+    setting("logging.filter", "LOGGING_FILTER", Some("axum_web=trace")),
+    setting("logging.format", "LOGGING_FORMAT", Some("compact")),
+    setting("secrets.provider", "SECRETS_PROVIDER", Some("none")),
+    setting(
+        "secrets.vault_addr",
+        "VAULT_ADDR",
+        Some("http://127.0.0.1:8200"),
+    ),
+    secret("secrets.vault_token", "VAULT_TOKEN", None),
+    setting(
+        "secrets.vault_path",
+        "VAULT_SECRET_PATH",
+        Some("secret/data/axum-web"),
+    ),
+    setting(
+        "secrets.refresh_seconds",
+        "SECRETS_REFRESH_SECONDS",
+        Some("0"),
+    ),
+];
+
+// the signing secret is expected to be a random string of this length at least
+const JWT_SECRET_MIN_LENGTH: usize = 32;
+
+// the layers are applied in the order of the calls, a later layer overrides the earlier ones
+#[derive(Debug, Clone)]
+pub struct ConfigBuilder {
+    values: BTreeMap<&'static str, String>,
+    errors: Vec<String>,
+}
+
+impl Default for ConfigBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl ConfigBuilder {
+    // starts with the defaults
+    pub fn new() -> Self {
+        let values = SETTINGS
+            .iter()
+            .filter_map(|setting| Some((setting.key, setting.default?.to_string())))
+            .collect();
+        Self {
+            values,
+            errors: Vec::new(),
+        }
+    }
+
+    // a TOML or YAML file, by its extension
+    pub fn file(mut self, path: impl AsRef<Path>) -> Self {
+        let path = path.as_ref();
+        match read_file(path) {
+            Ok(values) => {
+                for (key, value) in values {
+                    match find(&key) {
+                        Some(setting) => {
+                            self.values.insert(setting.key, value);
+                        }
+                        None => {
+                            self.errors
+                                .push(format!("{}: unknown setting {}", path.display(), key))
+                        }
+                    }
+                }
+            }
+            Err(e) => self.errors.push(format!("{}: {}", path.display(), e)),
+        }
+        self
+    }
+
+    pub fn env(self) -> Self {
+        self.vars(|name| std::env::var(name).ok())
+    }
+
+    // the settings by the names of their environment variables,
+    // a secret is read from the file named by its `{env}_FILE` variable, e.g. a mounted docker secret
+    pub fn vars(mut self, lookup: impl Fn(&str) -> Option<String>) -> Self {
+        for setting in SETTINGS {
+            if let Some(value) = lookup(setting.env) {
+                self.values.insert(setting.key, value);
+            }
+            if !setting.secret {
+                continue;
+            }
+            let file_env = format!("{}_FILE", setting.env);
+            if let Some(file) = lookup(&file_env) {
+                match std::fs::read_to_string(&file) {
+                    Ok(value) => {
+                        self.values
+                            .insert(setting.key, value.trim_end().to_string());
+                    }
+                    Err(e) => self.errors.push(format!("{}: {}: {}", file_env, file, e)),
+                }
+            }
+        }
+        self
+    }
+
+    // the secrets of a secret provider by the names of their environment variables,
+    // the other values of the provider are ignored
+    pub fn provided(mut self, secrets: &HashMap<String, String>) -> Self {
+        for setting in SETTINGS.iter().filter(|setting| setting.secret) {
+            if let Some(value) = secrets.get(setting.env) {
+                self.values.insert(setting.key, value.clone());
+            }
+        }
+        self
+    }
+
+    // the secret provider settings, read before the secrets the provider completes
+    pub fn secrets_config(&self) -> Result<SecretsConfig, ConfigError> {
+        let mut reader = Reader {
+            values: &self.values,
+            errors: Vec::new(),
+        };
+        let secrets = read_secrets(&mut reader);
+        reader.errors.extend(validate_secrets(&secrets));
+        if reader.errors.is_empty() {
+            Ok(secrets)
+        } else {
+            Err(ConfigError {
+                errors: reader.errors,
+            })
+        }
+    }
+
+    // a setting by its key, e.g. service.port
+    pub fn set(mut self, key: &str, value: impl Into<String>) -> Self {
+        match find(key) {
+            Some(setting) => {
+                self.values.insert(setting.key, value.into());
+            }
+            None => self.errors.push(format!("unknown setting {}", key)),
+        }
+        self
+    }
+
+    // a setting in the `key=value` format of the command line
+    pub fn set_arg(mut self, arg: &str) -> Self {
+        match arg.split_once('=') {
+            Some((key, value)) => self.set(key.trim(), value.trim()),
+            None => {
+                self.errors
+                    .push(format!("{}: expected an override as key=value", arg));
+                self
+            }
+        }
+    }
+
+    pub fn build(self) -> Result<Config, ConfigError> {
+        let mut reader = Reader {
+            values: &self.values,
+            errors: self.errors,
+        };
+
+        let jwt_secret = reader.string("jwt.secret");
+        let config = Config {
+            service: ServiceConfig {
+                host: reader.string("service.host"),
+                port: reader.parse("service.port"),
+                tcp: reader.parse("service.tcp"),
+                unix_socket: reader.optional("service.unix_socket"),
+                unix_socket_mode: reader.parse("service.unix_socket_mode"),
+            },
+            tls: TlsConfig {
+                enabled: reader.parse("tls.enabled"),
+                cert_path: reader.optional("tls.cert_path"),
+                key_path: reader.optional("tls.key_path"),
+                client_ca_path: reader.optional("tls.client_ca_path"),
+                client_auth: reader.parse("tls.client_auth"),
+                client_principals: reader.list("tls.client_principals"),
+                redirect_port: reader.optional_parse("tls.redirect_port"),
+            },
+            shutdown: ShutdownConfig {
+                pre_stop_delay_seconds: reader.parse("shutdown.pre_stop_delay_seconds"),
+                drain_timeout_seconds: reader.parse("shutdown.drain_timeout_seconds"),
+            },
+            api: ApiConfig {
+                version_default: reader.parse("api.version_default"),
+                version_precedence: reader.list("api.version_precedence"),
+            },
+            database: DatabaseConfig {
+                url: reader.optional("database.url"),
+            },
+            postgres: PostgresConfig {
+                user: reader.string("postgres.user"),
+                password: reader.string("postgres.password"),
+                host: reader.string("postgres.host"),
+                port: reader.parse("postgres.port"),
+                db: reader.string("postgres.db"),
+                connection_pool: reader.parse("postgres.connection_pool"),
+            },
+            redis: RedisConfig {
+                host: reader.string("redis.host"),
+                port: reader.parse("redis.port"),
+                username: reader.optional("redis.username"),
+                password: reader.optional("redis.password"),
+                tls: reader.parse("redis.tls"),
+                database: reader.parse("redis.db"),
+                mode: reader.parse("redis.mode"),
+                nodes: reader.list("redis.nodes"),
+                sentinel_master: reader.string("redis.sentinel_master"),
+                sentinel_password: reader.optional("redis.sentinel_password"),
+            },
+            jwt: JwtConfig {
+                keys: JwtKeys::new(jwt_secret.as_bytes()),
+                secret: jwt_secret,
+                expire_access_token_seconds: reader.parse("jwt.expire_access_token_seconds"),
+                expire_refresh_token_seconds: reader.parse("jwt.expire_refresh_token_seconds"),
+                validation_leeway_seconds: reader.parse("jwt.validation_leeway_seconds"),
+                enable_revoked_tokens: reader.parse("jwt.enable_revoked_tokens"),
+                revocation_store: reader.parse("jwt.revocation_store"),
+                previous_keys: None,
+            },
+            cors: CorsConfig {
+                default: CorsPolicy {
+                    allowed_origins: reader.list("cors.allowed_origins"),
+                    allowed_methods: reader.list("cors.allowed_methods"),
+                    allowed_headers: reader.list("cors.allowed_headers"),
+                    exposed_headers: reader.list("cors.exposed_headers"),
+                    allow_credentials: reader.parse("cors.allow_credentials"),
+                    max_age_seconds: reader.parse("cors.max_age_seconds"),
+                },
+                auth: read_cors_override(&mut reader, "cors.auth"),
+                users: read_cors_override(&mut reader, "cors.users"),
+            },
+            logging: LoggingConfig {
+                filter: reader.string("logging.filter"),
+                format: reader.parse("logging.format"),
+            },
+            secrets: read_secrets(&mut reader),
+        };
+
+        // the parsed values are validated, except the ones which could not be parsed
+        let mut errors = reader.errors;
+        let invalid: Vec<String> = errors.iter().map(|e| setting_key(e)).collect();
+        errors.extend(
+            validate(&config)
+                .into_iter()
+                .filter(|e| !invalid.contains(&setting_key(e))),
+        );
+        if errors.is_empty() {
+            Ok(config)
+        } else {
+            Err(ConfigError { errors })
+        }
+    }
+}
+
+fn read_cors_override(reader: &mut Reader, prefix: &str) -> CorsOverride {
+    let key = |name: &str| format!("{}.{}", prefix, name);
+    CorsOverride {
+        allowed_origins: reader.optional_list(&key("allowed_origins")),
+        allowed_methods: reader.optional_list(&key("allowed_methods")),
+        allowed_headers: reader.optional_list(&key("allowed_headers")),
+        exposed_headers: reader.optional_list(&key("exposed_headers")),
+        allow_credentials: reader.optional_parse(&key("allow_credentials")),
+        max_age_seconds: reader.optional_parse(&key("max_age_seconds")),
+    }
+}
+
+fn read_secrets(reader: &mut Reader) -> SecretsConfig {
+    SecretsConfig {
+        provider: reader.parse("secrets.provider"),
+        vault_addr: reader.string("secrets.vault_addr"),
+        vault_token: reader.optional("secrets.vault_token"),
+        vault_path: reader.string("secrets.vault_path"),
+        refresh_seconds: reader.parse("secrets.refresh_seconds"),
+    }
+}
+
+// the errors start with the key of the setting
+fn setting_key(error: &str) -> String {
+    error
+        .split([':', ' '])
+        .next()
+        .unwrap_or_default()
+        .to_string()
+}
+
+fn find(key: &str) -> Option<&'static Setting> {
+    SETTINGS.iter().find(|setting| setting.key == key)
+}
+
+// the settings of a file as `section.key` and their values as text,
+// the lists are joined by commas the same way as in the environment
+fn read_file(path: &Path) -> Result<Vec<(String, String)>, String> {
+    let content = std::fs::read_to_string(path).map_err(|e| e.to_string())?;
+    let extension = path.extension().and_then(|e| e.to_str()).unwrap_or("");
+    let document: serde_json::Value = match extension {
+        "toml" => toml::from_str(&content).map_err(|e| e.to_string())?,
+        "yaml" | "yml" => serde_yaml::from_str(&content).map_err(|e| e.to_string())?,
+        _ => return Err("expected a .toml, .yaml or .yml file".to_string()),
+    };
+
+    let mut values = Vec::new();
+    let sections = match document {
+        serde_json::Value::Object(sections) => sections,
+        serde_json::Value::Null => return Ok(values),
+        _ => return Err("expected sections of settings".to_string()),
+    };
+    for (section, settings) in sections {
+        let serde_json::Value::Object(settings) = settings else {
+            return Err(format!("expected the settings of the {} section", section));
+        };
+        for (key, value) in settings {
+            let key = format!("{}.{}", section, key);
+            // a nested table, e.g. [cors.auth]
+            if let serde_json::Value::Object(nested) = value {
+                for (name, value) in nested {
+                    let key = format!("{}.{}", key, name);
+                    let value = setting_text(&key, &value)?;
+                    values.push((key, value));
+                }
+                continue;
+            }
+            let value = setting_text(&key, &value)?;
+            values.push((key, value));
+        }
+    }
+    Ok(values)
+}
+
+fn setting_text(key: &str, value: &serde_json::Value) -> Result<String, String> {
+    match value {
+        serde_json::Value::Array(items) => Ok(items
+            .iter()
+            .map(text)
+            .collect::<Result<Vec<_>, _>>()
+            .map_err(|_| format!("{}: expected a list of values", key))?
+            .join(",")),
+        value => text(value).map_err(|_| format!("{}: expected a value", key)),
+    }
+}
+
+fn text(value: &serde_json::Value) -> Result<String, ()> {
+    match value {
+        serde_json::Value::String(s) => Ok(s.clone()),
+        serde_json::Value::Number(n) => Ok(n.to_string()),
+        serde_json::Value::Bool(b) => Ok(b.to_string()),
+        _ => Err(()),
+    }
+}
+
+// the value types of the settings, named in the errors
+trait SettingValue: FromStr + Default {
+    const EXPECTED: &'static str;
+}
+
+impl SettingValue for String {
+    const EXPECTED: &'static str = "a text";
+}
+
+impl SettingValue for u16 {
+    const EXPECTED: &'static str = "a port number";
+}
+
+impl SettingValue for u32 {
+    const EXPECTED: &'static str = "a positive number";
+}
+
+impl SettingValue for u64 {
+    const EXPECTED: &'static str = "zero or a positive number";
+}
+
+impl SettingValue for i64 {
+    const EXPECTED: &'static str = "a number";
+}
+
+impl SettingValue for bool {
+    const EXPECTED: &'static str = "true or false";
+}
+
+impl SettingValue for ApiVersion {
+    const EXPECTED: &'static str = "v1 or v2";
+}
+
+impl SettingValue for ApiVersionSource {
+    const EXPECTED: &'static str = "path, header or media_type";
+}
+
+impl SettingValue for RedisMode {
+    const EXPECTED: &'static str = "standalone, sentinel or cluster";
+}
+
+impl SettingValue for RevocationStore {
+    const EXPECTED: &'static str = "redis or memory";
+}
+
+impl SettingValue for LogFormat {
+    const EXPECTED: &'static str = "compact or pretty";
+}
+
+impl SettingValue for SocketMode {
+    const EXPECTED: &'static str = "an octal file mode like 660";
+}
+
+impl SettingValue for ClientAuth {
+    const EXPECTED: &'static str = "none, optional or required";
+}
+
+impl SettingValue for PrincipalUser {
+    const EXPECTED: &'static str = "a principal=username pair";
+}
+
+impl SettingValue for SecretsProvider {
+    const EXPECTED: &'static str = "none or vault";
+}
+
+// reads the values of the layers, the errors are collected to be reported at once
+struct Reader<'a> {
+    values: &'a BTreeMap<&'static str, String>,
+    errors: Vec<String>,
+}
+
+impl Reader<'_> {
+    fn string(&mut self, key: &str) -> String {
+        match self.values.get(key) {
+            Some(value) => value.clone(),
+            None => {
+                let env = find(key).map(|setting| setting.env).unwrap_or_default();
+                self.errors.push(format!(
+                    "{} is not set, set {} or {} in the configuration file",
+                    key, env, key
+                ));
+                String::new()
+            }
+        }
+    }
+
+    fn optional(&self, key: &str) -> Option<String> {
+        self.values.get(key).filter(|v| !v.is_empty()).cloned()
+    }
+
+    fn parse<T: SettingValue>(&mut self, key: &str) -> T {
+        let value = self.string(key);
+        self.parse_value(key, value.trim())
+    }
+
+    // a comma separated list
+    fn list<T: SettingValue>(&mut self, key: &str) -> Vec<T> {
+        let value = self.string(key);
+        self.parse_list(key, &value)
+    }
+
+    // an unset or empty setting is none, e.g. an override inheriting its value
+    fn optional_parse<T: SettingValue>(&mut self, key: &str) -> Option<T> {
+        let value = self.optional(key)?;
+        Some(self.parse_value(key, value.trim()))
+    }
+
+    fn optional_list<T: SettingValue>(&mut self, key: &str) -> Option<Vec<T>> {
+        let value = self.optional(key)?;
+        Some(self.parse_list(key, &value))
+    }
+
+    fn parse_list<T: SettingValue>(&mut self, key: &str, value: &str) -> Vec<T> {
+        value
+            .split(',')
+            .map(|s| s.trim())
+            .filter(|s| !s.is_empty())
+            .map(|s| self.parse_value(key, s))
+            .collect()
+    }
+
+    fn parse_value<T: SettingValue>(&mut self, key: &str, value: &str) -> T {
+        value.parse().unwrap_or_else(|_| {
+            self.errors.push(format!(
+                "{}: invalid value `{}`, expected {}",
+                key,
+                value,
+                T::EXPECTED
+            ));
+            T::default()
+        })
+    }
+}
+
+fn validate(config: &Config) -> Vec<String> {
+    let mut errors = Vec::new();
+
+    if config.service.host.is_empty() {
+        errors.push("service.host: expected a host name or an ip address".to_string());
+    }
+    errors.extend(validate_tls(&config.tls, &config.service));
+    if config.api.version_precedence.is_empty() {
+        errors.push("api.version_precedence: expected at least one source".to_string());
+    }
+
+    if let Some(url) = &config.database.url {
+        let schemes = ["postgres://", "postgresql://", "sqlite:"];
+        if !schemes.iter().any(|scheme| url.starts_with(scheme)) {
+            errors.push("database.url: expected a postgresql:// or sqlite: url".to_string());
+        }
+    }
+    if config.postgres.connection_pool == 0 {
+        errors.push("postgres.connection_pool: expected at least one connection".to_string());
+    }
+
+    for node in &config.redis.nodes {
+        if parse_node(node).is_none() {
+            errors.push(format!(
+                "redis.nodes: invalid node `{}`, expected host:port",
+                node
+            ));
+        }
+    }
+    if config.redis.mode == RedisMode::Cluster && config.redis.database != 0 {
+        errors.push("redis.db: Redis Cluster supports only the database 0".to_string());
+    }
+
+    if config.jwt.secret.len() < JWT_SECRET_MIN_LENGTH {
+        errors.push(format!(
+            "jwt.secret: expected at least {} characters, e.g. by `axum-web-admin generate-jwt-secret`",
+            JWT_SECRET_MIN_LENGTH
+        ));
+    }
+    if config.jwt.expire_access_token_seconds <= 0 {
+        errors.push("jwt.expire_access_token_seconds: expected a positive number".to_string());
+    }
+    if config.jwt.expire_refresh_token_seconds <= config.jwt.expire_access_token_seconds {
+        errors.push(
+            "jwt.expire_refresh_token_seconds: expected to outlive the access tokens".to_string(),
+        );
+    }
+    if config.jwt.validation_leeway_seconds < 0 {
+        errors
+            .push("jwt.validation_leeway_seconds: expected zero or a positive number".to_string());
+    }
+
+    errors.extend(validate_cors(&config.cors));
+    errors.extend(validate_secrets(&config.secrets));
+    errors
+}
+
+fn validate_tls(tls: &TlsConfig, service: &ServiceConfig) -> Vec<String> {
+    let mut errors = Vec::new();
+    if tls.enabled {
+        if tls.cert_path.is_none() {
+            errors.push(
+                "tls.cert_path: expected the PEM file of the certificate chain, set TLS_CERT_PATH"
+                    .to_string(),
+            );
+        }
+        if tls.key_path.is_none() {
+            errors.push(
+                "tls.key_path: expected the PEM file of the private key, set TLS_KEY_PATH"
+                    .to_string(),
+            );
+        }
+    }
+    if tls.client_auth != ClientAuth::None && tls.client_ca_path.is_none() {
+        errors.push(
+            "tls.client_ca_path: expected the PEM file of the CA certificates verifying the clients, set TLS_CLIENT_CA_PATH"
+                .to_string(),
+        );
+    }
+    if !tls.client_principals.is_empty() && tls.client_auth == ClientAuth::None {
+        errors.push(
+            "tls.client_principals: expected tls.client_auth optional or required, the principals are verified client certificates"
+                .to_string(),
+        );
+    }
+    if let Some(port) = tls.redirect_port {
+        if !tls.enabled {
+            errors.push(
+                "tls.redirect_port: expected tls.enabled, the redirects target the https listener"
+                    .to_string(),
+            );
+        } else if port == service.port {
+            errors.push(format!(
+                "tls.redirect_port: expected another port than the service port {}",
+                service.port
+            ));
+        }
+    }
+    errors
+}
+
+fn validate_secrets(secrets: &SecretsConfig) -> Vec<String> {
+    let mut errors = Vec::new();
+    if secrets.provider == SecretsProvider::Vault {
+        let addr = &secrets.vault_addr;
+        if !addr.starts_with("http://") && !addr.starts_with("https://") {
+            errors.push(format!(
+                "secrets.vault_addr: invalid address `{}`, expected an http(s):// url",
+                addr
+            ));
+        }
+        if secrets.vault_token.is_none() {
+            errors.push(
+                "secrets.vault_token: expected the token of the vault, set VAULT_TOKEN or VAULT_TOKEN_FILE"
+                    .to_string(),
+            );
+        }
+    }
+    errors
+}
+
+fn validate_cors(cors: &CorsConfig) -> Vec<String> {
+    let mut errors = Vec::new();
+    let policy = &cors.default;
+    validate_cors_lists(
+        "cors",
+        Some(&policy.allowed_origins),
+        Some(&policy.allowed_methods),
+        Some(&policy.allowed_headers),
+        Some(&policy.exposed_headers),
+        &mut errors,
+    );
+    validate_cors_credentials("cors", policy, &mut errors);
+
+    // the overridden settings, the inherited ones are validated by the default policy
+    for (group, overrides) in cors.overrides() {
+        let prefix = format!("cors.{}", group);
+        validate_cors_lists(
+            &prefix,
+            overrides.allowed_origins.as_ref(),
+            overrides.allowed_methods.as_ref(),
+            overrides.allowed_headers.as_ref(),
+            overrides.exposed_headers.as_ref(),
+            &mut errors,
+        );
+        let group_policy = overrides.apply(policy);
+        if group_policy != *policy {
+            validate_cors_credentials(&prefix, &group_policy, &mut errors);
+        }
+    }
+    errors
+}
+
+fn validate_cors_lists(
+    prefix: &str,
+    origins: Option<&Vec<String>>,
+    methods: Option<&Vec<String>>,
+    headers: Option<&Vec<String>>,
+    exposed_headers: Option<&Vec<String>>,
+    errors: &mut Vec<String>,
+) {
+    for origin in origins.into_iter().flatten() {
+        if !valid_origin(origin) {
+            errors.push(format!(
+                "{}.allowed_origins: invalid origin `{}`, expected *, an http(s):// origin or a wildcard subdomain like https://*.example.com",
+                prefix, origin
+            ));
+        }
+    }
+    for method in methods.into_iter().flatten() {
+        if method != "*" && Method::from_bytes(method.as_bytes()).is_err() {
+            errors.push(format!(
+                "{}.allowed_methods: invalid method `{}`",
+                prefix, method
+            ));
+        }
+    }
+    for (key, headers) in [
+        ("allowed_headers", headers),
+        ("exposed_headers", exposed_headers),
+    ] {
+        for header in headers.into_iter().flatten() {
+            if header != "*" && HeaderName::from_bytes(header.as_bytes()).is_err() {
+                errors.push(format!("{}.{}: invalid header `{}`", prefix, key, header));
+            }
+        }
+    }
+}
+
+// the browsers ignore the credentials allowed for any origin, the wildcards are listed instead
+fn validate_cors_credentials(prefix: &str, policy: &CorsPolicy, errors: &mut Vec<String>) {
+    let any = |values: &Vec<String>| values.iter().any(|value| value == "*");
+    if policy.allow_credentials
+        && (any(&policy.allowed_origins)
+            || any(&policy.allowed_methods)
+            || any(&policy.allowed_headers)
+            || any(&policy.exposed_headers))
+    {
+        errors.push(format!(
+            "{}.allow_credentials: credentials are not allowed with `*`, list the allowed origins, methods and headers",
+            prefix
+        ));
+    }
+}
+
+// `*`, an origin like https://example.com:8443 or a wildcard subdomain like https://*.example.com
+fn valid_origin(origin: &str) -> bool {
+    if origin == "*" {
+        return true;
+    }
+    let Some((scheme, host)) = origin.split_once("://") else {
+        return false;
+    };
+    let host = host.strip_prefix("*.").unwrap_or(host);
+    (scheme == "http" || scheme == "https")
+        && !host.is_empty()
+        && !host.contains(['*', '/'])
+        && HeaderValue::from_str(origin).is_ok()
+}
@@ -0,0 +1,240 @@
+use std::{
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::time::Instant;
+
+use super::{
+    get, layers, reloads, resolve, Config, ConfigArgs, ConfigError, PostgresConfig, CONFIG,
+};
+use crate::application::app_const::*;
+
+// the settings safe to change without a restart,
+// the other settings are kept until the process restarts
+pub const RELOADABLE_SETTINGS: &[&str] = &[
+    "jwt.expire_access_token_seconds",
+    "jwt.expire_refresh_token_seconds",
+    "jwt.validation_leeway_seconds",
+    "jwt.secret",
+    "postgres.password",
+    "cors",
+    "logging.filter",
+    "shutdown",
+];
+
+// the outcome of a reload
+#[derive(Debug, Default, PartialEq)]
+pub struct Reload {
+    // the reloadable settings with a new value
+    pub changed: Vec<&'static str>,
+    // the changed settings waiting for a restart
+    pub ignored: Vec<&'static str>,
+}
+
+// reloads the configuration by its layers and the secret provider, a valid one is swapped
+pub async fn reload(args: &ConfigArgs) -> Result<Reload, ConfigError> {
+    match resolve(layers(args)).await {
+        Ok(config) => {
+            let reload = swap(config);
+            if reload.changed.is_empty() {
+                tracing::debug!("configuration reloaded, unchanged");
+            } else {
+                tracing::info!("configuration reloaded, changed: {:?}", reload.changed);
+            }
+            if !reload.ignored.is_empty() {
+                tracing::warn!(
+                    "configuration changes applied by a restart only: {:?}",
+                    reload.ignored
+                );
+            }
+            metrics::counter!(METRIC_CONFIG_RELOADS, "result" => "success").increment(1);
+            Ok(reload)
+        }
+        Err(e) => {
+            tracing::error!(
+                "configuration reload failed, the configuration is kept: {}",
+                e
+            );
+            metrics::counter!(METRIC_CONFIG_RELOADS, "result" => "failure").increment(1);
+            Err(e)
+        }
+    }
+}
+
+// swaps the reloadable settings of the process configuration by the ones of a validated configuration
+pub fn swap(new: Config) -> Reload {
+    let current = get();
+    let mut config = (*current).clone();
+    let mut reload = Reload::default();
+
+    let changed = &mut reload.changed;
+    update(
+        "jwt.expire_access_token_seconds",
+        &mut config.jwt.expire_access_token_seconds,
+        new.jwt.expire_access_token_seconds,
+        changed,
+    );
+    update(
+        "jwt.expire_refresh_token_seconds",
+        &mut config.jwt.expire_refresh_token_seconds,
+        new.jwt.expire_refresh_token_seconds,
+        changed,
+    );
+    update(
+        "jwt.validation_leeway_seconds",
+        &mut config.jwt.validation_leeway_seconds,
+        new.jwt.validation_leeway_seconds,
+        changed,
+    );
+    // the tokens signed by the rotated secret stay valid until the next rotation
+    if config.jwt.secret != new.jwt.secret {
+        config.jwt.previous_keys = Some(config.jwt.keys.clone());
+        config.jwt.keys = new.jwt.keys.clone();
+        config.jwt.secret = new.jwt.secret.clone();
+        changed.push("jwt.secret");
+    }
+    // the new connections of the pool use the rotated password
+    update(
+        "postgres.password",
+        &mut config.postgres.password,
+        new.postgres.password.clone(),
+        changed,
+    );
+    update("cors", &mut config.cors, new.cors.clone(), changed);
+    update(
+        "logging.filter",
+        &mut config.logging.filter,
+        new.logging.filter.clone(),
+        changed,
+    );
+    update(
+        "shutdown",
+        &mut config.shutdown,
+        new.shutdown.clone(),
+        changed,
+    );
+
+    let ignored = &mut reload.ignored;
+    compare("service", &current.service, &new.service, ignored);
+    compare("tls", &current.tls, &new.tls, ignored);
+    compare("api", &current.api, &new.api, ignored);
+    compare("database", &current.database, &new.database, ignored);
+    let postgres = PostgresConfig {
+        password: current.postgres.password.clone(),
+        ..new.postgres.clone()
+    };
+    compare("postgres", &current.postgres, &postgres, ignored);
+    compare("redis", &current.redis, &new.redis, ignored);
+    compare(
+        "jwt.enable_revoked_tokens",
+        &current.jwt.enable_revoked_tokens,
+        &new.jwt.enable_revoked_tokens,
+        ignored,
+    );
+    compare(
+        "jwt.revocation_store",
+        &current.jwt.revocation_store,
+        &new.jwt.revocation_store,
+        ignored,
+    );
+    compare(
+        "logging.format",
+        &current.logging.format,
+        &new.logging.format,
+        ignored,
+    );
+    compare("secrets", &current.secrets, &new.secrets, ignored);
+
+    if !reload.changed.is_empty() {
+        CONFIG.get().unwrap().store(Arc::new(config));
+        reloads().send_replace(());
+    }
+    reload
+}
+
+fn update<T: PartialEq>(key: &'static str, current: &mut T, new: T, changed: &mut Vec<&str>) {
+    if *current != new {
+        *current = new;
+        changed.push(key);
+    }
+}
+
+fn compare<T: PartialEq>(key: &'static str, current: &T, new: &T, ignored: &mut Vec<&str>) {
+    if current != new {
+        ignored.push(key);
+    }
+}
+
+// reloads the configuration on SIGHUP, on a change of the configuration file
+// and periodically if the secrets are refreshed,
+// `on_reload` applies the reloaded configuration outside of the configuration, e.g. the log filter
+pub async fn watch(args: ConfigArgs, on_reload: impl Fn(&Config)) {
+    #[cfg(unix)]
+    let mut hangup = match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::hangup()) {
+        Ok(hangup) => hangup,
+        Err(e) => {
+            tracing::error!("could not watch SIGHUP for configuration reloads: {}", e);
+            return;
+        }
+    };
+
+    #[cfg(not(unix))]
+    let mut hangup = Hangup;
+
+    let file = args
+        .file
+        .clone()
+        .or_else(|| std::env::var_os("CONFIG_FILE").map(PathBuf::from));
+    let mut modified = file.as_deref().and_then(modified_time);
+    let mut interval = tokio::time::interval(Duration::from_secs(CONFIG_WATCH_INTERVAL_SECONDS));
+    let refresh_seconds = get().secrets.refresh_seconds;
+    let period = Duration::from_secs(refresh_seconds.max(1));
+    let mut refresh = tokio::time::interval_at(Instant::now() + period, period);
+
+    loop {
+        tokio::select! {
+            _ = hangup_received(&mut hangup) => {
+                tracing::info!("SIGHUP received, reloading the configuration")
+            }
+            _ = interval.tick() => {
+                let Some(file) = file.as_deref() else {
+                    continue;
+                };
+                let time = modified_time(file);
+                if time == modified {
+                    continue;
+                }
+                modified = time;
+                tracing::info!("{} changed, reloading the configuration", file.display());
+            }
+            _ = refresh.tick(), if refresh_seconds > 0 => {
+                tracing::debug!("refreshing the secrets");
+            }
+        }
+        if reload(&args).await.is_ok() {
+            on_reload(&get());
+        }
+    }
+}
+
+#[cfg(unix)]
+type Hangup = tokio::signal::unix::Signal;
+
+// there is no SIGHUP, the file changes and the secrets refresh reload the configuration
+#[cfg(not(unix))]
+struct Hangup;
+
+#[cfg(unix)]
+async fn hangup_received(hangup: &mut Hangup) {
+    hangup.recv().await;
+}
+
+#[cfg(not(unix))]
+async fn hangup_received(_: &mut Hangup) {
+    std::future::pending::<()>().await
+}
+
+fn modified_time(file: &Path) -> Option<SystemTime> {
+    std::fs::metadata(file).and_then(|m| m.modified()).ok()
+}
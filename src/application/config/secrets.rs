@@ -0,0 +1,41 @@
+use axum::async_trait;
+use std::{collections::HashMap, fmt, sync::Arc};
+
+use super::{SecretsConfig, SecretsProvider};
+use crate::infrastructure::vault::VaultSecretProvider;
+
+// a store of the secrets completing the configuration, e.g. a vault
+#[async_trait]
+pub trait SecretProvider: Send + Sync {
+    // the secrets by the names of their environment variables, e.g. JWT_SECRET
+    async fn secrets(&self) -> Result<HashMap<String, String>, SecretError>;
+}
+
+#[derive(Debug)]
+pub enum SecretError {
+    // the provider could not be reached or refused the request
+    Unavailable(String),
+    InvalidResponse(String),
+}
+
+impl fmt::Display for SecretError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            SecretError::Unavailable(e) => write!(f, "secrets unavailable: {}", e),
+            SecretError::InvalidResponse(e) => write!(f, "invalid secrets response: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for SecretError {}
+
+pub fn secret_provider(config: &SecretsConfig) -> Option<Arc<dyn SecretProvider>> {
+    match config.provider {
+        SecretsProvider::None => None,
+        SecretsProvider::Vault => Some(Arc::new(VaultSecretProvider::new(
+            &config.vault_addr,
+            config.vault_token.as_deref().unwrap_or_default(),
+            &config.vault_path,
+        ))),
+    }
+}
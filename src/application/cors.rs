@@ -0,0 +1,104 @@
+// the CORS policy of the configuration, selected by the route group of the request,
+// see https://docs.rs/tower-http/latest/tower_http/cors/index.html
+use axum::{
+    extract::Request,
+    http::{HeaderName, HeaderValue, Method},
+    middleware::Next,
+    response::Response,
+};
+use std::{convert::Infallible, time::Duration};
+use tower::{Layer, ServiceExt};
+use tower_http::cors::{AllowOrigin, Any, CorsLayer};
+
+use super::{
+    api_version,
+    config::{self, CorsPolicy},
+};
+
+// the policy is read by every request being reloadable
+pub async fn cors_middleware(request: Request, next: Next) -> Response {
+    let policy = config::get().cors.policy(route_group(request.uri().path()));
+    let mut next = Some(next);
+    let service = cors_layer(&policy).layer(tower::service_fn(move |request: Request| {
+        let next = next.take().expect("the request is served once");
+        async move { Ok::<_, Infallible>(next.run(request).await) }
+    }));
+    match service.oneshot(request).await {
+        Ok(response) => response,
+        Err(e) => match e {},
+    }
+}
+
+// the first path segment after the optional version, e.g. auth of /v1/auth/login
+pub fn route_group(path: &str) -> &str {
+    let mut segments = path.trim_start_matches('/').split('/');
+    let first = segments.next().unwrap_or_default();
+    if api_version::parse_version(first).is_ok() {
+        segments.next().unwrap_or_default()
+    } else {
+        first
+    }
+}
+
+// the settings are validated by the configuration
+pub fn cors_layer(policy: &CorsPolicy) -> CorsLayer {
+    let any = |values: &Vec<String>| values.iter().any(|value| value == "*");
+
+    let layer = if any(&policy.allowed_origins) {
+        CorsLayer::new().allow_origin(Any)
+    } else {
+        let origins = policy.allowed_origins.clone();
+        CorsLayer::new().allow_origin(AllowOrigin::predicate(move |origin: &HeaderValue, _| {
+            origin
+                .to_str()
+                .is_ok_and(|origin| origin_allowed(&origins, origin))
+        }))
+    };
+    let layer = if any(&policy.allowed_methods) {
+        layer.allow_methods(Any)
+    } else {
+        layer.allow_methods(parse::<Method>(&policy.allowed_methods))
+    };
+    let layer = if any(&policy.allowed_headers) {
+        layer.allow_headers(Any)
+    } else {
+        layer.allow_headers(parse::<HeaderName>(&policy.allowed_headers))
+    };
+    let layer = if any(&policy.exposed_headers) {
+        layer.expose_headers(Any)
+    } else {
+        layer.expose_headers(parse::<HeaderName>(&policy.exposed_headers))
+    };
+    let layer = layer.allow_credentials(policy.allow_credentials);
+    if policy.max_age_seconds > 0 {
+        layer.max_age(Duration::from_secs(policy.max_age_seconds))
+    } else {
+        layer
+    }
+}
+
+fn parse<T: std::str::FromStr>(values: &[String]) -> Vec<T> {
+    values
+        .iter()
+        .filter_map(|value| value.parse().ok())
+        .collect()
+}
+
+// an exact origin or a wildcard subdomain like https://*.example.com,
+// which does not match the domain itself
+pub fn origin_allowed(allowed: &[String], origin: &str) -> bool {
+    allowed.iter().any(|allowed| {
+        if allowed == origin {
+            return true;
+        }
+        let Some((scheme, domain)) = allowed.split_once("://*.") else {
+            return false;
+        };
+        origin
+            .strip_prefix(scheme)
+            .and_then(|origin| origin.strip_prefix("://"))
+            .and_then(|host| host.strip_suffix(domain))
+            .and_then(|subdomain| subdomain.strip_suffix('.'))
+            .is_some_and(|subdomain| !subdomain.is_empty())
+    })
+}
@@ -0,0 +1,68 @@
+use axum::http::{header, HeaderMap, HeaderValue, StatusCode};
+
+use super::api_error::{ApiError, ErrorCode};
+
+// strong entity tag of a row version
+pub fn etag(version: i64) -> HeaderValue {
+    HeaderValue::from_str(&format!("\"{}\"", version)).expect("Invalid entity tag")
+}
+
+// the If-Match precondition of a state changing request
+pub enum IfMatch {
+    Any,
+    Tags(Vec<String>),
+}
+
+impl IfMatch {
+    // strong comparison, weak tags never match
+    pub fn matches(&self, version: i64) -> bool {
+        match self {
+            IfMatch::Any => true,
+            IfMatch::Tags(tags) => tags.iter().any(|tag| etag(version) == tag.as_str()),
+        }
+    }
+}
+
+// If-Match is required on the requests modifying a resource
+pub fn if_match(headers: &HeaderMap) -> Result<IfMatch, ApiError> {
+    match header_tags(headers, header::IF_MATCH) {
+        Some(tags) if tags.iter().any(|tag| tag == "*") => Ok(IfMatch::Any),
+        Some(tags) if !tags.is_empty() => Ok(IfMatch::Tags(tags)),
+        _ => Err(ApiError::new(
+            StatusCode::PRECONDITION_REQUIRED,
+            ErrorCode::PreconditionRequired,
+            "If-Match header is required",
+        )),
+    }
+}
+
+// weak comparison, returns true if the client already has the current representation
+pub fn if_none_match(headers: &HeaderMap, version: i64) -> bool {
+    let current = etag(version);
+    header_tags(headers, header::IF_NONE_MATCH).is_some_and(|tags| {
+        tags.iter()
+            .any(|tag| tag == "*" || current == tag.trim_start_matches("W/"))
+    })
+}
+
+pub fn precondition_failed() -> ApiError {
+    ApiError::new(
+        StatusCode::PRECONDITION_FAILED,
+        ErrorCode::PreconditionFailed,
+        "The resource has been modified",
+    )
+}
+
+fn header_tags(headers: &HeaderMap, name: header::HeaderName) -> Option<Vec<String>> {
+    let values = headers.get_all(name);
+    let mut values = values.iter().peekable();
+    values.peek()?;
+    Some(
+        values
+            .filter_map(|value| value.to_str().ok())
+            .flat_map(|value| value.split(','))
+            .map(|tag| tag.trim().to_string())
+            .filter(|tag| !tag.is_empty())
+            .collect(),
+    )
+}
@@ -0,0 +1,279 @@
+// the listeners of the service: tcp, a unix domain socket or the sockets of systemd socket activation
+use axum::Router;
+use hyper::{body::Incoming, Request};
+use hyper_util::{
+    rt::{TokioExecutor, TokioIo},
+    server::{
+        conn::auto::Builder,
+        graceful::{GracefulShutdown, Watcher},
+    },
+};
+#[cfg(unix)]
+use socket2::Socket;
+use std::{future::Future, io, time::Duration};
+#[cfg(unix)]
+use std::{
+    ops::Range,
+    os::unix::{
+        fs::{FileTypeExt, PermissionsExt},
+        io::{FromRawFd, RawFd},
+    },
+    path::Path,
+};
+#[cfg(unix)]
+use tokio::net::{UnixListener, UnixStream};
+use tokio::{
+    io::{AsyncRead, AsyncWrite},
+    net::{TcpListener, TcpStream},
+    task::JoinSet,
+};
+use tower::ServiceExt;
+
+use super::{
+    config::ServiceConfig,
+    tls::{ClientPrincipal, TlsServer},
+};
+
+// the first file descriptor passed by systemd, see sd_listen_fds(3)
+#[cfg(unix)]
+const SD_LISTEN_FDS_START: RawFd = 3;
+
+#[derive(Debug)]
+pub enum Listener {
+    Tcp(TcpListener),
+    #[cfg(unix)]
+    Unix(UnixListener),
+}
+
+enum Stream {
+    Tcp(TcpStream),
+    #[cfg(unix)]
+    Unix(UnixStream),
+}
+
+impl Listener {
+    // the address of the listener in the logs, e.g. http://127.0.0.1:3000 or unix:/run/axum-web.sock
+    pub fn url(&self, tls: bool) -> String {
+        match self {
+            Listener::Tcp(listener) => {
+                let scheme = if tls { "https" } else { "http" };
+                let addr = listener.local_addr().map(|addr| addr.to_string());
+                format!("{}://{}", scheme, addr.unwrap_or_default())
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let addr = listener.local_addr().ok();
+                let path = addr.as_ref().and_then(|addr| addr.as_pathname());
+                format!(
+                    "unix:{}",
+                    path.map(|p| p.display().to_string()).unwrap_or_default()
+                )
+            }
+        }
+    }
+
+    async fn accept(&self) -> io::Result<(Stream, String)> {
+        match self {
+            Listener::Tcp(listener) => {
+                let (stream, addr) = listener.accept().await?;
+                Ok((Stream::Tcp(stream), addr.to_string()))
+            }
+            #[cfg(unix)]
+            Listener::Unix(listener) => {
+                let (stream, _) = listener.accept().await?;
+                Ok((Stream::Unix(stream), self.url(false)))
+            }
+        }
+    }
+}
+
+// the sockets passed by systemd replace the configured listeners,
+// otherwise the service host and port and the unix socket are bound
+pub async fn bind(service: &ServiceConfig) -> io::Result<Vec<Listener>> {
+    #[cfg(unix)]
+    {
+        let listeners = systemd_listeners()?;
+        if !listeners.is_empty() {
+            tracing::info!(
+                "{} listeners passed by systemd socket activation",
+                listeners.len()
+            );
+            return Ok(listeners);
+        }
+    }
+
+    let mut listeners = Vec::new();
+    if service.tcp {
+        let addr = service.socket_addr();
+        let listener = TcpListener::bind(&addr)
+            .await
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", addr, e)))?;
+        listeners.push(Listener::Tcp(listener));
+    }
+    #[cfg(unix)]
+    if let Some(path) = &service.unix_socket {
+        let listener = bind_unix(Path::new(path), service.unix_socket_mode.0)
+            .map_err(|e| io::Error::new(e.kind(), format!("{}: {}", path, e)))?;
+        listeners.push(Listener::Unix(listener));
+    }
+    #[cfg(not(unix))]
+    if let Some(path) = &service.unix_socket {
+        return Err(io::Error::new(
+            io::ErrorKind::Unsupported,
+            format!(
+                "{}: the unix sockets are not supported on this platform",
+                path
+            ),
+        ));
+    }
+    if listeners.is_empty() {
+        return Err(io::Error::new(
+            io::ErrorKind::InvalidInput,
+            "no listener, enable service.tcp, set service.unix_socket or start by systemd socket activation",
+        ));
+    }
+    Ok(listeners)
+}
+
+// a socket file left by a previous run is replaced, the other files are kept
+#[cfg(unix)]
+pub fn bind_unix(path: &Path, mode: u32) -> io::Result<UnixListener> {
+    if let Ok(metadata) = std::fs::symlink_metadata(path) {
+        if !metadata.file_type().is_socket() {
+            return Err(io::Error::new(
+                io::ErrorKind::AlreadyExists,
+                "the file exists and is not a socket",
+            ));
+        }
+        std::fs::remove_file(path)?;
+    }
+    let listener = UnixListener::bind(path)?;
+    std::fs::set_permissions(path, std::fs::Permissions::from_mode(mode))?;
+    Ok(listener)
+}
+
+// the sockets passed to this process by systemd, by LISTEN_PID and LISTEN_FDS,
+// the variables are removed to keep them from the child processes
+#[cfg(unix)]
+pub fn systemd_listeners() -> io::Result<Vec<Listener>> {
+    let fds = listen_fds(|key| std::env::var(key).ok());
+    if fds.is_empty() {
+        return Ok(Vec::new());
+    }
+    for key in ["LISTEN_PID", "LISTEN_FDS", "LISTEN_FDNAMES"] {
+        std::env::remove_var(key);
+    }
+    // safety: systemd passes these descriptors as open listening sockets owned by this process
+    fds.map(|fd| unsafe { from_fd(fd) }).collect()
+}
+
+// the descriptors passed by systemd, none if they are passed to another process
+#[cfg(unix)]
+pub fn listen_fds(var: impl Fn(&str) -> Option<String>) -> Range<RawFd> {
+    let pid = var("LISTEN_PID").and_then(|pid| pid.parse::<u32>().ok());
+    let fds = var("LISTEN_FDS").and_then(|fds| fds.parse::<RawFd>().ok());
+    match (pid, fds) {
+        (Some(pid), Some(fds)) if pid == std::process::id() && fds > 0 => {
+            SD_LISTEN_FDS_START..SD_LISTEN_FDS_START + fds
+        }
+        _ => SD_LISTEN_FDS_START..SD_LISTEN_FDS_START,
+    }
+}
+
+/// # Safety
+///
+/// `fd` is an open listening socket, owned by the returned listener
+#[cfg(unix)]
+pub unsafe fn from_fd(fd: RawFd) -> io::Result<Listener> {
+    let socket = Socket::from_raw_fd(fd);
+    socket.set_cloexec(true)?;
+    socket.set_nonblocking(true)?;
+    if socket.local_addr()?.is_unix() {
+        Ok(Listener::Unix(UnixListener::from_std(socket.into())?))
+    } else {
+        Ok(Listener::Tcp(TcpListener::from_std(socket.into())?))
+    }
+}
+
+// serves the app until the shutdown, then waits for the open connections and returns their count,
+// the connections still open at the deadline are aborted and waited for,
+// the tcp connections are encrypted by the given TLS server
+pub async fn serve(
+    listener: Listener,
+    app: Router,
+    tls: Option<TlsServer>,
+    shutdown: impl Future<Output = ()>,
+    deadline: impl Future<Output = ()>,
+) -> usize {
+    let graceful = GracefulShutdown::new();
+    // the tasks of the connections, aborted when dropped
+    let mut connections = JoinSet::new();
+    tokio::pin!(shutdown);
+    loop {
+        let (stream, peer) = tokio::select! {
+            accepted = listener.accept() => match accepted {
+                Ok(accepted) => accepted,
+                Err(e) => {
+                    // e.g. too many open files, retried after a pause
+                    tracing::error!("could not accept a connection: {}", e);
+                    tokio::time::sleep(Duration::from_secs(1)).await;
+                    continue;
+                }
+            },
+            Some(_) = connections.join_next(), if !connections.is_empty() => continue,
+            _ = &mut shutdown => break,
+        };
+        let app = app.clone();
+        let tls = tls.clone();
+        let watcher = graceful.watcher();
+        connections.spawn(async move {
+            match (stream, tls) {
+                (Stream::Tcp(stream), Some(tls)) => match tls.accept(stream).await {
+                    Ok((stream, principal)) => {
+                        serve_connection(stream, app, principal, watcher, &peer).await
+                    }
+                    Err(e) => tracing::debug!("TLS handshake with {} failed: {}", peer, e),
+                },
+                (Stream::Tcp(stream), None) => {
+                    serve_connection(stream, app, None, watcher, &peer).await
+                }
+                #[cfg(unix)]
+                (Stream::Unix(stream), _) => {
+                    serve_connection(stream, app, None, watcher, &peer).await
+                }
+            }
+        });
+    }
+    drop(listener);
+    let count = graceful.count();
+    tokio::select! {
+        _ = graceful.shutdown() => {}
+        _ = deadline => connections.abort_all(),
+    }
+    while connections.join_next().await.is_some() {}
+    count
+}
+
+// serves HTTP/1 or HTTP/2 by the preface of the connection
+async fn serve_connection<S>(
+    stream: S,
+    app: Router,
+    principal: Option<ClientPrincipal>,
+    watcher: Watcher,
+    peer: &str,
+) where
+    S: AsyncRead + AsyncWrite + Unpin + Send + 'static,
+{
+    let service = hyper::service::service_fn(move |mut request: Request<Incoming>| {
+        if let Some(principal) = &principal {
+            request.extensions_mut().insert(principal.clone());
+        }
+        app.clone().oneshot(request)
+    });
+    let connection = Builder::new(TokioExecutor::new())
+        .serve_connection_with_upgrades(TokioIo::new(stream), service)
+        .into_owned();
+    if let Err(e) = watcher.watch(connection).await {
+        tracing::debug!("connection with {} closed: {}", peer, e);
+    }
+}
@@ -0,0 +1,23 @@
+use serde_json::Value;
+
+// applies a JSON Merge Patch (RFC 7396) to the target document:
+// nulls remove members, objects are merged recursively, any other value replaces the target
+pub fn apply(target: &mut Value, patch: &Value) {
+    let Value::Object(patch) = patch else {
+        *target = patch.clone();
+        return;
+    };
+
+    if !target.is_object() {
+        *target = Value::Object(serde_json::Map::new());
+    }
+    if let Value::Object(target) = target {
+        for (key, value) in patch {
+            if value.is_null() {
+                target.remove(key);
+            } else {
+                apply(target.entry(key.as_str()).or_insert(Value::Null), value);
+            }
+        }
+    }
+}
@@ -1,9 +1,18 @@
+pub mod admin;
 pub mod api_error;
 pub mod api_version;
 pub mod app;
 pub mod app_const;
+pub mod check;
 pub mod config;
+pub mod cors;
+pub mod etag;
+pub mod listener;
+pub mod merge_patch;
 pub mod redis_service;
 pub mod repository;
 pub mod security;
+pub mod shutdown;
 pub mod state;
+pub mod tls;
+pub mod validation;
@@ -1,9 +1,5 @@
-use redis::{aio::MultiplexedConnection, AsyncCommands, RedisResult};
-use std::collections::HashMap;
-use tokio::sync::MutexGuard;
-
 use super::{
-    app_const::*,
+    repository::revocation_repo::RevocationResult,
     security::jwt_claims::{ClaimsMethods, RefreshClaims},
 };
 use crate::application::state::SharedState;
@@ -12,11 +8,11 @@ pub async fn revoke_global(state: &SharedState) -> bool {
     let timestamp_now = chrono::Utc::now().timestamp() as usize;
     tracing::debug!("setting a timestamp for global revoke: {}", timestamp_now);
 
-    let mut redis = state.redis.lock().await;
-    let redis_result: RedisResult<()> = redis
-        .set(JWT_REDIS_REVOKE_GLOBAL_BEFORE_KEY, timestamp_now)
-        .await;
-    if let Err(e) = redis_result {
+    if let Err(e) = state
+        .revocation_repo
+        .set_global_revoke_before(timestamp_now)
+        .await
+    {
         tracing::error!("{}", e);
         return false;
     }
@@ -31,37 +27,24 @@ pub async fn revoke_user_tokens(user_id: &str, state: &SharedState) -> bool {
         timestamp_now
     );
 
-    let mut redis = state.redis.lock().await;
-    let redis_result: RedisResult<()> = redis
-        .hset(JWT_REDIS_REVOKE_USER_BEFORE_KEY, user_id, timestamp_now)
-        .await;
-    if let Err(e) = redis_result {
+    if let Err(e) = state
+        .revocation_repo
+        .set_user_revoke_before(user_id, timestamp_now)
+        .await
+    {
         tracing::error!("{}", e);
         return false;
     }
     true
 }
 
-async fn is_global_revoked<T: ClaimsMethods>(
-    claims: &T,
-    redis: &mut MutexGuard<'_, redis::aio::MultiplexedConnection>,
-) -> Option<bool> {
+async fn is_global_revoked<T: ClaimsMethods>(claims: &T, state: &SharedState) -> Option<bool> {
     // check in global revoke
-    let redis_result: RedisResult<Option<String>> =
-        redis.get(JWT_REDIS_REVOKE_GLOBAL_BEFORE_KEY).await;
-    match redis_result {
+    match state.revocation_repo.get_global_revoke_before().await {
         Ok(opt_exp) => {
-            if let Some(exp) = opt_exp {
-                match exp.parse::<usize>() {
-                    Ok(global_exp) => {
-                        if global_exp >= claims.get_iat() {
-                            return Some(true);
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("{}", e);
-                        return None;
-                    }
+            if let Some(global_exp) = opt_exp {
+                if global_exp >= claims.get_iat() {
+                    return Some(true);
                 }
             }
         }
@@ -73,27 +56,14 @@ async fn is_global_revoked<T: ClaimsMethods>(
     Some(false)
 }
 
-async fn is_user_revoked<T: ClaimsMethods>(
-    claims: &T,
-    redis: &mut MutexGuard<'_, redis::aio::MultiplexedConnection>,
-) -> Option<bool> {
+async fn is_user_revoked<T: ClaimsMethods>(claims: &T, state: &SharedState) -> Option<bool> {
     // check in user revoke
     let user_id = claims.get_sub();
-    let redis_result: RedisResult<Option<String>> =
-        redis.hget(JWT_REDIS_REVOKE_USER_BEFORE_KEY, user_id).await;
-    match redis_result {
+    match state.revocation_repo.get_user_revoke_before(user_id).await {
         Ok(opt_exp) => {
-            if let Some(exp) = opt_exp {
-                match exp.parse::<usize>() {
-                    Ok(global_exp) => {
-                        if global_exp >= claims.get_iat() {
-                            return Some(true);
-                        }
-                    }
-                    Err(e) => {
-                        tracing::error!("{}", e);
-                        return None;
-                    }
+            if let Some(user_exp) = opt_exp {
+                if user_exp >= claims.get_iat() {
+                    return Some(true);
                 }
             }
         }
@@ -105,15 +75,13 @@ async fn is_user_revoked<T: ClaimsMethods>(
     Some(false)
 }
 
-async fn is_token_revoked<T: ClaimsMethods>(
-    claims: &T,
-    redis: &mut MutexGuard<'_, redis::aio::MultiplexedConnection>,
-) -> Option<bool> {
+async fn is_token_revoked<T: ClaimsMethods>(claims: &T, state: &SharedState) -> Option<bool> {
     // check the token in revoked list
-    let redis_result: RedisResult<bool> = redis
-        .hexists(JWT_REDIS_REVOKED_TOKENS_KEY, claims.get_jti())
-        .await;
-    match redis_result {
+    match state
+        .revocation_repo
+        .is_token_revoked(claims.get_jti())
+        .await
+    {
         Ok(revoked) => Some(revoked),
         Err(e) => {
             tracing::error!("{}", e);
@@ -126,8 +94,7 @@ pub async fn is_revoked<T: std::fmt::Debug + ClaimsMethods>(
     claims: &T,
     state: &SharedState,
 ) -> Option<bool> {
-    let mut redis = state.redis.lock().await;
-    match is_global_revoked(claims, &mut redis).await {
+    match is_global_revoked(claims, state).await {
         Some(revoked) => {
             if revoked {
                 tracing::error!("Access denied (globally revoked): {:#?}", claims);
@@ -139,7 +106,7 @@ pub async fn is_revoked<T: std::fmt::Debug + ClaimsMethods>(
         }
     }
 
-    match is_user_revoked(claims, &mut redis).await {
+    match is_user_revoked(claims, state).await {
         Some(revoked) => {
             if revoked {
                 tracing::error!("Access denied (user revoked): {:#?}", claims);
@@ -151,7 +118,7 @@ pub async fn is_revoked<T: std::fmt::Debug + ClaimsMethods>(
         }
     }
 
-    match is_token_revoked(claims, &mut redis).await {
+    match is_token_revoked(claims, state).await {
         Some(revoked) => {
             if revoked {
                 tracing::error!("Access denied (token revoked): {:#?}", claims);
@@ -167,24 +134,24 @@ pub async fn is_revoked<T: std::fmt::Debug + ClaimsMethods>(
 }
 
 pub async fn revoke_refresh_token(claims: &RefreshClaims, state: &SharedState) -> bool {
-    // adds the both refersh token and its paired access token into revoked list in Redis
+    // adds the both refersh token and its paired access token into revoked list
     // tokens are tracked by JWT ID that handles the cases of reusing lost tokens and multi-device scenarios
 
-    let mut redis = state.redis.lock().await;
     let list_to_revoke = vec![&claims.jti, &claims.prf];
     tracing::debug!("adding jwt tokens into revoked list: {:#?}", list_to_revoke);
     for claims_jti in list_to_revoke {
-        let redis_result: RedisResult<()> = redis
-            .hset(JWT_REDIS_REVOKED_TOKENS_KEY, claims_jti, claims.exp)
-            .await;
-        if let Err(e) = redis_result {
+        if let Err(e) = state
+            .revocation_repo
+            .add_revoked_token(claims_jti, claims.exp)
+            .await
+        {
             tracing::error!("{}", e);
             return false;
         }
     }
 
     if tracing::enabled!(tracing::Level::TRACE) {
-        log_revoked_tokens_count(&mut redis).await;
+        log_revoked_tokens_count(state).await;
     }
     true
 }
@@ -205,44 +172,30 @@ pub async fn cleanup_expired(state: &SharedState) -> Option<usize> {
     }
 }
 
-async fn delete_expired_tokens(state: &SharedState) -> RedisResult<usize> {
+async fn delete_expired_tokens(state: &SharedState) -> RevocationResult<usize> {
     let timestamp_now = chrono::Utc::now().timestamp() as usize;
 
-    let mut redis = state.redis.lock().await;
-    let revoked_tokens: HashMap<String, String> =
-        redis.hgetall(JWT_REDIS_REVOKED_TOKENS_KEY).await?;
+    let revoked_tokens = state.revocation_repo.get_revoked_tokens().await?;
 
     let mut deleted = 0;
-    for (key, exp) in revoked_tokens {
-        match exp.parse::<usize>() {
-            Ok(timestamp_exp) => {
-                if timestamp_now > timestamp_exp {
-                    // Workaround for https://github.com/redis-rs/redis-rs/issues/1322
-                    let _: () = redis.hdel(JWT_REDIS_REVOKED_TOKENS_KEY, key).await?;
-                    deleted += 1;
-                }
-            }
-            Err(e) => {
-                tracing::error!("{}", e);
-            }
+    for (key, timestamp_exp) in revoked_tokens {
+        if timestamp_now > timestamp_exp {
+            state.revocation_repo.delete_revoked_token(&key).await?;
+            deleted += 1;
         }
     }
 
     if tracing::enabled!(tracing::Level::TRACE) {
-        log_revoked_tokens_count(&mut redis).await;
+        log_revoked_tokens_count(state).await;
     }
 
     Ok(deleted)
 }
 
-pub async fn log_revoked_tokens_count(redis: &mut MultiplexedConnection) {
-    let redis_result: RedisResult<usize> = redis.hlen(JWT_REDIS_REVOKED_TOKENS_KEY).await;
-    match redis_result {
+pub async fn log_revoked_tokens_count(state: &SharedState) {
+    match state.revocation_repo.revoked_tokens_count().await {
         Ok(revoked_tokens_count) => {
-            tracing::debug!(
-                "REDIS: count of revoked jwt tokens: {}",
-                revoked_tokens_count
-            );
+            tracing::debug!("count of revoked jwt tokens: {}", revoked_tokens_count);
         }
         Err(e) => {
             tracing::error!("{}", e);
@@ -250,13 +203,10 @@ pub async fn log_revoked_tokens_count(redis: &mut MultiplexedConnection) {
     }
 }
 
-pub async fn log_revoked_tokens(redis: &mut MultiplexedConnection) {
-    let redis_result: RedisResult<HashMap<String, String>> =
-        redis.hgetall(JWT_REDIS_REVOKED_TOKENS_KEY).await;
-
-    match redis_result {
+pub async fn log_revoked_tokens(state: &SharedState) {
+    match state.revocation_repo.get_revoked_tokens().await {
         Ok(revoked_tokens) => {
-            tracing::trace!("REDIS: list of revoked jwt tokens: {:#?}", revoked_tokens);
+            tracing::trace!("list of revoked jwt tokens: {:#?}", revoked_tokens);
         }
         Err(e) => {
             tracing::error!("{}", e);
@@ -1,3 +1,7 @@
+pub mod repository_error;
+pub mod revocation_repo;
 pub mod user_repo;
 
-pub type RepositoryResult<T> = Result<T, sqlx::Error>;
+use repository_error::RepositoryError;
+
+pub type RepositoryResult<T> = Result<T, RepositoryError>;
@@ -0,0 +1,137 @@
+use axum::http::{header, HeaderValue, StatusCode};
+use sqlx::error::{DatabaseError, ErrorKind};
+
+use crate::application::{
+    api_error::{ApiError, ErrorCode, FieldError},
+    app_const::REPOSITORY_RETRY_AFTER_SECONDS,
+};
+
+#[derive(Debug)]
+pub enum RepositoryError {
+    NotFound,
+    // the field is known if the storage reports the violated constraint
+    UniqueViolation(Option<String>),
+    ForeignKeyViolation(String),
+    CheckViolation(String),
+    // the storage is temporarily unreachable, e.g. the pool timed out
+    Unavailable(String),
+    Storage(sqlx::Error),
+}
+
+impl std::fmt::Display for RepositoryError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            RepositoryError::NotFound => write!(f, "row not found"),
+            RepositoryError::UniqueViolation(Some(field)) => {
+                write!(f, "unique violation: {}", field)
+            }
+            RepositoryError::UniqueViolation(None) => write!(f, "unique violation"),
+            RepositoryError::ForeignKeyViolation(message) => {
+                write!(f, "foreign key violation: {}", message)
+            }
+            RepositoryError::CheckViolation(message) => write!(f, "check violation: {}", message),
+            RepositoryError::Unavailable(message) => write!(f, "storage unavailable: {}", message),
+            RepositoryError::Storage(e) => write!(f, "storage error: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for RepositoryError {}
+
+impl From<sqlx::Error> for RepositoryError {
+    fn from(err: sqlx::Error) -> Self {
+        match err {
+            sqlx::Error::RowNotFound => RepositoryError::NotFound,
+            sqlx::Error::PoolTimedOut | sqlx::Error::PoolClosed | sqlx::Error::Io(_) => {
+                RepositoryError::Unavailable(err.to_string())
+            }
+            sqlx::Error::Database(db_error) => match db_error.kind() {
+                ErrorKind::UniqueViolation => {
+                    RepositoryError::UniqueViolation(constraint_field(db_error.as_ref()))
+                }
+                ErrorKind::ForeignKeyViolation => {
+                    RepositoryError::ForeignKeyViolation(db_error.message().to_string())
+                }
+                ErrorKind::CheckViolation | ErrorKind::NotNullViolation => {
+                    RepositoryError::CheckViolation(db_error.message().to_string())
+                }
+                _ => RepositoryError::Storage(sqlx::Error::Database(db_error)),
+            },
+            _ => RepositoryError::Storage(err),
+        }
+    }
+}
+
+// finds the column of a violated unique constraint
+fn constraint_field(db_error: &dyn DatabaseError) -> Option<String> {
+    // postgres names the constraints as <table>_pkey and <table>_<column>_key
+    if let Some(constraint) = db_error.constraint() {
+        if constraint.ends_with("_pkey") {
+            return Some("id".to_string());
+        }
+        return constraint
+            .strip_suffix("_key")
+            .and_then(|name| name.split_once('_'))
+            .map(|(_, column)| column.to_string());
+    }
+    // sqlite reports the columns in the message: UNIQUE constraint failed: <table>.<column>
+    db_error
+        .message()
+        .rsplit_once(": ")
+        .and_then(|(_, columns)| columns.split_once('.'))
+        .map(|(_, column)| column.to_string())
+}
+
+impl From<RepositoryError> for ApiError {
+    fn from(err: RepositoryError) -> Self {
+        match err {
+            RepositoryError::NotFound => {
+                ApiError::new(StatusCode::NOT_FOUND, ErrorCode::NotFound, "Not found")
+            }
+            RepositoryError::UniqueViolation(field) => {
+                let error = ApiError::new(
+                    StatusCode::CONFLICT,
+                    ErrorCode::UniqueViolation,
+                    match &field {
+                        Some(field) => format!("Duplicate value: {}", field),
+                        None => "Duplicate value".to_string(),
+                    },
+                );
+                match field {
+                    Some(field) => error.with_field_errors(vec![FieldError {
+                        field,
+                        code: "unique".to_string(),
+                        message: "already exists".to_string(),
+                    }]),
+                    None => error,
+                }
+            }
+            RepositoryError::ForeignKeyViolation(message)
+            | RepositoryError::CheckViolation(message) => ApiError::new(
+                StatusCode::UNPROCESSABLE_ENTITY,
+                ErrorCode::ConstraintViolation,
+                message,
+            ),
+            RepositoryError::Unavailable(message) => {
+                tracing::error!("{}", message);
+                ApiError::new(
+                    StatusCode::SERVICE_UNAVAILABLE,
+                    ErrorCode::StorageUnavailable,
+                    "Storage is temporarily unavailable",
+                )
+                .with_header(
+                    header::RETRY_AFTER,
+                    HeaderValue::from(REPOSITORY_RETRY_AFTER_SECONDS),
+                )
+            }
+            RepositoryError::Storage(e) => {
+                tracing::error!("{}", e);
+                ApiError::new(
+                    StatusCode::INTERNAL_SERVER_ERROR,
+                    ErrorCode::StorageError,
+                    "Storage error",
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,21 @@
+use axum::async_trait;
+use std::collections::HashMap;
+
+pub type RevocationResult<T> = Result<T, Box<dyn std::error::Error + Send + Sync>>;
+
+// storage of revoked JWT tokens and revocation timestamps
+#[async_trait]
+pub trait RevocationRepository: Send + Sync {
+    async fn set_global_revoke_before(&self, timestamp: usize) -> RevocationResult<()>;
+    async fn get_global_revoke_before(&self) -> RevocationResult<Option<usize>>;
+    async fn set_user_revoke_before(&self, user_id: &str, timestamp: usize)
+        -> RevocationResult<()>;
+    async fn get_user_revoke_before(&self, user_id: &str) -> RevocationResult<Option<usize>>;
+    async fn add_revoked_token(&self, jti: &str, exp: usize) -> RevocationResult<()>;
+    async fn is_token_revoked(&self, jti: &str) -> RevocationResult<bool>;
+    async fn get_revoked_tokens(&self) -> RevocationResult<HashMap<String, usize>>;
+    async fn delete_revoked_token(&self, jti: &str) -> RevocationResult<()>;
+    async fn revoked_tokens_count(&self) -> RevocationResult<usize>;
+    // closes the connection of the storage on shutdown
+    async fn close(&self) {}
+}
@@ -1,104 +1,165 @@
-use chrono::Utc;
-use sqlx::query_as;
+use axum::async_trait;
+use base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
 use uuid::Uuid;
 
-use crate::{
-    application::{app_const::USER_ROLE_GUEST, repository::RepositoryResult, state::SharedState},
-    domain::models::user::User,
-};
+use crate::{application::repository::RepositoryResult, domain::models::user::User};
 
-pub async fn get_all(state: &SharedState) -> RepositoryResult<Vec<User>> {
-    let users = query_as::<_, User>("SELECT * FROM users")
-        .fetch_all(&state.pgpool)
-        .await?;
+// the whitelisted sort fields of the list query
+pub use axum_web_types::users::{SortOrder, UserSortField};
 
-    Ok(users)
+#[async_trait]
+pub trait UserRepository: Send + Sync {
+    async fn get_all(&self) -> RepositoryResult<Vec<User>>;
+    async fn list(&self, query: &UserListQuery) -> RepositoryResult<UserPage>;
+    async fn get_by_id(&self, id: Uuid) -> RepositoryResult<User>;
+    async fn get_by_username(&self, username: &str) -> RepositoryResult<User>;
+    async fn get_by_email(&self, email: &str) -> RepositoryResult<User>;
+    // adds an active user with the given roles
+    async fn add(&self, user: User) -> RepositoryResult<User>;
+    // compare-and-swap on the row version, fails with NotFound if the stored version differs
+    async fn update(&self, id: Uuid, user: User, version: i64) -> RepositoryResult<User>;
+    // soft deletes the user only if the stored version matches,
+    // deleted users are excluded from all the other queries
+    async fn delete(&self, id: Uuid, version: i64) -> RepositoryResult<bool>;
+    // undeletes a soft deleted user, fails with NotFound if there is no such user
+    async fn restore(&self, id: Uuid) -> RepositoryResult<User>;
+    // removes a soft deleted user permanently
+    async fn purge(&self, id: Uuid) -> RepositoryResult<bool>;
+    // closes the connections of the database on shutdown, waiting for the checked out ones
+    async fn close(&self) {}
 }
 
-pub async fn add(user: User, state: &SharedState) -> RepositoryResult<User> {
-    let time_now = Utc::now().naive_utc();
-    tracing::trace!("user: {:#?}", user);
-    let user = sqlx::query_as::<_, User>(
-        r#"INSERT INTO users (id,
-         username,
-         email,
-         password_hash,
-         password_salt,
-         active,
-         roles,
-         created_at,
-         updated_at)
-         VALUES ($1,$2,$3,$4,$5,$6,$7,$8,$9)
-         RETURNING users.*"#,
-    )
-    .bind(user.id)
-    .bind(user.username)
-    .bind(user.email)
-    .bind(user.password_hash)
-    .bind(user.password_salt)
-    .bind(true)
-    .bind(USER_ROLE_GUEST)
-    .bind(time_now)
-    .bind(time_now)
-    .fetch_one(&state.pgpool)
-    .await?;
-
-    Ok(user)
+// the column and the value of a user a sort field stands for
+pub trait UserSortKey {
+    fn column(&self) -> &'static str;
+    fn value(&self, user: &User) -> UserSortValue;
 }
 
-pub async fn get_by_id(id: Uuid, state: &SharedState) -> RepositoryResult<User> {
-    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE id = $1")
-        .bind(id)
-        .fetch_one(&state.pgpool)
-        .await?;
-    Ok(user)
+impl UserSortKey for UserSortField {
+    fn column(&self) -> &'static str {
+        match self {
+            UserSortField::Username => "username",
+            UserSortField::Email => "email",
+            UserSortField::CreatedAt => "created_at",
+            UserSortField::UpdatedAt => "updated_at",
+        }
+    }
+
+    fn value(&self, user: &User) -> UserSortValue {
+        match self {
+            UserSortField::Username => UserSortValue::Text(user.username.clone()),
+            UserSortField::Email => UserSortValue::Text(user.email.clone()),
+            UserSortField::CreatedAt => UserSortValue::Timestamp(user.created_at),
+            UserSortField::UpdatedAt => UserSortValue::Timestamp(user.updated_at),
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, PartialOrd, Serialize, Deserialize)]
+pub enum UserSortValue {
+    Text(String),
+    Timestamp(Option<NaiveDateTime>),
+}
+
+#[derive(Debug, Clone, Default)]
+pub struct UserFilter {
+    pub active: Option<bool>,
+    pub role: Option<String>,
+    pub username_prefix: Option<String>,
+    pub email_prefix: Option<String>,
+    pub created_after: Option<NaiveDateTime>,
+    pub created_before: Option<NaiveDateTime>,
+}
+
+impl UserFilter {
+    pub fn matches(&self, user: &User) -> bool {
+        self.active.is_none_or(|active| user.active == active)
+            && self
+                .role
+                .as_ref()
+                .is_none_or(|role| user.roles.split(',').any(|r| r.trim() == role.as_str()))
+            && self
+                .username_prefix
+                .as_ref()
+                .is_none_or(|prefix| user.username.starts_with(prefix.as_str()))
+            && self
+                .email_prefix
+                .as_ref()
+                .is_none_or(|prefix| user.email.starts_with(prefix.as_str()))
+            && self
+                .created_after
+                .is_none_or(|after| user.created_at.is_some_and(|c| c >= after))
+            && self
+                .created_before
+                .is_none_or(|before| user.created_at.is_some_and(|c| c < before))
+    }
+}
+
+// keyset pagination cursor: the sort value and id of the last user on the previous page
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct UserCursor {
+    pub sort: UserSortField,
+    pub order: SortOrder,
+    pub value: UserSortValue,
+    pub id: Uuid,
 }
 
-pub async fn get_user_by_username(username: &str, state: &SharedState) -> RepositoryResult<User> {
-    let user = sqlx::query_as::<_, User>("SELECT * FROM users WHERE username = $1")
-        .bind(username)
-        .fetch_one(&state.pgpool)
-        .await?;
+impl UserCursor {
+    pub fn after(user: &User, sort: UserSortField, order: SortOrder) -> Self {
+        Self {
+            sort,
+            order,
+            value: sort.value(user),
+            id: user.id,
+        }
+    }
+
+    pub fn encode(&self) -> String {
+        URL_SAFE_NO_PAD.encode(serde_json::to_vec(self).unwrap_or_default())
+    }
+
+    pub fn decode(cursor: &str) -> Option<Self> {
+        let bytes = URL_SAFE_NO_PAD.decode(cursor).ok()?;
+        serde_json::from_slice(&bytes).ok()
+    }
+}
 
-    Ok(user)
+#[derive(Debug, Clone, Default)]
+pub struct UserListQuery {
+    pub filter: UserFilter,
+    pub sort: UserSortField,
+    pub order: SortOrder,
+    pub limit: u32,
+    pub cursor: Option<UserCursor>,
+    pub include_total: bool,
 }
 
-pub async fn update(id: Uuid, user: User, state: &SharedState) -> RepositoryResult<User> {
-    tracing::trace!("user: {:#?}", user);
-    let time_now = Utc::now().naive_utc();
-    let user = sqlx::query_as::<_, User>(
-        r#"UPDATE users
-         SET id = $1,
-         username = $2,
-         email = $3,
-         password_hash = $4,
-         password_salt = $5,
-         active = $6,
-         roles = $7,
-         updated_at = $8
-         WHERE id = $9
-         RETURNING users.*"#,
-    )
-    .bind(user.id)
-    .bind(user.username)
-    .bind(user.email)
-    .bind(user.password_hash)
-    .bind(user.password_salt)
-    .bind(user.active)
-    .bind(user.roles)
-    .bind(time_now)
-    .bind(id)
-    .fetch_one(&state.pgpool)
-    .await?;
-
-    Ok(user)
+#[derive(Debug, Clone)]
+pub struct UserPage {
+    pub users: Vec<User>,
+    pub next_cursor: Option<UserCursor>,
+    pub total_count: Option<i64>,
 }
 
-pub async fn delete(id: Uuid, state: &SharedState) -> RepositoryResult<bool> {
-    let query_result = sqlx::query("DELETE FROM users WHERE id = $1")
-        .bind(id)
-        .execute(&state.pgpool)
-        .await?;
+impl UserPage {
+    // expects up to `limit + 1` users, the extra one signals the existence of a next page
+    pub fn new(mut users: Vec<User>, query: &UserListQuery, total_count: Option<i64>) -> Self {
+        let limit = query.limit as usize;
+        let next_cursor = if users.len() > limit {
+            users.truncate(limit);
+            users
+                .last()
+                .map(|user| UserCursor::after(user, query.sort, query.order))
+        } else {
+            None
+        };
 
-    Ok(query_result.rows_affected() == 1)
+        Self {
+            users,
+            next_cursor,
+            total_count,
+        }
+    }
 }
@@ -1,4 +1,4 @@
-use crate::application::api_error::ApiError;
+use crate::application::api_error::{ApiError, ErrorCode};
 use axum::http::StatusCode;
 
 #[derive(Debug)]
@@ -7,19 +7,58 @@ pub enum AuthError {
     MissingCredentials,
     TokenCreation,
     InvalidToken,
+    ExpiredToken,
+    RevokedToken,
+    // the revocation store could not record a revocation
+    RevocationFailed,
+    // the revocation store could not be read, the revocation of a token is unknown
+    RevocationUnavailable,
 }
 
 impl From<AuthError> for ApiError {
     fn from(err: AuthError) -> Self {
-        let (status_code, error_message) = match err {
-            AuthError::WrongCredentials => (StatusCode::UNAUTHORIZED, "Wrong credentials"),
-            AuthError::MissingCredentials => (StatusCode::BAD_REQUEST, "Missing credentials"),
-            AuthError::TokenCreation => (StatusCode::INTERNAL_SERVER_ERROR, "Token creation error"),
-            AuthError::InvalidToken => (StatusCode::BAD_REQUEST, "Invalid token"),
+        let (status_code, error_code, error_message) = match err {
+            AuthError::WrongCredentials => (
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::WrongCredentials,
+                "Wrong credentials",
+            ),
+            AuthError::MissingCredentials => (
+                StatusCode::BAD_REQUEST,
+                ErrorCode::MissingCredentials,
+                "Missing credentials",
+            ),
+            AuthError::TokenCreation => (
+                StatusCode::INTERNAL_SERVER_ERROR,
+                ErrorCode::TokenCreation,
+                "Token creation error",
+            ),
+            AuthError::InvalidToken => (
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::InvalidToken,
+                "Invalid token",
+            ),
+            AuthError::ExpiredToken => (
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::ExpiredToken,
+                "Expired token",
+            ),
+            AuthError::RevokedToken => (
+                StatusCode::UNAUTHORIZED,
+                ErrorCode::RevokedToken,
+                "Revoked token",
+            ),
+            AuthError::RevocationFailed => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorCode::RevocationFailed,
+                "The tokens could not be revoked",
+            ),
+            AuthError::RevocationUnavailable => (
+                StatusCode::SERVICE_UNAVAILABLE,
+                ErrorCode::RevocationUnavailable,
+                "The revoked tokens could not be checked",
+            ),
         };
-        ApiError {
-            status_code,
-            error_message: error_message.to_owned(),
-        }
+        ApiError::new(status_code, error_code, error_message)
     }
 }
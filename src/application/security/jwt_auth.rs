@@ -1,6 +1,10 @@
 use crate::{
     application::{
-        api_error::ApiError, config, redis_service, repository::user_repo, state::SharedState,
+        api_error::{ApiError, ErrorCode},
+        config, redis_service,
+        repository::repository_error::RepositoryError,
+        state::SharedState,
+        tls::ClientPrincipal,
     },
     domain::models::user::User,
 };
@@ -16,14 +20,14 @@ pub struct JwtTokens {
 
 pub async fn logout(refresh_claims: RefreshClaims, state: SharedState) -> Result<(), ApiError> {
     // checking the configuration if the usage of the list of revoked tokens is enabled
-    if config::get().jwt_enable_revoked_tokens {
+    if config::get().jwt.enable_revoked_tokens {
         // decode and validate the refresh token
         if !validate_token_type(&refresh_claims, JwtTokenType::RefreshToken) {
             return Err(AuthError::InvalidToken.into());
         }
         revoke_refresh_token(&refresh_claims, &state).await
     } else {
-        Err(StatusCode::NOT_ACCEPTABLE.into())
+        Err(revoked_tokens_disabled())
     }
 }
 
@@ -36,21 +40,26 @@ pub async fn refresh(
         return Err(AuthError::InvalidToken.into());
     }
 
+    let user_id: Uuid = refresh_claims
+        .sub
+        .parse()
+        .map_err(|_| AuthError::InvalidToken)?;
+
     // checking the configuration if the usage of the list of revoked tokens is enabled
-    if config::get().jwt_enable_revoked_tokens {
+    if config::get().jwt.enable_revoked_tokens {
         revoke_refresh_token(&refresh_claims, &state).await?;
     }
 
-    let user_id = refresh_claims.sub.parse().unwrap();
-    if let Ok(user) = user_repo::get_by_id(user_id, &state).await {
-        let tokens = generate_tokens(user);
-        return Ok(tokens);
+    match state.user_repo.get_by_id(user_id).await {
+        Ok(user) => Ok(generate_tokens(user)),
+        Err(RepositoryError::NotFound) => Err(ApiError::new(
+            StatusCode::UNPROCESSABLE_ENTITY,
+            ErrorCode::NotFound,
+            format!("user not found: {}", user_id),
+        )),
+        // storage failures must not look like a missing user
+        Err(e) => Err(e.into()),
     }
-
-    Err(ApiError {
-        status_code: StatusCode::UNPROCESSABLE_ENTITY,
-        error_message: format!("user not found: {}", user_id),
-    })
 }
 
 pub async fn cleanup_revoked_and_expired(
@@ -58,15 +67,23 @@ pub async fn cleanup_revoked_and_expired(
     state: &SharedState,
 ) -> Result<usize, ApiError> {
     // checking the configuration if the usage of the list of revoked tokens is enabled
-    if !config::get().jwt_enable_revoked_tokens {
-        return Err(StatusCode::NOT_ACCEPTABLE.into());
+    if !config::get().jwt.enable_revoked_tokens {
+        return Err(revoked_tokens_disabled());
     }
 
     if let Some(deleted) = redis_service::cleanup_expired(state).await {
         return Ok(deleted);
     }
 
-    Err(StatusCode::INTERNAL_SERVER_ERROR.into())
+    Err(AuthError::RevocationUnavailable.into())
+}
+
+fn revoked_tokens_disabled() -> ApiError {
+    ApiError::new(
+        StatusCode::NOT_ACCEPTABLE,
+        ErrorCode::NotAcceptable,
+        "The list of revoked tokens is disabled",
+    )
 }
 
 pub fn validate_token_type(claims: &RefreshClaims, expected_type: JwtTokenType) -> bool {
@@ -90,7 +107,41 @@ async fn revoke_refresh_token(
     if redis_service::revoke_refresh_token(refresh_claims, state).await {
         return Ok(());
     }
-    Err(StatusCode::INTERNAL_SERVER_ERROR.into())
+    Err(AuthError::RevocationFailed.into())
+}
+
+// the access claims of the user mapped to the principal of a verified client certificate,
+// none if the principal is not mapped, the inactive users are rejected
+pub async fn principal_claims(
+    principal: &ClientPrincipal,
+    state: &SharedState,
+) -> Result<Option<AccessClaims>, ApiError> {
+    let config = config::get();
+    let Some(mapping) = config
+        .tls
+        .client_principals
+        .iter()
+        .find(|mapping| mapping.principal == principal.0)
+    else {
+        return Ok(None);
+    };
+    let user = match state.user_repo.get_by_username(&mapping.username).await {
+        Ok(user) if user.active => user,
+        Ok(_) | Err(RepositoryError::NotFound) => {
+            tracing::error!("access denied, client principal: {}", principal.0);
+            return Err(AuthError::WrongCredentials.into());
+        }
+        Err(e) => return Err(e.into()),
+    };
+    let iat = chrono::Utc::now().timestamp() as usize;
+    Ok(Some(AccessClaims {
+        sub: user.id.to_string(),
+        jti: Uuid::new_v4().to_string(),
+        iat,
+        exp: iat + config.jwt.expire_access_token_seconds as usize,
+        typ: JwtTokenType::AccessToken as u8,
+        roles: user.roles,
+    }))
 }
 
 pub fn generate_tokens(user: User) -> JwtTokens {
@@ -103,7 +154,7 @@ pub fn generate_tokens(user: User) -> JwtTokens {
     let access_token_id = Uuid::new_v4().to_string();
     let refresh_token_id = Uuid::new_v4().to_string();
     let access_token_exp = (time_now
-        + chrono::Duration::seconds(config.jwt_expire_access_token_seconds))
+        + chrono::Duration::seconds(config.jwt.expire_access_token_seconds))
     .timestamp() as usize;
 
     let access_claims = AccessClaims {
@@ -119,7 +170,7 @@ pub fn generate_tokens(user: User) -> JwtTokens {
         sub,
         jti: refresh_token_id,
         iat,
-        exp: (time_now + chrono::Duration::seconds(config.jwt_expire_refresh_token_seconds))
+        exp: (time_now + chrono::Duration::seconds(config.jwt.expire_refresh_token_seconds))
             .timestamp() as usize,
         prf: access_token_id,
         pex: access_token_exp,
@@ -136,14 +187,14 @@ pub fn generate_tokens(user: User) -> JwtTokens {
     let access_token = jsonwebtoken::encode(
         &jsonwebtoken::Header::default(),
         &access_claims,
-        &jsonwebtoken::EncodingKey::from_secret(config.jwt_secret.as_ref()),
+        &jsonwebtoken::EncodingKey::from_secret(config.jwt.secret.as_ref()),
     )
     .unwrap();
 
     let refresh_token = jsonwebtoken::encode(
         &jsonwebtoken::Header::default(),
         &refresh_claims,
-        &jsonwebtoken::EncodingKey::from_secret(config.jwt_secret.as_ref()),
+        &jsonwebtoken::EncodingKey::from_secret(config.jwt.secret.as_ref()),
     )
     .unwrap();
 
@@ -166,10 +217,10 @@ pub async fn validate_revoked<T: std::fmt::Debug + ClaimsMethods>(
     match redis_service::is_revoked(claims, state).await {
         Some(revoked) => {
             if revoked {
-                return Err(AuthError::WrongCredentials.into());
+                return Err(AuthError::RevokedToken.into());
             }
         }
-        None => return Err(StatusCode::INTERNAL_SERVER_ERROR.into()),
+        None => return Err(AuthError::RevocationUnavailable.into()),
     }
     Ok(())
 }
@@ -3,7 +3,7 @@ use std::sync::Arc;
 use axum::{
     async_trait,
     extract::{FromRef, FromRequestParts},
-    http::request::Parts,
+    http::{header, request::Parts},
     RequestPartsExt,
 };
 use axum_extra::{
@@ -17,6 +17,7 @@ use crate::application::{
     config,
     security::{self, auth_error::*},
     state::SharedState,
+    tls::ClientPrincipal,
 };
 
 use super::jwt_auth;
@@ -145,6 +146,14 @@ where
     type Rejection = ApiError;
 
     async fn from_request_parts(parts: &mut Parts, state: &S) -> Result<Self, Self::Rejection> {
+        // the requests without a token are authenticated by the client certificate if its principal is mapped
+        let principal = parts.extensions.get::<ClientPrincipal>();
+        if let (None, Some(principal)) = (parts.headers.get(header::AUTHORIZATION), principal) {
+            let shared_state: SharedState = Arc::from_ref(state);
+            if let Some(claims) = jwt_auth::principal_claims(principal, &shared_state).await? {
+                return Ok(claims);
+            }
+        }
         decode_token_from_request_part(parts, state).await
     }
 }
@@ -181,7 +190,7 @@ where
     let claims = decode_token::<T>(bearer.token())?;
 
     // check for revoked tokens if enabled by configuration
-    if config::get().jwt_enable_revoked_tokens {
+    if config::get().jwt.enable_revoked_tokens {
         let shared_state: SharedState = Arc::from_ref(state);
         jwt_auth::validate_revoked(&claims, &shared_state).await?
     }
@@ -191,12 +200,22 @@ where
 pub fn decode_token<T: for<'de> serde::Deserialize<'de>>(token: &str) -> Result<T, AuthError> {
     let config = config::get();
     let mut validation = jsonwebtoken::Validation::default();
-    validation.leeway = config.jwt_validation_leeway_seconds as u64;
-    let token_data = jsonwebtoken::decode::<T>(token, &config.jwt_keys.decoding, &validation)
-        .map_err(|_| {
-            tracing::error!("Invalid token: {}", token);
-            AuthError::WrongCredentials
-        })?;
+    validation.leeway = config.jwt.validation_leeway_seconds as u64;
+    let mut decoded = jsonwebtoken::decode::<T>(token, &config.jwt.keys.decoding, &validation);
+    // a token signed by the rotated secret
+    if let (Err(e), Some(keys)) = (&decoded, &config.jwt.previous_keys) {
+        if *e.kind() == jsonwebtoken::errors::ErrorKind::InvalidSignature {
+            decoded = jsonwebtoken::decode::<T>(token, &keys.decoding, &validation);
+        }
+    }
+    let token_data = decoded.map_err(|e| {
+        tracing::error!("Invalid token: {}, {}", token, e);
+        match e.kind() {
+            jsonwebtoken::errors::ErrorKind::ExpiredSignature => AuthError::ExpiredToken,
+            // malformed, wrongly signed or of another type
+            _ => AuthError::InvalidToken,
+        }
+    })?;
 
     Ok(token_data.claims)
 }
@@ -1,4 +1,5 @@
 pub mod auth_error;
 pub mod jwt_auth;
 pub mod jwt_claims;
+pub mod password;
 pub mod roles;
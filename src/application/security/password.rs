@@ -0,0 +1,20 @@
+use rand::{distributions::Alphanumeric, Rng};
+use sha2::{Digest, Sha256};
+
+use crate::application::app_const::USER_SALT_MAX_LENGTH;
+
+// the clients send this digest of the password, never the password itself
+pub fn hash_password(password: &str, salt: &str) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(password.as_bytes());
+    hasher.update(salt.as_bytes());
+    hex::encode(hasher.finalize())
+}
+
+pub fn generate_salt() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(USER_SALT_MAX_LENGTH as usize / 2)
+        .map(char::from)
+        .collect()
+}
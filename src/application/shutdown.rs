@@ -0,0 +1,185 @@
+// the graceful shutdown: the readiness flips first, the load balancers stop routing during the pre-stop delay,
+// then the in-flight requests are drained and the background jobs and the connections are stopped
+use std::{
+    fmt,
+    future::Future,
+    sync::{
+        atomic::{AtomicBool, Ordering},
+        Mutex,
+    },
+    time::{Duration, Instant},
+};
+use tokio::{sync::watch, task::JoinHandle};
+
+use super::{app_const::*, config::ShutdownConfig, state::AppState};
+
+// the service is ready from the bind of its listeners until the shutdown starts
+static READY: AtomicBool = AtomicBool::new(false);
+
+// the background jobs of the process, stopped once the requests are drained
+static JOBS: Mutex<Vec<(&'static str, JoinHandle<()>)>> = Mutex::new(Vec::new());
+
+pub fn is_ready() -> bool {
+    READY.load(Ordering::SeqCst)
+}
+
+pub fn set_ready(ready: bool) {
+    READY.store(ready, Ordering::SeqCst);
+}
+
+// spawns a background job, e.g. the watch of the configuration, stopped by the shutdown
+pub fn spawn_job(name: &'static str, job: impl Future<Output = ()> + Send + 'static) {
+    let mut jobs = JOBS.lock().unwrap();
+    jobs.retain(|(_, handle)| !handle.is_finished());
+    jobs.push((name, tokio::spawn(job)));
+}
+
+// aborts the running background jobs and waits for them, returns their names
+pub async fn stop_jobs() -> Vec<&'static str> {
+    let jobs = std::mem::take(&mut *JOBS.lock().unwrap());
+    let mut stopped = Vec::new();
+    for (name, handle) in jobs {
+        if handle.is_finished() {
+            continue;
+        }
+        handle.abort();
+        if let Err(e) = handle.await {
+            if !e.is_cancelled() {
+                tracing::error!("background job {} failed: {}", name, e);
+            }
+        }
+        stopped.push(name);
+    }
+    stopped
+}
+
+// the outcome of a shutdown, logged when it completes
+#[derive(Debug, PartialEq)]
+pub struct Summary {
+    pub elapsed: Duration,
+    // the connections open when the listeners stopped accepting, known if they were drained in time
+    pub connections: Option<usize>,
+    pub jobs: Vec<&'static str>,
+}
+
+impl fmt::Display for Summary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "shutdown completed in {:.1}s",
+            self.elapsed.as_secs_f64()
+        )?;
+        match self.connections {
+            Some(connections) => write!(f, ", {} connections drained", connections)?,
+            None => write!(
+                f,
+                ", the drain timed out, the open connections were aborted"
+            )?,
+        }
+        write!(f, ", {} background jobs stopped", self.jobs.len())?;
+        if !self.jobs.is_empty() {
+            write!(f, " ({})", self.jobs.join(", "))?;
+        }
+        Ok(())
+    }
+}
+
+// coordinates the shutdown of the listeners
+pub struct Shutdown {
+    stop: watch::Sender<()>,
+    deadline: watch::Sender<()>,
+}
+
+impl Default for Shutdown {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Shutdown {
+    pub fn new() -> Self {
+        Self {
+            stop: watch::channel(()).0,
+            deadline: watch::channel(()).0,
+        }
+    }
+
+    // completes when the listeners stop accepting connections
+    pub fn stopped(&self) -> impl Future<Output = ()> + Send + 'static {
+        changed(&self.stop)
+    }
+
+    // completes when the drain times out, the open connections are aborted then
+    pub fn deadline(&self) -> impl Future<Output = ()> + Send + 'static {
+        changed(&self.deadline)
+    }
+
+    // runs the shutdown of the servers, the tasks of `listener::serve`,
+    // then closes the connections of the repositories once no request holds them
+    pub async fn run(
+        self,
+        config: &ShutdownConfig,
+        servers: Vec<JoinHandle<usize>>,
+        state: &AppState,
+    ) -> Summary {
+        let started = Instant::now();
+        set_ready(false);
+        if config.pre_stop_delay_seconds > 0 {
+            tracing::info!("not ready, stopping in {}s", config.pre_stop_delay_seconds);
+            tokio::time::sleep(Duration::from_secs(config.pre_stop_delay_seconds)).await;
+        }
+
+        self.stop.send_replace(());
+        let drain = Duration::from_secs(config.drain_timeout_seconds);
+        let mut servers = servers;
+        let (mut drained, mut connections) = (0, 0);
+        let timed_out = tokio::time::timeout(drain, async {
+            while drained < servers.len() {
+                connections += (&mut servers[drained]).await.unwrap_or_default();
+                drained += 1;
+            }
+        })
+        .await
+        .is_err();
+        let connections = if timed_out {
+            tracing::warn!(
+                "the in-flight requests were not drained in {}s",
+                config.drain_timeout_seconds
+            );
+            // the servers abort their connections and wait for them
+            self.deadline.send_replace(());
+            for server in servers.drain(drained..) {
+                let _ = server.await;
+            }
+            None
+        } else {
+            Some(connections)
+        };
+
+        let jobs = stop_jobs().await;
+
+        // the aborted requests returned their pooled connections, the close waits for the others
+        let close = Duration::from_secs(SHUTDOWN_CLOSE_TIMEOUT_SECONDS);
+        let closed = tokio::time::timeout(close, async {
+            state.user_repo.close().await;
+            state.revocation_repo.close().await;
+        })
+        .await;
+        if closed.is_err() {
+            tracing::warn!("the connections were not closed in {}s", close.as_secs());
+        }
+
+        Summary {
+            elapsed: started.elapsed(),
+            connections,
+            jobs,
+        }
+    }
+}
+
+fn changed(sender: &watch::Sender<()>) -> impl Future<Output = ()> + Send + 'static {
+    let mut receiver = sender.subscribe();
+    async move {
+        let _ = receiver.changed().await;
+    }
+}
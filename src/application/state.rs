@@ -1,10 +1,12 @@
-use sqlx::{Pool, Postgres};
 use std::sync::Arc;
-use tokio::sync::Mutex;
+
+use crate::application::repository::{
+    revocation_repo::RevocationRepository, user_repo::UserRepository,
+};
 
 pub type SharedState = Arc<AppState>;
 
 pub struct AppState {
-    pub pgpool: Pool<Postgres>,
-    pub redis: Mutex<redis::aio::MultiplexedConnection>,
+    pub user_repo: Arc<dyn UserRepository>,
+    pub revocation_repo: Arc<dyn RevocationRepository>,
 }
@@ -0,0 +1,237 @@
+// the https termination: rustls with the certificates reloaded on change,
+// HTTP/2 negotiated by ALPN and the client certificates of mutual TLS mapped to principals
+use arc_swap::ArcSwap;
+use axum::{
+    http::{header, HeaderMap, StatusCode, Uri},
+    response::{IntoResponse, Redirect, Response},
+    Router,
+};
+use std::{
+    fmt,
+    fs::File,
+    io::{self, BufReader},
+    path::PathBuf,
+    sync::Arc,
+    time::{Duration, SystemTime},
+};
+use tokio::net::TcpStream;
+use tokio_rustls::{
+    rustls::{
+        crypto::ring,
+        pki_types::{CertificateDer, PrivateKeyDer},
+        server::WebPkiClientVerifier,
+        RootCertStore, ServerConfig,
+    },
+    server::TlsStream,
+    TlsAcceptor,
+};
+use x509_parser::extensions::GeneralName;
+
+use super::{
+    app_const::*,
+    config::{ClientAuth, TlsConfig},
+};
+
+// the principal of a verified client certificate, an extension of the requests of its connection
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClientPrincipal(pub String);
+
+#[derive(Debug)]
+pub enum TlsError {
+    // a PEM file could not be read or holds no usable item
+    File(String),
+    // the certificates or the key are rejected
+    Invalid(String),
+}
+
+impl fmt::Display for TlsError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TlsError::File(e) => write!(f, "TLS file error: {}", e),
+            TlsError::Invalid(e) => write!(f, "invalid TLS configuration: {}", e),
+        }
+    }
+}
+
+impl std::error::Error for TlsError {}
+
+// the server configuration of the https listener, swapped by the certificate reloads
+#[derive(Clone)]
+pub struct TlsServer {
+    config: Arc<ArcSwap<ServerConfig>>,
+}
+
+impl TlsServer {
+    pub fn load(tls: &TlsConfig) -> Result<Self, TlsError> {
+        Ok(Self {
+            config: Arc::new(ArcSwap::from_pointee(server_config(tls)?)),
+        })
+    }
+
+    // the new handshakes use the reloaded certificates, the open connections keep the previous ones
+    pub fn reload(&self, tls: &TlsConfig) -> Result<(), TlsError> {
+        self.config.store(Arc::new(server_config(tls)?));
+        Ok(())
+    }
+
+    // the handshake of a new connection, with the principal of its client certificate
+    pub async fn accept(
+        &self,
+        stream: TcpStream,
+    ) -> io::Result<(TlsStream<TcpStream>, Option<ClientPrincipal>)> {
+        let acceptor = TlsAcceptor::from(self.config.load_full());
+        let handshake = Duration::from_secs(TLS_HANDSHAKE_TIMEOUT_SECONDS);
+        let stream = tokio::time::timeout(handshake, acceptor.accept(stream))
+            .await
+            .map_err(|_| io::Error::new(io::ErrorKind::TimedOut, "TLS handshake timed out"))??;
+        let principal = stream
+            .get_ref()
+            .1
+            .peer_certificates()
+            .and_then(|certs| certs.first())
+            .and_then(|cert| client_principal(cert));
+        Ok((stream, principal))
+    }
+}
+
+fn server_config(tls: &TlsConfig) -> Result<ServerConfig, TlsError> {
+    let provider = Arc::new(ring::default_provider());
+    let certs = read_certs(tls.cert_path.as_deref().unwrap_or_default())?;
+    let key = read_key(tls.key_path.as_deref().unwrap_or_default())?;
+
+    let builder = ServerConfig::builder_with_provider(provider.clone())
+        .with_safe_default_protocol_versions()
+        .map_err(|e| TlsError::Invalid(e.to_string()))?;
+    let builder = match tls.client_auth {
+        ClientAuth::None => builder.with_no_client_auth(),
+        client_auth => {
+            let ca_path = tls.client_ca_path.as_deref().unwrap_or_default();
+            let mut roots = RootCertStore::empty();
+            for cert in read_certs(ca_path)? {
+                roots
+                    .add(cert)
+                    .map_err(|e| TlsError::Invalid(format!("{}: {}", ca_path, e)))?;
+            }
+            let verifier = WebPkiClientVerifier::builder_with_provider(Arc::new(roots), provider);
+            let verifier = match client_auth {
+                ClientAuth::Optional => verifier.allow_unauthenticated(),
+                _ => verifier,
+            };
+            let verifier = verifier
+                .build()
+                .map_err(|e| TlsError::Invalid(format!("{}: {}", ca_path, e)))?;
+            builder.with_client_cert_verifier(verifier)
+        }
+    };
+    let mut config = builder
+        .with_single_cert(certs, key)
+        .map_err(|e| TlsError::Invalid(e.to_string()))?;
+    // HTTP/2 is preferred by the clients supporting it
+    config.alpn_protocols = vec![b"h2".to_vec(), b"http/1.1".to_vec()];
+    Ok(config)
+}
+
+fn read_certs(path: &str) -> Result<Vec<CertificateDer<'static>>, TlsError> {
+    let file = File::open(path).map_err(|e| TlsError::File(format!("{}: {}", path, e)))?;
+    let certs = rustls_pemfile::certs(&mut BufReader::new(file))
+        .collect::<Result<Vec<_>, _>>()
+        .map_err(|e| TlsError::File(format!("{}: {}", path, e)))?;
+    if certs.is_empty() {
+        return Err(TlsError::File(format!("{}: no certificate found", path)));
+    }
+    Ok(certs)
+}
+
+fn read_key(path: &str) -> Result<PrivateKeyDer<'static>, TlsError> {
+    let file = File::open(path).map_err(|e| TlsError::File(format!("{}: {}", path, e)))?;
+    rustls_pemfile::private_key(&mut BufReader::new(file))
+        .map_err(|e| TlsError::File(format!("{}: {}", path, e)))?
+        .ok_or_else(|| TlsError::File(format!("{}: no private key found", path)))
+}
+
+// the common name of the subject, falls back to the first DNS name, email or uri of the alternative names
+pub fn client_principal(cert: &[u8]) -> Option<ClientPrincipal> {
+    let (_, cert) = x509_parser::parse_x509_certificate(cert).ok()?;
+    let common_name = cert
+        .subject()
+        .iter_common_name()
+        .find_map(|name| name.as_str().ok());
+    if let Some(name) = common_name {
+        return Some(ClientPrincipal(name.to_string()));
+    }
+    let alternative_names = cert.subject_alternative_name().ok()??;
+    alternative_names
+        .value
+        .general_names
+        .iter()
+        .find_map(|name| match name {
+            GeneralName::DNSName(name) | GeneralName::RFC822Name(name) | GeneralName::URI(name) => {
+                Some(ClientPrincipal(name.to_string()))
+            }
+            _ => None,
+        })
+}
+
+// reloads the certificates on a change of their files,
+// invalid certificates are logged and the current ones are kept
+pub async fn watch(server: TlsServer, tls: TlsConfig) {
+    let files: Vec<PathBuf> = [&tls.cert_path, &tls.key_path, &tls.client_ca_path]
+        .into_iter()
+        .flatten()
+        .map(PathBuf::from)
+        .collect();
+    let modified_times = || -> Vec<Option<SystemTime>> {
+        files
+            .iter()
+            .map(|file| std::fs::metadata(file).and_then(|m| m.modified()).ok())
+            .collect()
+    };
+    let mut modified = modified_times();
+    let mut interval = tokio::time::interval(Duration::from_secs(CONFIG_WATCH_INTERVAL_SECONDS));
+    loop {
+        interval.tick().await;
+        let times = modified_times();
+        if times == modified {
+            continue;
+        }
+        modified = times;
+        match server.reload(&tls) {
+            Ok(()) => {
+                tracing::info!("TLS certificates reloaded");
+                metrics::counter!(METRIC_TLS_RELOADS, "result" => "success").increment(1);
+            }
+            Err(e) => {
+                tracing::error!(
+                    "TLS certificates reload failed, the current ones are kept: {}",
+                    e
+                );
+                metrics::counter!(METRIC_TLS_RELOADS, "result" => "failure").increment(1);
+            }
+        }
+    }
+}
+
+// the routes of the plain http listener, redirecting every request to https
+pub fn redirect_routes(https_port: u16) -> Router {
+    Router::new().fallback(move |headers: HeaderMap, uri: Uri| async move {
+        redirect(https_port, &headers, &uri)
+    })
+}
+
+fn redirect(https_port: u16, headers: &HeaderMap, uri: &Uri) -> Response {
+    let Some(host) = headers.get(header::HOST).and_then(|h| h.to_str().ok()) else {
+        return StatusCode::BAD_REQUEST.into_response();
+    };
+    // the host without the port of the http listener, an ipv6 address keeps its brackets
+    let host = match host.strip_prefix('[') {
+        Some(rest) => format!("[{}]", rest.split(']').next().unwrap_or_default()),
+        None => host.split(':').next().unwrap_or_default().to_string(),
+    };
+    let port = if https_port == 443 {
+        String::new()
+    } else {
+        format!(":{}", https_port)
+    };
+    let path = uri.path_and_query().map(|p| p.as_str()).unwrap_or("/");
+    Redirect::permanent(&format!("https://{}{}{}", host, port, path)).into_response()
+}
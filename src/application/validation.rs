@@ -0,0 +1,100 @@
+use axum::{
+    async_trait,
+    extract::{rejection::JsonRejection, FromRequest, Request},
+    http::StatusCode,
+    Json,
+};
+use serde::de::DeserializeOwned;
+use validator::{Validate, ValidationError, ValidationErrors, ValidationErrorsKind};
+
+use super::api_error::{ApiError, ErrorCode, FieldError};
+
+// a Json extractor that runs the declared validation rules of the payload
+pub struct ValidatedJson<T>(pub T);
+
+#[async_trait]
+impl<T, S> FromRequest<S> for ValidatedJson<T>
+where
+    T: DeserializeOwned + Validate,
+    S: Send + Sync,
+{
+    type Rejection = ApiError;
+
+    async fn from_request(request: Request, state: &S) -> Result<Self, Self::Rejection> {
+        let Json(value) = Json::<T>::from_request(request, state)
+            .await
+            .map_err(json_rejection)?;
+        value.validate().map_err(validation_failed)?;
+        Ok(ValidatedJson(value))
+    }
+}
+
+fn json_rejection(rejection: JsonRejection) -> ApiError {
+    // a well formed json not matching the expected structure is a validation failure
+    let error_code = match rejection {
+        JsonRejection::JsonDataError(_) => ErrorCode::ValidationFailed,
+        _ => ErrorCode::BadRequest,
+    };
+    ApiError::new(rejection.status(), error_code, rejection.body_text())
+}
+
+pub fn validation_failed(errors: ValidationErrors) -> ApiError {
+    let mut field_errors = Vec::new();
+    collect_field_errors("", &errors, &mut field_errors);
+    field_errors.sort_by(|a, b| a.field.cmp(&b.field));
+    ApiError::new(
+        StatusCode::UNPROCESSABLE_ENTITY,
+        ErrorCode::ValidationFailed,
+        "Request validation failed",
+    )
+    .with_field_errors(field_errors)
+}
+
+fn collect_field_errors(prefix: &str, errors: &ValidationErrors, out: &mut Vec<FieldError>) {
+    for (field, kind) in errors.errors() {
+        let path = |field: &str| match prefix {
+            "" => field.to_string(),
+            _ => format!("{}.{}", prefix, field),
+        };
+        let field = path(field);
+        match kind {
+            ValidationErrorsKind::Field(errors) => {
+                out.extend(errors.iter().map(|error| FieldError {
+                    // the struct level rules name the field in their params
+                    field: match error.params.get("field").and_then(|value| value.as_str()) {
+                        Some(name) if field.ends_with("__all__") => path(name),
+                        _ => field.clone(),
+                    },
+                    code: error.code.to_string(),
+                    message: error_message(error),
+                }))
+            }
+            ValidationErrorsKind::Struct(errors) => collect_field_errors(&field, errors, out),
+            ValidationErrorsKind::List(items) => {
+                for (index, errors) in items {
+                    collect_field_errors(&format!("{}[{}]", field, index), errors, out);
+                }
+            }
+        }
+    }
+}
+
+fn error_message(error: &ValidationError) -> String {
+    if let Some(message) = &error.message {
+        return message.to_string();
+    }
+    let param = |name: &str| error.params.get(name).map(|value| value.to_string());
+    match error.code.as_ref() {
+        "length" => match (param("min"), param("max")) {
+            (Some(min), Some(max)) => format!("length must be between {} and {}", min, max),
+            (Some(min), None) => format!("length must be at least {}", min),
+            (None, Some(max)) => format!("length must be at most {}", max),
+            _ => "invalid length".to_string(),
+        },
+        "email" => "invalid email address".to_string(),
+        code => format!("invalid value ({})", code),
+    }
+}
+
+// the reusable rules of the request types
+pub use axum_web_types::validation::*;
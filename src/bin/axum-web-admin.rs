@@ -0,0 +1,180 @@
+use axum_web::{
+    application::{
+        admin, app,
+        config::{self, Config, ConfigArgs},
+        state::{AppState, SharedState},
+    },
+    infrastructure::database::DatabasePool,
+};
+use clap::{Parser, Subcommand};
+use std::sync::Arc;
+use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+
+// operational tasks of the axum-web service, configured the same way as the service
+#[derive(Parser)]
+#[command(
+    name = "axum-web-admin",
+    version,
+    about = "Operational tasks of the axum-web service"
+)]
+struct Cli {
+    #[command(subcommand)]
+    command: Command,
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Apply the pending database migrations
+    Migrate,
+    /// Create an active user with the admin role
+    CreateAdmin {
+        #[arg(long)]
+        username: String,
+        #[arg(long)]
+        email: String,
+        /// The password, better passed by the environment than the shell history
+        #[arg(long, env = "ADMIN_PASSWORD", hide_env_values = true)]
+        password: String,
+        /// The salt of the password digest sent on login, random if not given
+        #[arg(long, env = "ADMIN_PASSWORD_SALT")]
+        password_salt: Option<String>,
+    },
+    /// Add the admin role to a user
+    Promote {
+        /// The id or the username of the user
+        user: String,
+    },
+    /// Set a new password of a user and revoke the tokens of the user
+    ResetPassword {
+        /// The id or the username of the user
+        user: String,
+        #[arg(long, env = "ADMIN_PASSWORD", hide_env_values = true)]
+        password: String,
+        /// The salt of the password digest sent on login, random if not given
+        #[arg(long, env = "ADMIN_PASSWORD_SALT")]
+        password_salt: Option<String>,
+    },
+    /// Revoke the tokens issued until now
+    RevokeTokens {
+        /// The id or the username of the user
+        #[arg(long, conflicts_with = "all", required_unless_present = "all")]
+        user: Option<String>,
+        /// The tokens of all the users
+        #[arg(long)]
+        all: bool,
+    },
+    /// Remove the expired tokens from the list of revoked tokens
+    Cleanup,
+    /// Generate a random JWT_SECRET
+    GenerateJwtSecret,
+    /// Print the effective configuration with the secrets masked
+    Config,
+}
+
+#[tokio::main]
+async fn main() {
+    // the logs go to stderr, the output of the commands to stdout
+    let filter_layer = tracing_subscriber::EnvFilter::try_from_default_env()
+        .unwrap_or_else(|_| "axum_web=warn".into());
+    let fmt_layer = tracing_subscriber::fmt::layer()
+        .compact()
+        .with_target(false)
+        .with_writer(std::io::stderr);
+    tracing_subscriber::registry()
+        .with(filter_layer)
+        .with(fmt_layer)
+        .init();
+
+    let cli = Cli::parse();
+    if let Err(e) = run(cli.command, &cli.config).await {
+        eprintln!("error: {}", e);
+        std::process::exit(1);
+    }
+}
+
+async fn run(command: Command, args: &ConfigArgs) -> Result<(), Box<dyn std::error::Error>> {
+    if let Command::GenerateJwtSecret = command {
+        println!("JWT_SECRET = {}", admin::generate_jwt_secret());
+        return Ok(());
+    }
+
+    let config = &config::try_load_with(args).await?;
+    match command {
+        Command::Migrate => {
+            DatabasePool::connect(config).await?.migrate().await?;
+            println!("migrations applied");
+        }
+        Command::CreateAdmin {
+            username,
+            email,
+            password,
+            password_salt,
+        } => {
+            let state = state(config).await;
+            let user = admin::create_admin(
+                &state,
+                &username,
+                &email,
+                &password,
+                password_salt.as_deref(),
+            )
+            .await?;
+            // the clients log in with the digest of the password and this salt
+            println!(
+                "admin created: {} {}, password salt: {}",
+                user.id, user.username, user.password_salt
+            );
+        }
+        Command::Promote { user } => {
+            let state = state(config).await;
+            let user = admin::promote(&state, &user).await?;
+            println!(
+                "user promoted: {} {}, roles: {}",
+                user.id, user.username, user.roles
+            );
+        }
+        Command::ResetPassword {
+            user,
+            password,
+            password_salt,
+        } => {
+            let state = state(config).await;
+            let user =
+                admin::reset_password(&state, &user, &password, password_salt.as_deref()).await?;
+            println!(
+                "password reset: {} {}, password salt: {}",
+                user.id, user.username, user.password_salt
+            );
+        }
+        Command::RevokeTokens {
+            user: Some(user), ..
+        } => {
+            let state = state(config).await;
+            let user = admin::revoke_user(&state, &user).await?;
+            println!("tokens revoked: {} {}", user.id, user.username);
+        }
+        Command::RevokeTokens { user: None, .. } => {
+            let state = state(config).await;
+            admin::revoke_all(&state).await?;
+            println!("tokens revoked: all users");
+        }
+        Command::Cleanup => {
+            let state = state(config).await;
+            let deleted = admin::cleanup(&state).await?;
+            println!("expired revoked tokens deleted: {}", deleted);
+        }
+        Command::Config => print!("{}", config.masked()),
+        Command::GenerateJwtSecret => unreachable!(),
+    }
+    Ok(())
+}
+
+// the migrations are left to the migrate command
+async fn state(config: &Config) -> SharedState {
+    Arc::new(AppState {
+        user_repo: app::user_repo(config, false).await,
+        revocation_repo: app::revocation_repo(config).await,
+    })
+}
@@ -15,6 +15,11 @@ pub struct User {
     pub roles: String,
     pub created_at: Option<NaiveDateTime>,
     pub updated_at: Option<NaiveDateTime>,
+    // incremented on every update, exposed only as the ETag
+    #[serde(skip)]
+    pub version: i64,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<NaiveDateTime>,
 }
 
 impl User {
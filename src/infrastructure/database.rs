@@ -0,0 +1,134 @@
+use sqlx::{
+    migrate::{Migrate, MigrateError, Migrator},
+    Pool, Postgres,
+};
+use std::collections::HashSet;
+
+use super::postgres;
+use crate::application::config::{Config, DatabaseBackend};
+
+// a pool of the configured database, used for the schema migrations and the connectivity checks
+pub enum DatabasePool {
+    Postgres(Pool<Postgres>),
+    #[cfg(feature = "sqlite")]
+    Sqlite(Pool<sqlx::Sqlite>),
+}
+
+// a migration known to the binary and whether it is applied to the database
+#[derive(Debug, Clone, PartialEq)]
+pub struct MigrationStatus {
+    pub version: i64,
+    pub description: String,
+    pub applied: bool,
+}
+
+impl DatabasePool {
+    pub async fn connect(config: &Config) -> Result<Self, sqlx::Error> {
+        match config.database_backend() {
+            DatabaseBackend::Postgres => {
+                Ok(DatabasePool::Postgres(postgres::connect(config).await?))
+            }
+            #[cfg(feature = "sqlite")]
+            DatabaseBackend::Sqlite => {
+                Ok(DatabasePool::Sqlite(super::sqlite::connect(config).await?))
+            }
+            #[cfg(not(feature = "sqlite"))]
+            DatabaseBackend::Sqlite => Err(sqlx::Error::Configuration(
+                "SQLite support is not enabled, build with the `sqlite` feature".into(),
+            )),
+        }
+    }
+
+    pub async fn ping(&self) -> Result<(), sqlx::Error> {
+        match self {
+            DatabasePool::Postgres(pool) => sqlx::query("SELECT 1").execute(pool).await.map(|_| ()),
+            #[cfg(feature = "sqlite")]
+            DatabasePool::Sqlite(pool) => sqlx::query("SELECT 1").execute(pool).await.map(|_| ()),
+        }
+    }
+
+    // applies the pending migrations
+    pub async fn migrate(&self) -> Result<(), MigrateError> {
+        match self {
+            DatabasePool::Postgres(pool) => postgres::MIGRATOR.run(pool).await,
+            #[cfg(feature = "sqlite")]
+            DatabasePool::Sqlite(pool) => super::sqlite::MIGRATOR.run(pool).await,
+        }
+    }
+
+    // reverts the migrations applied after the target version, by default the last applied one,
+    // returns the reverted versions
+    pub async fn undo(&self, target: Option<i64>) -> Result<Vec<i64>, MigrateError> {
+        let applied: Vec<i64> = self
+            .migration_status()
+            .await?
+            .into_iter()
+            .filter(|migration| migration.applied)
+            .map(|migration| migration.version)
+            .collect();
+        let target = match (target, applied.as_slice()) {
+            (Some(target), _) => target,
+            (None, []) => return Ok(Vec::new()),
+            (None, [_]) => 0,
+            (None, [.., previous, _]) => *previous,
+        };
+
+        match self {
+            DatabasePool::Postgres(pool) => postgres::MIGRATOR.undo(pool, target).await?,
+            #[cfg(feature = "sqlite")]
+            DatabasePool::Sqlite(pool) => super::sqlite::MIGRATOR.undo(pool, target).await?,
+        }
+        Ok(applied.into_iter().rev().filter(|v| *v > target).collect())
+    }
+
+    // read only, no migrations are applied if the migrations table does not exist yet
+    pub async fn migration_status(&self) -> Result<Vec<MigrationStatus>, MigrateError> {
+        match self {
+            DatabasePool::Postgres(pool) => {
+                let mut connection = pool.acquire().await?;
+                let exists: bool =
+                    sqlx::query_scalar("SELECT to_regclass('_sqlx_migrations') IS NOT NULL")
+                        .fetch_one(&mut *connection)
+                        .await?;
+                migration_status(&postgres::MIGRATOR, &mut *connection, exists).await
+            }
+            #[cfg(feature = "sqlite")]
+            DatabasePool::Sqlite(pool) => {
+                let mut connection = pool.acquire().await?;
+                let exists: bool = sqlx::query_scalar(
+                    "SELECT EXISTS (SELECT 1 FROM sqlite_master \
+                     WHERE type = 'table' AND name = '_sqlx_migrations')",
+                )
+                .fetch_one(&mut *connection)
+                .await?;
+                migration_status(&super::sqlite::MIGRATOR, &mut *connection, exists).await
+            }
+        }
+    }
+}
+
+async fn migration_status(
+    migrator: &Migrator,
+    connection: &mut impl Migrate,
+    table_exists: bool,
+) -> Result<Vec<MigrationStatus>, MigrateError> {
+    let applied: HashSet<i64> = if table_exists {
+        connection
+            .list_applied_migrations()
+            .await?
+            .into_iter()
+            .map(|migration| migration.version)
+            .collect()
+    } else {
+        HashSet::new()
+    };
+    Ok(migrator
+        .iter()
+        .filter(|migration| !migration.migration_type.is_down_migration())
+        .map(|migration| MigrationStatus {
+            version: migration.version,
+            description: migration.description.to_string(),
+            applied: applied.contains(&migration.version),
+        })
+        .collect())
+}
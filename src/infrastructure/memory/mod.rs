@@ -0,0 +1,4 @@
+mod revocation_repo;
+mod user_repo;
+pub use revocation_repo::InMemoryRevocationRepository;
+pub use user_repo::InMemoryUserRepository;
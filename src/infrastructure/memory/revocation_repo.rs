@@ -0,0 +1,86 @@
+use axum::async_trait;
+use std::collections::HashMap;
+use tokio::sync::RwLock;
+
+use crate::application::repository::revocation_repo::{RevocationRepository, RevocationResult};
+
+#[derive(Default)]
+struct RevocationData {
+    global_revoke_before: Option<usize>,
+    user_revoke_before: HashMap<String, usize>,
+    revoked_tokens: HashMap<String, usize>,
+}
+
+// keeps revoked tokens in memory of the process,
+// intended for tests and single instance deployments without redis
+#[derive(Default)]
+pub struct InMemoryRevocationRepository {
+    data: RwLock<RevocationData>,
+}
+
+impl InMemoryRevocationRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+#[async_trait]
+impl RevocationRepository for InMemoryRevocationRepository {
+    async fn set_global_revoke_before(&self, timestamp: usize) -> RevocationResult<()> {
+        self.data.write().await.global_revoke_before = Some(timestamp);
+        Ok(())
+    }
+
+    async fn get_global_revoke_before(&self) -> RevocationResult<Option<usize>> {
+        Ok(self.data.read().await.global_revoke_before)
+    }
+
+    async fn set_user_revoke_before(
+        &self,
+        user_id: &str,
+        timestamp: usize,
+    ) -> RevocationResult<()> {
+        self.data
+            .write()
+            .await
+            .user_revoke_before
+            .insert(user_id.to_string(), timestamp);
+        Ok(())
+    }
+
+    async fn get_user_revoke_before(&self, user_id: &str) -> RevocationResult<Option<usize>> {
+        Ok(self
+            .data
+            .read()
+            .await
+            .user_revoke_before
+            .get(user_id)
+            .copied())
+    }
+
+    async fn add_revoked_token(&self, jti: &str, exp: usize) -> RevocationResult<()> {
+        self.data
+            .write()
+            .await
+            .revoked_tokens
+            .insert(jti.to_string(), exp);
+        Ok(())
+    }
+
+    async fn is_token_revoked(&self, jti: &str) -> RevocationResult<bool> {
+        Ok(self.data.read().await.revoked_tokens.contains_key(jti))
+    }
+
+    async fn get_revoked_tokens(&self) -> RevocationResult<HashMap<String, usize>> {
+        Ok(self.data.read().await.revoked_tokens.clone())
+    }
+
+    async fn delete_revoked_token(&self, jti: &str) -> RevocationResult<()> {
+        self.data.write().await.revoked_tokens.remove(jti);
+        Ok(())
+    }
+
+    async fn revoked_tokens_count(&self) -> RevocationResult<usize> {
+        Ok(self.data.read().await.revoked_tokens.len())
+    }
+}
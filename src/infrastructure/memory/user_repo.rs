@@ -0,0 +1,204 @@
+use axum::async_trait;
+use chrono::Utc;
+use std::{cmp::Ordering, collections::HashMap};
+use tokio::sync::RwLock;
+use uuid::Uuid;
+
+use crate::{
+    application::repository::{
+        repository_error::RepositoryError,
+        user_repo::{SortOrder, UserListQuery, UserPage, UserRepository, UserSortKey},
+        RepositoryResult,
+    },
+    domain::models::user::User,
+};
+
+// keeps users in memory, mirrors the behaviour of the postgres repository
+// intended for tests and running the service without a database
+#[derive(Default)]
+pub struct InMemoryUserRepository {
+    users: RwLock<HashMap<Uuid, User>>,
+}
+
+impl InMemoryUserRepository {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_users(users: Vec<User>) -> Self {
+        Self {
+            users: RwLock::new(users.into_iter().map(|user| (user.id, user)).collect()),
+        }
+    }
+}
+
+// the email is unique among the users that are not deleted
+fn email_taken(users: &HashMap<Uuid, User>, email: &str, except_id: Uuid) -> bool {
+    users
+        .values()
+        .any(|u| u.email == email && u.id != except_id && u.deleted_at.is_none())
+}
+
+#[async_trait]
+impl UserRepository for InMemoryUserRepository {
+    async fn get_all(&self) -> RepositoryResult<Vec<User>> {
+        Ok(self
+            .users
+            .read()
+            .await
+            .values()
+            .filter(|u| u.deleted_at.is_none())
+            .cloned()
+            .collect())
+    }
+
+    async fn list(&self, query: &UserListQuery) -> RepositoryResult<UserPage> {
+        let users = self.users.read().await;
+        let mut filtered: Vec<&User> = users
+            .values()
+            .filter(|user| user.deleted_at.is_none() && query.filter.matches(user))
+            .collect();
+        let total_count = query.include_total.then_some(filtered.len() as i64);
+
+        let key = |user: &User| (query.sort.value(user), user.id);
+        let in_order = |ordering: Ordering| match query.order {
+            SortOrder::Asc => ordering,
+            SortOrder::Desc => ordering.reverse(),
+        };
+        filtered.sort_by(|a, b| in_order(key(a).partial_cmp(&key(b)).unwrap_or(Ordering::Equal)));
+
+        let page = filtered
+            .into_iter()
+            .filter(|user| match &query.cursor {
+                Some(cursor) => {
+                    let ordering = key(user)
+                        .partial_cmp(&(cursor.value.clone(), cursor.id))
+                        .unwrap_or(Ordering::Equal);
+                    in_order(ordering) == Ordering::Greater
+                }
+                None => true,
+            })
+            .take(query.limit as usize + 1)
+            .cloned()
+            .collect();
+
+        Ok(UserPage::new(page, query, total_count))
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> RepositoryResult<User> {
+        self.users
+            .read()
+            .await
+            .get(&id)
+            .filter(|u| u.deleted_at.is_none())
+            .cloned()
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn get_by_username(&self, username: &str) -> RepositoryResult<User> {
+        self.users
+            .read()
+            .await
+            .values()
+            .find(|u| u.username == username && u.deleted_at.is_none())
+            .cloned()
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn get_by_email(&self, email: &str) -> RepositoryResult<User> {
+        self.users
+            .read()
+            .await
+            .values()
+            .find(|u| u.email == email && u.deleted_at.is_none())
+            .cloned()
+            .ok_or(RepositoryError::NotFound)
+    }
+
+    async fn add(&self, user: User) -> RepositoryResult<User> {
+        let mut users = self.users.write().await;
+        if users.contains_key(&user.id) {
+            return Err(RepositoryError::UniqueViolation(Some("id".to_string())));
+        }
+        if email_taken(&users, &user.email, user.id) {
+            return Err(RepositoryError::UniqueViolation(Some("email".to_string())));
+        }
+
+        let time_now = Utc::now().naive_utc();
+        let user = User {
+            active: true,
+            created_at: Some(time_now),
+            updated_at: Some(time_now),
+            version: 1,
+            deleted_at: None,
+            ..user
+        };
+        users.insert(user.id, user.clone());
+        Ok(user)
+    }
+
+    async fn update(&self, id: Uuid, user: User, version: i64) -> RepositoryResult<User> {
+        let mut users = self.users.write().await;
+        let existing = users
+            .get(&id)
+            .filter(|existing| existing.version == version && existing.deleted_at.is_none())
+            .ok_or(RepositoryError::NotFound)?;
+        let created_at = existing.created_at;
+        if email_taken(&users, &user.email, id) {
+            return Err(RepositoryError::UniqueViolation(Some("email".to_string())));
+        }
+
+        // the primary key is never updated
+        let user = User {
+            id,
+            created_at,
+            updated_at: Some(Utc::now().naive_utc()),
+            version: version + 1,
+            deleted_at: None,
+            ..user
+        };
+        users.insert(id, user.clone());
+        Ok(user)
+    }
+
+    async fn delete(&self, id: Uuid, version: i64) -> RepositoryResult<bool> {
+        let mut users = self.users.write().await;
+        match users.get_mut(&id) {
+            Some(user) if user.version == version && user.deleted_at.is_none() => {
+                user.deleted_at = Some(Utc::now().naive_utc());
+                user.version += 1;
+                Ok(true)
+            }
+            _ => Ok(false),
+        }
+    }
+
+    async fn restore(&self, id: Uuid) -> RepositoryResult<User> {
+        let mut users = self.users.write().await;
+        let Some(email) = users
+            .get(&id)
+            .filter(|user| user.deleted_at.is_some())
+            .map(|user| user.email.clone())
+        else {
+            return Err(RepositoryError::NotFound);
+        };
+        // the email may have been taken while the user was deleted
+        if email_taken(&users, &email, id) {
+            return Err(RepositoryError::UniqueViolation(Some("email".to_string())));
+        }
+        let user = users.get_mut(&id).ok_or(RepositoryError::NotFound)?;
+        user.deleted_at = None;
+        user.updated_at = Some(Utc::now().naive_utc());
+        user.version += 1;
+        Ok(user.clone())
+    }
+
+    async fn purge(&self, id: Uuid) -> RepositoryResult<bool> {
+        let mut users = self.users.write().await;
+        if users.get(&id).is_some_and(|user| user.deleted_at.is_some()) {
+            users.remove(&id);
+            return Ok(true);
+        }
+        Ok(false)
+    }
+}
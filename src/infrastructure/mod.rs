@@ -1,2 +1,8 @@
+pub mod database;
+pub mod memory;
 pub mod postgres;
 pub mod redis;
+#[cfg(feature = "sqlite")]
+pub mod sqlite;
+mod user_query;
+pub mod vault;
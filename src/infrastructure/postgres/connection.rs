@@ -1,15 +1,18 @@
-use sqlx::{postgres::PgPoolOptions, Pool, Postgres};
+use sqlx::{
+    postgres::{PgConnectOptions, PgPoolOptions},
+    Pool, Postgres,
+};
 
-use crate::application::config::Config;
+use crate::application::{
+    config::{self, Config},
+    shutdown,
+};
 
 pub async fn pgpool(config: &Config) -> Pool<Postgres> {
-    match PgPoolOptions::new()
-        .max_connections(config.postgres_connection_pool)
-        .connect(&config.postgres_url())
-        .await
-    {
+    match connect(config).await {
         Ok(pool) => {
             tracing::info!("Connected to postgres");
+            shutdown::spawn_job("postgres password rotation", follow_password(pool.clone()));
             pool
         }
         Err(e) => {
@@ -18,3 +21,32 @@ pub async fn pgpool(config: &Config) -> Pool<Postgres> {
         }
     }
 }
+
+pub async fn connect(config: &Config) -> Result<Pool<Postgres>, sqlx::Error> {
+    PgPoolOptions::new()
+        .max_connections(config.postgres.connection_pool)
+        .connect(&config.database_url())
+        .await
+}
+
+// the new connections of the pool use the password of the reloaded configuration,
+// the open connections are kept, being authenticated already
+async fn follow_password(pool: Pool<Postgres>) {
+    let mut reloads = config::subscribe();
+    let mut password = config::get().postgres.password.clone();
+    while reloads.changed().await.is_ok() {
+        let config = config::get();
+        // the password of a database url is applied by a restart
+        if config.postgres.password == password || config.database.url.is_some() {
+            continue;
+        }
+        password = config.postgres.password.clone();
+        match config.database_url().parse::<PgConnectOptions>() {
+            Ok(options) => {
+                pool.set_connect_options(options);
+                tracing::info!("postgres password rotated");
+            }
+            Err(e) => tracing::error!("could not rotate the postgres password: {}", e),
+        }
+    }
+}
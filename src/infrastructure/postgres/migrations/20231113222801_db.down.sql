@@ -0,0 +1,2 @@
+-- drops the users table with the seeded admin
+DROP TABLE users;
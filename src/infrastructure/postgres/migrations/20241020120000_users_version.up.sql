@@ -0,0 +1,2 @@
+-- row version used for the optimistic concurrency control (ETag)
+ALTER TABLE users ADD COLUMN version BIGINT NOT NULL DEFAULT 1;
@@ -0,0 +1,3 @@
+-- the soft deleted users are removed with the column
+DELETE FROM users WHERE deleted_at IS NOT NULL;
+ALTER TABLE users DROP COLUMN deleted_at;
@@ -0,0 +1,3 @@
+-- the email is unique among the users that are not deleted, a deleted user does not block its email
+ALTER TABLE users DROP CONSTRAINT users_email_key;
+CREATE UNIQUE INDEX users_email_key ON users (email) WHERE deleted_at IS NULL;
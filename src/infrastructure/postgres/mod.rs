@@ -1,2 +1,9 @@
+use sqlx::migrate::Migrator;
+
 mod connection;
-pub use connection::pgpool;
+mod user_repo;
+pub use connection::{connect, pgpool};
+pub use user_repo::PostgresUserRepository;
+
+// the schema migrations, embedded into the binary
+pub static MIGRATOR: Migrator = sqlx::migrate!("src/infrastructure/postgres/migrations");
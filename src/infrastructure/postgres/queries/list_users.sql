@@ -1 +0,0 @@
-SELECT * from users;
\ No newline at end of file
@@ -1,22 +1,125 @@
-use redis::aio::MultiplexedConnection;
+use redis::{
+    aio::{ConnectionLike, MultiplexedConnection},
+    cluster::ClusterClient,
+    cluster_async::ClusterConnection,
+    sentinel::{SentinelClient, SentinelNodeConnectionInfo, SentinelServerType},
+    Cmd, ErrorKind, Pipeline, RedisConnectionInfo, RedisFuture, RedisResult, TlsMode, Value,
+};
 
-use crate::application::config::Config;
+use crate::application::config::{Config, RedisMode};
 
-pub async fn open(config: &Config) -> MultiplexedConnection {
-    match redis::Client::open(config.redis_url()) {
-        Ok(redis) => match redis.get_multiplexed_async_connection().await {
-            Ok(connection) => {
-                tracing::info!("Connected to redis");
-                connection
+// a connection to a single redis server (standalone or discovered via sentinel) or to a redis cluster
+#[derive(Clone)]
+pub enum RedisConnection {
+    Single(MultiplexedConnection),
+    Cluster(ClusterConnection),
+}
+
+impl ConnectionLike for RedisConnection {
+    fn req_packed_command<'a>(&'a mut self, cmd: &'a Cmd) -> RedisFuture<'a, Value> {
+        match self {
+            RedisConnection::Single(connection) => connection.req_packed_command(cmd),
+            RedisConnection::Cluster(connection) => connection.req_packed_command(cmd),
+        }
+    }
+
+    fn req_packed_commands<'a>(
+        &'a mut self,
+        cmd: &'a Pipeline,
+        offset: usize,
+        count: usize,
+    ) -> RedisFuture<'a, Vec<Value>> {
+        match self {
+            RedisConnection::Single(connection) => {
+                connection.req_packed_commands(cmd, offset, count)
             }
-            Err(e) => {
-                tracing::error!("Could not connect to redis: {}", e);
-                std::process::exit(1);
+            RedisConnection::Cluster(connection) => {
+                connection.req_packed_commands(cmd, offset, count)
             }
-        },
+        }
+    }
+
+    fn get_db(&self) -> i64 {
+        match self {
+            RedisConnection::Single(connection) => connection.get_db(),
+            RedisConnection::Cluster(connection) => connection.get_db(),
+        }
+    }
+}
+
+pub async fn open(config: &Config) -> RedisConnection {
+    match connect(config).await {
+        Ok(connection) => {
+            tracing::info!("Connected to redis ({})", config.redis.mode);
+            connection
+        }
         Err(e) => {
-            tracing::error!("Could not open redis: {}", e);
+            tracing::error!("Could not connect to redis: {}", e);
             std::process::exit(1);
         }
     }
 }
+
+pub async fn connect(config: &Config) -> RedisResult<RedisConnection> {
+    match config.redis.mode {
+        RedisMode::Standalone => open_standalone(config).await,
+        RedisMode::Sentinel => open_sentinel(config).await,
+        RedisMode::Cluster => open_cluster(config).await,
+    }
+}
+
+async fn open_standalone(config: &Config) -> RedisResult<RedisConnection> {
+    let client = redis::Client::open(config.redis.connection_info())?;
+    let connection = client.get_multiplexed_async_connection().await?;
+    Ok(RedisConnection::Single(connection))
+}
+
+async fn open_sentinel(config: &Config) -> RedisResult<RedisConnection> {
+    // sentinels may have their own password, the master's credentials are used for the master
+    let sentinels = config
+        .redis
+        .nodes()
+        .into_iter()
+        .map(|(host, port)| config.redis.sentinel_connection_info(&host, port))
+        .collect::<Vec<_>>();
+
+    let master_connection_info = SentinelNodeConnectionInfo {
+        tls_mode: config.redis.tls.then_some(TlsMode::Secure),
+        redis_connection_info: Some(RedisConnectionInfo {
+            db: config.redis.database,
+            username: config.redis.username.clone(),
+            password: config.redis.password.clone(),
+            ..Default::default()
+        }),
+    };
+
+    let mut client = SentinelClient::build(
+        sentinels,
+        config.redis.sentinel_master.clone(),
+        Some(master_connection_info),
+        SentinelServerType::Master,
+    )?;
+    let connection = client.get_async_connection().await?;
+    Ok(RedisConnection::Single(connection))
+}
+
+async fn open_cluster(config: &Config) -> RedisResult<RedisConnection> {
+    if config.redis.database != 0 {
+        return Err((
+            ErrorKind::InvalidClientConfig,
+            "Redis Cluster supports only the database 0",
+        )
+            .into());
+    }
+
+    let nodes = config
+        .redis
+        .nodes()
+        .into_iter()
+        .map(|(host, port)| config.redis.node_connection_info(&host, port))
+        .collect::<Vec<_>>();
+
+    let client = ClusterClient::new(nodes)?;
+    let connection = client.get_async_connection().await?;
+    Ok(RedisConnection::Cluster(connection))
+}
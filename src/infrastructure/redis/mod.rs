@@ -1,2 +1,4 @@
 mod connection;
-pub use connection::open;
+mod revocation_repo;
+pub use connection::{connect, open, RedisConnection};
+pub use revocation_repo::RedisRevocationRepository;
@@ -0,0 +1,119 @@
+use axum::async_trait;
+use redis::{AsyncCommands, RedisResult};
+use std::collections::HashMap;
+use tokio::sync::Mutex;
+
+use crate::{
+    application::{
+        app_const::*,
+        repository::revocation_repo::{RevocationRepository, RevocationResult},
+    },
+    infrastructure::redis::RedisConnection,
+};
+
+pub struct RedisRevocationRepository {
+    redis: Mutex<RedisConnection>,
+}
+
+impl RedisRevocationRepository {
+    pub fn new(redis: RedisConnection) -> Self {
+        Self {
+            redis: Mutex::new(redis),
+        }
+    }
+}
+
+fn parse_timestamp(value: Option<String>) -> RevocationResult<Option<usize>> {
+    match value {
+        Some(timestamp) => Ok(Some(timestamp.parse()?)),
+        None => Ok(None),
+    }
+}
+
+#[async_trait]
+impl RevocationRepository for RedisRevocationRepository {
+    async fn set_global_revoke_before(&self, timestamp: usize) -> RevocationResult<()> {
+        let mut redis = self.redis.lock().await;
+        let _: () = redis
+            .set(JWT_REDIS_REVOKE_GLOBAL_BEFORE_KEY, timestamp)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_global_revoke_before(&self) -> RevocationResult<Option<usize>> {
+        let mut redis = self.redis.lock().await;
+        let timestamp: Option<String> = redis.get(JWT_REDIS_REVOKE_GLOBAL_BEFORE_KEY).await?;
+        parse_timestamp(timestamp)
+    }
+
+    async fn set_user_revoke_before(
+        &self,
+        user_id: &str,
+        timestamp: usize,
+    ) -> RevocationResult<()> {
+        let mut redis = self.redis.lock().await;
+        let _: () = redis
+            .hset(JWT_REDIS_REVOKE_USER_BEFORE_KEY, user_id, timestamp)
+            .await?;
+        Ok(())
+    }
+
+    async fn get_user_revoke_before(&self, user_id: &str) -> RevocationResult<Option<usize>> {
+        let mut redis = self.redis.lock().await;
+        let timestamp: Option<String> = redis
+            .hget(JWT_REDIS_REVOKE_USER_BEFORE_KEY, user_id)
+            .await?;
+        parse_timestamp(timestamp)
+    }
+
+    async fn add_revoked_token(&self, jti: &str, exp: usize) -> RevocationResult<()> {
+        let mut redis = self.redis.lock().await;
+        let _: () = redis.hset(JWT_REDIS_REVOKED_TOKENS_KEY, jti, exp).await?;
+        Ok(())
+    }
+
+    async fn is_token_revoked(&self, jti: &str) -> RevocationResult<bool> {
+        let mut redis = self.redis.lock().await;
+        Ok(redis.hexists(JWT_REDIS_REVOKED_TOKENS_KEY, jti).await?)
+    }
+
+    async fn get_revoked_tokens(&self) -> RevocationResult<HashMap<String, usize>> {
+        let mut redis = self.redis.lock().await;
+        let revoked_tokens: HashMap<String, String> =
+            redis.hgetall(JWT_REDIS_REVOKED_TOKENS_KEY).await?;
+
+        // skip the entries with invalid expiration time
+        let revoked_tokens = revoked_tokens
+            .into_iter()
+            .filter_map(|(jti, exp)| match exp.parse::<usize>() {
+                Ok(exp) => Some((jti, exp)),
+                Err(e) => {
+                    tracing::error!("{}", e);
+                    None
+                }
+            })
+            .collect();
+        Ok(revoked_tokens)
+    }
+
+    async fn delete_revoked_token(&self, jti: &str) -> RevocationResult<()> {
+        let mut redis = self.redis.lock().await;
+        // Workaround for https://github.com/redis-rs/redis-rs/issues/1322
+        let _: () = redis.hdel(JWT_REDIS_REVOKED_TOKENS_KEY, jti).await?;
+        Ok(())
+    }
+
+    async fn revoked_tokens_count(&self) -> RevocationResult<usize> {
+        let mut redis = self.redis.lock().await;
+        Ok(redis.hlen(JWT_REDIS_REVOKED_TOKENS_KEY).await?)
+    }
+
+    async fn close(&self) {
+        // the server closes the connection after the pending replies,
+        // the connections of a cluster are closed when dropped
+        let mut redis = self.redis.lock().await;
+        if let RedisConnection::Single(connection) = &mut *redis {
+            let _: RedisResult<()> = redis::cmd("QUIT").query_async(connection).await;
+        }
+    }
+}
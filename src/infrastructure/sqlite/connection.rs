@@ -0,0 +1,40 @@
+use sqlx::{
+    sqlite::{SqliteConnectOptions, SqlitePoolOptions},
+    Pool, Sqlite,
+};
+use std::str::FromStr;
+
+use crate::application::config::Config;
+
+pub async fn sqlite_pool(config: &Config) -> Pool<Sqlite> {
+    match connect(config).await {
+        Ok(pool) => {
+            tracing::info!("Connected to sqlite");
+            pool
+        }
+        Err(e) => {
+            tracing::error!("Could not connect to sqlite: {}", e);
+            std::process::exit(1);
+        }
+    }
+}
+
+pub async fn connect(config: &Config) -> Result<Pool<Sqlite>, sqlx::Error> {
+    let url = config.database_url();
+    let options = SqliteConnectOptions::from_str(&url)?.create_if_missing(true);
+
+    // every connection to an in-memory database opens its own database,
+    // so a single connection is kept open for the whole lifetime of the pool
+    let in_memory = url.contains(":memory:") || url.contains("mode=memory");
+    let pool_options = if in_memory {
+        SqlitePoolOptions::new()
+            .max_connections(1)
+            .min_connections(1)
+            .idle_timeout(None)
+            .max_lifetime(None)
+    } else {
+        SqlitePoolOptions::new().max_connections(config.postgres.connection_pool)
+    };
+
+    pool_options.connect_with(options).await
+}
@@ -0,0 +1,2 @@
+-- the optimistic concurrency control is removed with the row version
+ALTER TABLE users DROP COLUMN version;
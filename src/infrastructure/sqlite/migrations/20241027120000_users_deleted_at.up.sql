@@ -0,0 +1,2 @@
+-- soft deleted users are kept until they are purged
+ALTER TABLE users ADD COLUMN deleted_at TIMESTAMP NULL;
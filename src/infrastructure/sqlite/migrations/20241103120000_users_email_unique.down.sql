@@ -0,0 +1,6 @@
+-- the deleted users sharing their email with another user are removed with the partial index
+DELETE FROM users
+WHERE deleted_at IS NOT NULL
+    AND email IN (SELECT email FROM users GROUP BY email HAVING COUNT(*) > 1);
+DROP INDEX users_email_key;
+CREATE UNIQUE INDEX users_email_key ON users (email);
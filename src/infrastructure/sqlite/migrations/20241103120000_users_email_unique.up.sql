@@ -0,0 +1,20 @@
+-- the email is unique among the users that are not deleted, a deleted user does not block its email;
+-- sqlite can not drop the column constraint, the table is rebuilt without it
+CREATE TABLE users_new (
+    id BLOB PRIMARY KEY NOT NULL DEFAULT (randomblob(16)),
+    username TEXT NOT NULL,
+    email TEXT NOT NULL,
+    password_hash TEXT NOT NULL,
+    password_salt TEXT NOT NULL,
+    active BOOLEAN NOT NULL DEFAULT TRUE,
+    roles TEXT NOT NULL,
+    created_at TIMESTAMP NOT NULL,
+    updated_at TIMESTAMP NOT NULL,
+    version INTEGER NOT NULL DEFAULT 1,
+    deleted_at TIMESTAMP NULL
+);
+INSERT INTO users_new (id, username, email, password_hash, password_salt, active, roles, created_at, updated_at, version, deleted_at)
+SELECT id, username, email, password_hash, password_salt, active, roles, created_at, updated_at, version, deleted_at FROM users;
+DROP TABLE users;
+ALTER TABLE users_new RENAME TO users;
+CREATE UNIQUE INDEX users_email_key ON users (email) WHERE deleted_at IS NULL;
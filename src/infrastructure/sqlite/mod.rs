@@ -0,0 +1,9 @@
+use sqlx::migrate::Migrator;
+
+mod connection;
+mod user_repo;
+pub use connection::{connect, sqlite_pool};
+pub use user_repo::SqliteUserRepository;
+
+// the schema migrations, embedded into the binary
+pub static MIGRATOR: Migrator = sqlx::migrate!("src/infrastructure/sqlite/migrations");
@@ -0,0 +1,207 @@
+use axum::async_trait;
+use chrono::Utc;
+use sqlx::{query_as, Pool, Sqlite};
+use uuid::Uuid;
+
+use crate::{
+    application::repository::{
+        user_repo::{UserListQuery, UserPage, UserRepository},
+        RepositoryResult,
+    },
+    domain::models::user::User,
+    infrastructure::user_query,
+};
+
+const USER_COLUMNS: &str = "id, username, email, password_hash, password_salt, active, roles, \
+     created_at, updated_at, version, deleted_at";
+
+pub struct SqliteUserRepository {
+    pool: Pool<Sqlite>,
+}
+
+impl SqliteUserRepository {
+    pub fn new(pool: Pool<Sqlite>) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl UserRepository for SqliteUserRepository {
+    async fn get_all(&self) -> RepositoryResult<Vec<User>> {
+        let users = query_as::<_, User>(&format!(
+            "SELECT {} FROM users WHERE deleted_at IS NULL",
+            USER_COLUMNS
+        ))
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(users)
+    }
+
+    async fn list(&self, query: &UserListQuery) -> RepositoryResult<UserPage> {
+        let users = user_query::select_users::<Sqlite>(USER_COLUMNS, query)
+            .build_query_as::<User>()
+            .fetch_all(&self.pool)
+            .await?;
+
+        let total_count = if query.include_total {
+            let count = user_query::count_users::<Sqlite>(&query.filter)
+                .build_query_scalar::<i64>()
+                .fetch_one(&self.pool)
+                .await?;
+            Some(count)
+        } else {
+            None
+        };
+
+        Ok(UserPage::new(users, query, total_count))
+    }
+
+    async fn get_by_id(&self, id: Uuid) -> RepositoryResult<User> {
+        let user = query_as::<_, User>(&format!(
+            "SELECT {} FROM users WHERE id = ? AND deleted_at IS NULL",
+            USER_COLUMNS
+        ))
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+        Ok(user)
+    }
+
+    async fn get_by_username(&self, username: &str) -> RepositoryResult<User> {
+        let user = query_as::<_, User>(&format!(
+            "SELECT {} FROM users WHERE username = ? AND deleted_at IS NULL",
+            USER_COLUMNS
+        ))
+        .bind(username)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn get_by_email(&self, email: &str) -> RepositoryResult<User> {
+        let user = query_as::<_, User>(&format!(
+            "SELECT {} FROM users WHERE email = ? AND deleted_at IS NULL",
+            USER_COLUMNS
+        ))
+        .bind(email)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn add(&self, user: User) -> RepositoryResult<User> {
+        let time_now = Utc::now().naive_utc();
+        tracing::trace!("user: {:#?}", user);
+        let user = query_as::<_, User>(&format!(
+            r#"INSERT INTO users (id,
+             username,
+             email,
+             password_hash,
+             password_salt,
+             active,
+             roles,
+             created_at,
+             updated_at)
+             VALUES (?,?,?,?,?,?,?,?,?)
+             RETURNING {}"#,
+            USER_COLUMNS
+        ))
+        .bind(user.id)
+        .bind(user.username)
+        .bind(user.email)
+        .bind(user.password_hash)
+        .bind(user.password_salt)
+        .bind(true)
+        .bind(user.roles)
+        .bind(time_now)
+        .bind(time_now)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn update(&self, id: Uuid, user: User, version: i64) -> RepositoryResult<User> {
+        tracing::trace!("user: {:#?}", user);
+        let time_now = Utc::now().naive_utc();
+        let user = query_as::<_, User>(&format!(
+            r#"UPDATE users
+             SET username = ?,
+             email = ?,
+             password_hash = ?,
+             password_salt = ?,
+             active = ?,
+             roles = ?,
+             updated_at = ?,
+             version = version + 1
+             WHERE id = ? AND version = ? AND deleted_at IS NULL
+             RETURNING {}"#,
+            USER_COLUMNS
+        ))
+        .bind(user.username)
+        .bind(user.email)
+        .bind(user.password_hash)
+        .bind(user.password_salt)
+        .bind(user.active)
+        .bind(user.roles)
+        .bind(time_now)
+        .bind(id)
+        .bind(version)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn delete(&self, id: Uuid, version: i64) -> RepositoryResult<bool> {
+        let time_now = Utc::now().naive_utc();
+        let query_result = sqlx::query(
+            r#"UPDATE users
+             SET deleted_at = ?,
+             version = version + 1
+             WHERE id = ? AND version = ? AND deleted_at IS NULL"#,
+        )
+        .bind(time_now)
+        .bind(id)
+        .bind(version)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(query_result.rows_affected() == 1)
+    }
+
+    async fn restore(&self, id: Uuid) -> RepositoryResult<User> {
+        let time_now = Utc::now().naive_utc();
+        let user = query_as::<_, User>(&format!(
+            r#"UPDATE users
+             SET deleted_at = NULL,
+             updated_at = ?,
+             version = version + 1
+             WHERE id = ? AND deleted_at IS NOT NULL
+             RETURNING {}"#,
+            USER_COLUMNS
+        ))
+        .bind(time_now)
+        .bind(id)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(user)
+    }
+
+    async fn purge(&self, id: Uuid) -> RepositoryResult<bool> {
+        let query_result = sqlx::query("DELETE FROM users WHERE id = ? AND deleted_at IS NOT NULL")
+            .bind(id)
+            .execute(&self.pool)
+            .await?;
+
+        Ok(query_result.rows_affected() == 1)
+    }
+
+    async fn close(&self) {
+        self.pool.close().await;
+    }
+}
@@ -0,0 +1,112 @@
+use chrono::NaiveDateTime;
+use sqlx::{Database, Encode, QueryBuilder, Type};
+use uuid::Uuid;
+
+use crate::application::repository::user_repo::{
+    SortOrder, UserFilter, UserListQuery, UserSortKey, UserSortValue,
+};
+
+// builds the users list queries shared by the sql backends,
+// the placeholders are generated by the query builder for each database
+
+pub(crate) fn select_users<'args, DB>(
+    columns: &str,
+    query: &UserListQuery,
+) -> QueryBuilder<'args, DB>
+where
+    DB: Database,
+    DB::Arguments<'args>: Default,
+    bool: Encode<'args, DB> + Type<DB>,
+    String: Encode<'args, DB> + Type<DB>,
+    Option<NaiveDateTime>: Encode<'args, DB> + Type<DB>,
+    NaiveDateTime: Encode<'args, DB> + Type<DB>,
+    Uuid: Encode<'args, DB> + Type<DB>,
+    i64: Encode<'args, DB> + Type<DB>,
+{
+    let mut builder = QueryBuilder::new(format!(
+        "SELECT {} FROM users WHERE deleted_at IS NULL",
+        columns
+    ));
+    push_filter(&mut builder, &query.filter);
+
+    let column = query.sort.column();
+    let (comparison, order) = match query.order {
+        SortOrder::Asc => (">", "ASC"),
+        SortOrder::Desc => ("<", "DESC"),
+    };
+
+    // keyset pagination, the id breaks ties of equal sort values
+    if let Some(cursor) = &query.cursor {
+        builder.push(format!(" AND ({}, id) {} (", column, comparison));
+        match &cursor.value {
+            UserSortValue::Text(value) => builder.push_bind(value.clone()),
+            UserSortValue::Timestamp(value) => builder.push_bind(*value),
+        };
+        builder.push(", ");
+        builder.push_bind(cursor.id);
+        builder.push(")");
+    }
+
+    builder.push(format!(" ORDER BY {} {}, id {}", column, order, order));
+    // one more row to find out if there is a next page
+    builder.push(" LIMIT ");
+    builder.push_bind(query.limit as i64 + 1);
+    builder
+}
+
+pub(crate) fn count_users<'args, DB>(filter: &UserFilter) -> QueryBuilder<'args, DB>
+where
+    DB: Database,
+    DB::Arguments<'args>: Default,
+    bool: Encode<'args, DB> + Type<DB>,
+    String: Encode<'args, DB> + Type<DB>,
+    NaiveDateTime: Encode<'args, DB> + Type<DB>,
+{
+    let mut builder = QueryBuilder::new("SELECT COUNT(*) FROM users WHERE deleted_at IS NULL");
+    push_filter(&mut builder, filter);
+    builder
+}
+
+fn push_filter<'args, DB>(builder: &mut QueryBuilder<'args, DB>, filter: &UserFilter)
+where
+    DB: Database,
+    bool: Encode<'args, DB> + Type<DB>,
+    String: Encode<'args, DB> + Type<DB>,
+    NaiveDateTime: Encode<'args, DB> + Type<DB>,
+{
+    if let Some(active) = filter.active {
+        builder.push(" AND active = ");
+        builder.push_bind(active);
+    }
+    if let Some(role) = &filter.role {
+        // roles are stored as a comma separated list
+        builder.push(" AND (',' || REPLACE(roles, ' ', '') || ',') LIKE ");
+        builder.push_bind(format!("%,{},%", escape_like(role)));
+        builder.push(" ESCAPE '\\'");
+    }
+    if let Some(prefix) = &filter.username_prefix {
+        builder.push(" AND username LIKE ");
+        builder.push_bind(format!("{}%", escape_like(prefix)));
+        builder.push(" ESCAPE '\\'");
+    }
+    if let Some(prefix) = &filter.email_prefix {
+        builder.push(" AND email LIKE ");
+        builder.push_bind(format!("{}%", escape_like(prefix)));
+        builder.push(" ESCAPE '\\'");
+    }
+    if let Some(created_after) = filter.created_after {
+        builder.push(" AND created_at >= ");
+        builder.push_bind(created_after);
+    }
+    if let Some(created_before) = filter.created_before {
+        builder.push(" AND created_at < ");
+        builder.push_bind(created_before);
+    }
+}
+
+fn escape_like(value: &str) -> String {
+    value
+        .replace('\\', "\\\\")
+        .replace('%', "\\%")
+        .replace('_', "\\_")
+}
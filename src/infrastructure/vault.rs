@@ -0,0 +1,77 @@
+// the secrets of a HashiCorp Vault compatible KV secrets engine
+use axum::async_trait;
+use std::{collections::HashMap, time::Duration};
+
+use crate::application::{
+    app_const::*,
+    config::{SecretError, SecretProvider},
+};
+
+pub struct VaultSecretProvider {
+    client: reqwest::Client,
+    url: String,
+    token: String,
+}
+
+impl VaultSecretProvider {
+    pub fn new(addr: &str, token: &str, path: &str) -> Self {
+        let client = reqwest::Client::builder()
+            .timeout(Duration::from_secs(SECRETS_REQUEST_TIMEOUT_SECONDS))
+            .build()
+            .unwrap_or_default();
+        Self {
+            client,
+            url: format!(
+                "{}/v1/{}",
+                addr.trim_end_matches('/'),
+                path.trim_start_matches('/')
+            ),
+            token: token.to_string(),
+        }
+    }
+}
+
+#[async_trait]
+impl SecretProvider for VaultSecretProvider {
+    async fn secrets(&self) -> Result<HashMap<String, String>, SecretError> {
+        let response = self
+            .client
+            .get(&self.url)
+            .header(HEADER_VAULT_TOKEN, &self.token)
+            .send()
+            .await
+            .map_err(|e| SecretError::Unavailable(e.to_string()))?;
+        if !response.status().is_success() {
+            return Err(SecretError::Unavailable(format!(
+                "{} responded {}",
+                self.url,
+                response.status()
+            )));
+        }
+        let body: serde_json::Value = response
+            .json()
+            .await
+            .map_err(|e| SecretError::InvalidResponse(e.to_string()))?;
+
+        // the KV version 2 engine nests the secret in data.data, the version 1 engine in data
+        let data = match &body["data"]["data"] {
+            serde_json::Value::Object(data) => data,
+            _ => match &body["data"] {
+                serde_json::Value::Object(data) => data,
+                _ => {
+                    return Err(SecretError::InvalidResponse(
+                        "expected the data of a secret".to_string(),
+                    ))
+                }
+            },
+        };
+        Ok(data
+            .iter()
+            .filter_map(|(key, value)| match value {
+                serde_json::Value::String(value) => Some((key.clone(), value.clone())),
+                serde_json::Value::Number(value) => Some((key.clone(), value.to_string())),
+                _ => None,
+            })
+            .collect())
+    }
+}
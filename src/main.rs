@@ -1,24 +1,171 @@
+use axum_web::{
+    application::{
+        app::{self, ServeOptions},
+        check,
+        config::{self, Config, ConfigArgs, LogFormat},
+        shutdown,
+    },
+    infrastructure::database::DatabasePool,
+};
+use clap::{Parser, Subcommand};
 use tokio::sync::oneshot;
-use tracing_subscriber::{layer::SubscriberExt, util::SubscriberInitExt};
+use tracing_subscriber::{layer::SubscriberExt, reload, util::SubscriberInitExt, EnvFilter, Layer};
+
+#[derive(Parser)]
+#[command(name = "axum-web", version, about = "REST API Web service")]
+struct Cli {
+    /// Serves the api if no command is given
+    #[command(subcommand)]
+    command: Option<Command>,
+    #[command(flatten)]
+    config: ConfigArgs,
+}
+
+#[derive(Subcommand)]
+enum Command {
+    /// Serve the api
+    Serve {
+        /// Leave the database migrations to a separate `migrate up` step
+        #[arg(long, env = "SKIP_MIGRATIONS")]
+        skip_migrations: bool,
+    },
+    /// Manage the database migrations
+    Migrate {
+        #[command(subcommand)]
+        command: MigrateCommand,
+    },
+    /// Validate the configuration and the connectivity to the database and Redis
+    CheckConfig,
+}
+
+#[derive(Subcommand)]
+enum MigrateCommand {
+    /// Apply the pending migrations
+    Up,
+    /// Revert the last applied migration
+    Down {
+        /// Revert all the migrations applied after this version
+        #[arg(long)]
+        target: Option<i64>,
+    },
+    /// List the migrations and whether they are applied
+    Status,
+}
 
 #[tokio::main]
 async fn main() {
-    // tracing configuration
-    let filter_layer = tracing_subscriber::EnvFilter::try_from_default_env()
-        .unwrap_or_else(|_| "axum_web=trace".into());
+    let cli = Cli::parse();
+    let command = cli.command.unwrap_or(Command::Serve {
+        skip_migrations: false,
+    });
+
+    // the problems of the configuration are reported at once
+    let config = match config::try_load_with(&cli.config).await {
+        Ok(config) => config,
+        Err(e) => {
+            println!("configuration: failed");
+            for error in &e.errors {
+                println!("  {}", error);
+            }
+            std::process::exit(1);
+        }
+    };
+
+    // tracing configuration, the commands other than serve log only the warnings by default
+    let default_filter = match command {
+        Command::Serve { .. } => config.logging.filter.as_str(),
+        _ => "axum_web=warn",
+    };
+    let env_filter = std::env::var(EnvFilter::DEFAULT_ENV).is_ok();
+    let filter_layer = EnvFilter::try_from_default_env().unwrap_or_else(|_| default_filter.into());
+    let (filter_layer, filter_handle) = reload::Layer::new(filter_layer);
     let fmt_layer = tracing_subscriber::fmt::layer()
-        .compact()
         .with_target(false)
         .with_file(true)
         .with_line_number(true);
+    let fmt_layer = match config.logging.format {
+        LogFormat::Compact => fmt_layer.compact().boxed(),
+        LogFormat::Pretty => fmt_layer.pretty().boxed(),
+    };
     tracing_subscriber::registry()
         .with(filter_layer)
         .with(fmt_layer)
         .init();
 
-    tracing::info!("{} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+    match command {
+        Command::Serve { skip_migrations } => {
+            tracing::info!("{} v{}", env!("CARGO_PKG_NAME"), env!("CARGO_PKG_VERSION"));
+            // the reloaded log filter is applied unless RUST_LOG is set
+            let watch = config::watch(cli.config.clone(), move |config| {
+                if env_filter {
+                    return;
+                }
+                if let Err(e) = filter_handle.reload(EnvFilter::new(&config.logging.filter)) {
+                    tracing::error!("could not reload the log filter: {}", e);
+                }
+            });
+            shutdown::spawn_job("configuration watch", watch);
+            let (api_ready_tx, api_ready_rx) = oneshot::channel();
+            app::serve(ServeOptions { skip_migrations }, api_ready_tx).await;
+            api_ready_rx.await.expect("Could not start server");
+        }
+        Command::Migrate { command } => {
+            if let Err(e) = migrate(&config, command).await {
+                eprintln!("error: {}", e);
+                std::process::exit(1);
+            }
+        }
+        Command::CheckConfig => {
+            println!("configuration: ok");
+            let mut failed = false;
+            for check in check::check_services(&config).await {
+                match check.result {
+                    Ok(description) => println!("{}: ok, {}", check.name, description),
+                    Err(e) => {
+                        println!("{}: failed, {}", check.name, e);
+                        failed = true;
+                    }
+                }
+            }
+            if failed {
+                std::process::exit(1);
+            }
+        }
+    }
+}
 
-    let (api_ready_tx, api_ready_rx) = oneshot::channel();
-    axum_web::application::app::start_server(api_ready_tx).await;
-    api_ready_rx.await.expect("Could not start server");
+async fn migrate(
+    config: &Config,
+    command: MigrateCommand,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let pool = DatabasePool::connect(config).await?;
+    match command {
+        MigrateCommand::Up => {
+            pool.migrate().await?;
+            println!("migrations applied");
+        }
+        MigrateCommand::Down { target } => {
+            let reverted = pool.undo(target).await?;
+            if reverted.is_empty() {
+                println!("no migrations to revert");
+            }
+            for version in reverted {
+                println!("migration reverted: {}", version);
+            }
+        }
+        MigrateCommand::Status => {
+            for migration in pool.migration_status().await? {
+                let status = if migration.applied {
+                    "applied"
+                } else {
+                    "pending"
+                };
+                println!(
+                    "{} {:<8} {}",
+                    migration.version, status, migration.description
+                );
+            }
+        }
+    }
+    Ok(())
 }
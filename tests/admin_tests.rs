@@ -0,0 +1,139 @@
+use axum::http::{Method, StatusCode};
+use axum_web::{
+    application::{
+        admin::{self, AdminError},
+        security::password,
+        state::SharedState,
+    },
+    infrastructure::memory::{InMemoryRevocationRepository, InMemoryUserRepository},
+};
+use std::sync::Arc;
+
+pub mod common;
+use common::{
+    in_memory::{test_admin, test_user, TestApp},
+    utils, *,
+};
+
+// the salt given on the command line, known to the client
+const TEST_PASSWORD_SALT: &str = "admin-tool-salt-0123";
+
+fn state(users: Vec<axum_web::domain::models::user::User>) -> SharedState {
+    utils::build_state(
+        Arc::new(InMemoryUserRepository::with_users(users)),
+        Arc::new(InMemoryRevocationRepository::new()),
+    )
+}
+
+#[tokio::test]
+async fn admin_user_test() {
+    let guest = test_user("guest-user", TEST_USER_PASSWORD_HASH, "guest");
+    let state = state(vec![test_admin(), guest.clone()]);
+    let app = TestApp::with_state(state.clone());
+
+    // the created admin logs in with the digest of the password and the given salt
+    let user = admin::create_admin(
+        &state,
+        "ops",
+        "ops@email.com",
+        "pswd1234",
+        Some(TEST_PASSWORD_SALT),
+    )
+    .await
+    .unwrap();
+    assert_eq!(user.roles, "admin");
+    assert!(user.active);
+    // inserted as an admin, not updated after the insert
+    assert_eq!(user.version, 1);
+    let password_hash = password::hash_password("pswd1234", TEST_PASSWORD_SALT);
+    let (status, access_token) = app.login("ops", &password_hash).await;
+    assert_eq!(status, StatusCode::OK);
+    let access_token = access_token.unwrap();
+    let (status, _) = app
+        .request(Method::GET, "/v1/users", Some(&access_token), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // a random salt unless given, returned to be printed
+    let user = admin::create_admin(&state, "ops3", "ops3@email.com", "pswd1234", None)
+        .await
+        .unwrap();
+    assert_ne!(user.password_salt, TEST_PASSWORD_SALT);
+    assert!(user.password_salt.len() >= 16);
+
+    // the admins follow the rules of the api
+    let error = admin::create_admin(&state, " ", "ops", "pswd1234", None)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, AdminError::InvalidUser(_)));
+    let error = admin::create_admin(&state, "ops4", "ops4@email.com", "pswd1234", Some("short"))
+        .await
+        .unwrap_err();
+    assert!(matches!(error, AdminError::InvalidUser(_)));
+    let error = admin::create_admin(&state, "ops2", "ops@email.com", "pswd1234", None)
+        .await
+        .unwrap_err();
+    assert!(matches!(error, AdminError::Repository(_)));
+
+    // the promoted user keeps its roles
+    let promoted = admin::promote(&state, "guest-user").await.unwrap();
+    assert_eq!(promoted.roles, "guest,admin");
+    let promoted = admin::promote(&state, &guest.id.to_string()).await.unwrap();
+    assert_eq!(promoted.roles, "guest,admin");
+    let error = admin::promote(&state, "nobody").await.unwrap_err();
+    assert!(matches!(error, AdminError::UserNotFound(_)));
+}
+
+#[tokio::test]
+async fn admin_tokens_test() {
+    let state = state(vec![test_admin()]);
+    let app = TestApp::with_state(state.clone());
+    let access_token = app.login_admin().await;
+
+    // the tokens of the user are revoked with the password reset
+    admin::reset_password(
+        &state,
+        TEST_ADMIN_USERNAME,
+        "new-password",
+        Some(TEST_PASSWORD_SALT),
+    )
+    .await
+    .unwrap();
+    let (status, _) = app
+        .request(Method::GET, "/v1/users", Some(&access_token), None)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // only the new password is accepted, the revocation covers the tokens of the same second
+    tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
+    let (status, _) = app
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let password_hash = password::hash_password("new-password", TEST_PASSWORD_SALT);
+    let (status, access_token) = app.login(TEST_ADMIN_USERNAME, &password_hash).await;
+    assert_eq!(status, StatusCode::OK);
+    let access_token = access_token.unwrap();
+
+    admin::revoke_all(&state).await.unwrap();
+    let (status, _) = app
+        .request(Method::GET, "/v1/users", Some(&access_token), None)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    assert_eq!(admin::cleanup(&state).await.unwrap(), 0);
+}
+
+#[test]
+fn admin_config_test() {
+    let config = utils::test_config();
+    let masked = config.masked();
+    assert!(masked.contains("SERVICE_PORT = 3000\n"));
+    assert!(masked.contains("JWT_SECRET = ********\n"));
+    assert!(masked.contains("POSTGRES_PASSWORD = ********\n"));
+    assert!(!masked.contains(&config.jwt.secret));
+
+    let secret = admin::generate_jwt_secret();
+    assert_eq!(secret.len(), 64);
+    assert_ne!(secret, admin::generate_jwt_secret());
+}
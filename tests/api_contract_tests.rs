@@ -0,0 +1,181 @@
+use axum::http::{header, Method, StatusCode};
+use serde_json::{json, Value};
+
+pub mod common;
+use common::{
+    in_memory::{test_admin, test_user, TestApp},
+    *,
+};
+
+// the exact set of the members of a json object
+fn members(json: &Value) -> Vec<&str> {
+    let mut members: Vec<&str> = json
+        .as_object()
+        .unwrap()
+        .keys()
+        .map(|k| k.as_str())
+        .collect();
+    members.sort();
+    members
+}
+
+#[tokio::test]
+async fn users_v1_contract_test() {
+    let user = test_user("v1-user", TEST_USER_PASSWORD_HASH, "admin,guest");
+    let app = TestApp::start(vec![test_admin(), user.clone()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    // the user is a flat object with comma separated roles
+    let uri = format!("/v1/users/{}", user.id);
+    let (status, json) = app.request(Method::GET, &uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        members(&json),
+        vec![
+            "active",
+            "created_at",
+            "email",
+            "id",
+            "roles",
+            "updated_at",
+            "username"
+        ]
+    );
+    assert_eq!(json["roles"], "admin,guest");
+
+    // the list is a bare array, the paging is in the headers
+    let (status, headers, json) = app
+        .request_with_headers(
+            Method::GET,
+            "/v1/users?limit=1&include_total=true",
+            token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert!(headers.contains_key("x-next-cursor"));
+    assert_eq!(headers["x-total-count"], "2");
+
+    // the update carries the id and the roles as a string
+    let mut update = json!(user);
+    update["roles"] = json!("guest");
+    let (status, _, json) = app
+        .send(
+            Method::PUT,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            Some(update.clone()),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["roles"], "guest");
+
+    update.as_object_mut().unwrap().remove("id");
+    let (status, _, json) = app
+        .send(
+            Method::PUT,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            Some(update),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json["code"], "validation_failed");
+}
+
+#[tokio::test]
+async fn users_v2_contract_test() {
+    let user = test_user("v2-user", TEST_USER_PASSWORD_HASH, "admin,guest");
+    let app = TestApp::start(vec![test_admin(), user.clone()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    // the roles are an array
+    let uri = format!("/v2/users/{}", user.id);
+    let (status, json) = app.request(Method::GET, &uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(
+        members(&json),
+        vec![
+            "active",
+            "created_at",
+            "email",
+            "id",
+            "roles",
+            "updated_at",
+            "username"
+        ]
+    );
+    assert_eq!(json["roles"], json!(["admin", "guest"]));
+
+    // the list is wrapped in a pagination envelope
+    let (status, json) = app
+        .request(
+            Method::GET,
+            "/v2/users?limit=1&include_total=true",
+            token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(members(&json), vec!["items", "next_cursor", "total_count"]);
+    assert_eq!(json["items"].as_array().unwrap().len(), 1);
+    assert_eq!(json["total_count"], 2);
+
+    let cursor = json["next_cursor"].as_str().unwrap();
+    let (status, json) = app
+        .request(
+            Method::GET,
+            &format!("/v2/users?limit=1&cursor={}", cursor),
+            token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(members(&json), vec!["items", "next_cursor"]);
+    assert!(json["next_cursor"].is_null());
+
+    // the update takes the id from the path and the roles as an array
+    let update = json!({
+        "username": user.username,
+        "email": user.email,
+        "active": true,
+        "roles": ["guest"]
+    });
+    let (status, _, json) = app
+        .send(
+            Method::PUT,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            Some(update),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["roles"], json!(["guest"]));
+
+    let (status, _, json) = app
+        .send(
+            Method::PATCH,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            Some(json!({"roles": ["guest", "root"]})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json["errors"][0]["field"], "roles");
+
+    // the errors are problem details
+    let (status, headers, json) = app
+        .request_with_headers(Method::GET, "/v2/users?limit=0", token, None)
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert_eq!(headers[header::CONTENT_TYPE], "application/problem+json");
+    assert_eq!(json["code"], "bad_request");
+    assert_eq!(json["instance"], "/v2/users");
+}
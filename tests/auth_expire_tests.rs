@@ -1,9 +1,10 @@
 use axum_web::application::config;
+use axum_web_client::ErrorCode;
 use reqwest::StatusCode;
 use serial_test::serial;
 
 pub mod common;
-use common::{auth, route, utils, *};
+use common::{utils, *};
 
 #[tokio::test]
 #[serial]
@@ -13,36 +14,33 @@ async fn access_token_expire_test() {
     let config = config::get();
 
     // assert that revoked options are enabled
-    assert!(config.jwt_enable_revoked_tokens);
+    assert!(config.jwt.enable_revoked_tokens);
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let client = utils::client().with_auto_refresh(false);
+    let tokens = client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, refresh_token) = result.unwrap();
 
     // wait to expire access token
     tokio::time::sleep(tokio::time::Duration::from_secs(
-        (config.jwt_expire_access_token_seconds + config.jwt_validation_leeway_seconds + 1) as u64,
+        (config.jwt.expire_access_token_seconds + config.jwt.validation_leeway_seconds + 1) as u64,
     ))
     .await;
 
     // check the access to the root handler with expired token
-    assert_eq!(
-        route::fetch_root(&access_token).await.unwrap(),
-        StatusCode::UNAUTHORIZED
-    );
+    let error = client.root().await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
+    assert_eq!(error.code(), Some(ErrorCode::ExpiredToken));
 
-    // refresh tokens
-    let (status, result) = auth::refresh(&refresh_token).await.unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token_new, _) = result.unwrap();
+    // the client renews the expired tokens and retries
+    let client = client.with_auto_refresh(true);
+    client.root().await.unwrap();
+    let tokens_new = client.tokens().unwrap();
+    assert_ne!(tokens.access_token, tokens_new.access_token);
 
     // try access to the root handler with new token
-    assert_eq!(
-        route::fetch_root(&access_token_new).await.unwrap(),
-        StatusCode::OK
-    );
+    client.root().await.unwrap();
 }
 
 #[tokio::test]
@@ -53,22 +51,22 @@ async fn refresh_token_expire_test() {
     let config = config::get();
 
     // assert that revoked options are enabled
-    assert!(config.jwt_enable_revoked_tokens);
+    assert!(config.jwt.enable_revoked_tokens);
 
     // login
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let client = utils::client();
+    client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (_, refresh_token) = result.unwrap();
 
     // wait to expire refresh token
     tokio::time::sleep(tokio::time::Duration::from_secs(
-        (config.jwt_expire_refresh_token_seconds + config.jwt_validation_leeway_seconds + 1) as u64,
+        (config.jwt.expire_refresh_token_seconds + config.jwt.validation_leeway_seconds + 1) as u64,
     ))
     .await;
 
     // try to refresh with expired token
-    let (status, _) = auth::refresh(&refresh_token).await.unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let error = client.refresh().await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 }
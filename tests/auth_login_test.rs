@@ -1,45 +1,51 @@
+use axum_web_client::ErrorCode;
 use reqwest::StatusCode;
 use serial_test::serial;
 
 pub mod common;
-use common::{auth, route, utils, *};
+use common::{utils, *};
 
 #[tokio::test]
 #[serial]
 async fn login_test() {
     // load the test configuration and start the api server
     utils::start_api().await;
+    let client = utils::client();
 
     // try unauthorized access to the root handler
-    assert_eq!(
-        route::fetch_root("").await.unwrap(),
-        StatusCode::UNAUTHORIZED
-    );
+    let error = client.root().await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 
     let username_wrong = format!("{}1", TEST_ADMIN_USERNAME);
-    let (status, _) = auth::login(&username_wrong, TEST_ADMIN_PASSWORD_HASH)
+    let error = client
+        .login(&username_wrong, TEST_ADMIN_PASSWORD_HASH)
         .await
-        .unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+        .unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
+    assert_eq!(error.code(), Some(ErrorCode::WrongCredentials));
 
     let password_wrong = format!("{}1", TEST_ADMIN_PASSWORD_HASH);
-    let (status, _) = auth::login(TEST_ADMIN_USERNAME, &password_wrong)
+    let error = client
+        .login(TEST_ADMIN_USERNAME, &password_wrong)
         .await
-        .unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+        .unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 
-    let (status, _) = auth::login(&username_wrong, &password_wrong).await.unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let error = client
+        .login(&username_wrong, &password_wrong)
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
+    assert!(client.tokens().is_none());
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let tokens = client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, _) = result.unwrap();
+    assert!(!tokens.access_token.is_empty());
+    assert!(!tokens.refresh_token.is_empty());
 
     // access to the root handler
-    assert_eq!(
-        route::fetch_root(&access_token).await.unwrap(),
-        StatusCode::OK
-    );
+    let greeting = client.root().await.unwrap();
+    assert_eq!(greeting.message, "Hello from Axum-Web!");
 }
@@ -3,7 +3,7 @@ use reqwest::StatusCode;
 use serial_test::serial;
 
 pub mod common;
-use common::{auth, route, utils, *};
+use common::{utils, *};
 
 #[tokio::test]
 #[serial]
@@ -13,32 +13,28 @@ async fn logout_test() {
     let config = config::get();
 
     // assert that revoked options are enabled
-    assert!(config.jwt_enable_revoked_tokens);
+    assert!(config.jwt.enable_revoked_tokens);
+
+    let client = utils::client();
 
     // try unauthorized access to the root handler
-    assert_eq!(
-        route::fetch_root("").await.unwrap(),
-        StatusCode::UNAUTHORIZED
-    );
+    let error = client.root().await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let tokens = client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, refresh_token) = result.unwrap();
 
     // access to the root handler
-    assert_eq!(
-        route::fetch_root(&access_token).await.unwrap(),
-        StatusCode::OK
-    );
+    client.root().await.unwrap();
 
     // logout
-    assert_eq!(auth::logout(&refresh_token).await.unwrap(), StatusCode::OK);
+    client.logout().await.unwrap();
+    assert!(client.tokens().is_none());
 
     // try access to the root handler after logout
-    assert_eq!(
-        route::fetch_root(&access_token).await.unwrap(),
-        StatusCode::UNAUTHORIZED
-    );
+    client.set_tokens(Some(tokens));
+    let error = client.root().await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 }
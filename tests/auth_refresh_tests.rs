@@ -3,7 +3,7 @@ use reqwest::StatusCode;
 use serial_test::serial;
 
 pub mod common;
-use common::{auth, route, utils, *};
+use common::{utils, *};
 
 #[tokio::test]
 #[serial]
@@ -11,31 +11,25 @@ async fn refresh_test() {
     // load the test configuration and start the api server
     utils::start_api().await;
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let client = utils::client();
+    let tokens = client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, refresh_token) = result.unwrap();
 
     // refresh tokens
-    let (status, result) = auth::refresh(&refresh_token).await.unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token_new, refresh_token_new) = result.unwrap();
-
-    assert_ne!(access_token, access_token_new);
-    assert_ne!(refresh_token, refresh_token_new);
+    let tokens_new = client.refresh().await.unwrap();
+    assert_ne!(tokens.access_token, tokens_new.access_token);
+    assert_ne!(tokens.refresh_token, tokens_new.refresh_token);
+    assert_eq!(client.tokens(), Some(tokens_new));
 
     // try access to the root handler with old token
-    assert_eq!(
-        route::fetch_root(&access_token).await.unwrap(),
-        StatusCode::UNAUTHORIZED
-    );
+    let client_old = utils::client_with_tokens(&tokens.access_token, &tokens.refresh_token);
+    let error = client_old.root().await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 
     // try access to the root handler with new token
-    assert_eq!(
-        route::fetch_root(&access_token_new).await.unwrap(),
-        StatusCode::OK
-    );
+    client.root().await.unwrap();
 }
 
 #[tokio::test]
@@ -46,28 +40,22 @@ async fn refresh_logout_test() {
     let config = config::get();
 
     // assert that revoked options are enabled
-    assert!(config.jwt_enable_revoked_tokens);
+    assert!(config.jwt.enable_revoked_tokens);
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let client = utils::client();
+    let tokens = client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (_, refresh_token) = result.unwrap();
 
     // refresh tokens
-    let (status, result) = auth::refresh(&refresh_token).await.unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (_, refresh_token_new) = result.unwrap();
+    client.refresh().await.unwrap();
 
     // try logout with old token
-    assert_eq!(
-        auth::logout(&refresh_token).await.unwrap(),
-        StatusCode::UNAUTHORIZED
-    );
+    let client_old = utils::client_with_tokens(&tokens.access_token, &tokens.refresh_token);
+    let error = client_old.logout().await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 
     // logout with new token
-    assert_eq!(
-        auth::logout(&refresh_token_new).await.unwrap(),
-        StatusCode::OK
-    );
+    client.logout().await.unwrap();
 }
@@ -6,7 +6,7 @@ use reqwest::StatusCode;
 use serial_test::serial;
 
 pub mod common;
-use common::{auth, route, utils, *};
+use common::{utils, *};
 
 #[tokio::test]
 #[serial]
@@ -16,27 +16,22 @@ async fn revoke_user_test() {
     let config = config::get();
 
     // assert that revoked options are enabled
-    assert!(config.jwt_enable_revoked_tokens);
+    assert!(config.jwt.enable_revoked_tokens);
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let client = utils::client();
+    let tokens = client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, _) = result.unwrap();
 
-    let access_claims: AccessClaims = jwt_claims::decode_token(&access_token).unwrap();
-    let user_id = access_claims.sub;
+    let access_claims: AccessClaims = jwt_claims::decode_token(&tokens.access_token).unwrap();
+    let user_id = access_claims.sub.parse().unwrap();
 
-    assert_eq!(
-        auth::revoke_user(&access_token, &user_id).await.unwrap(),
-        StatusCode::OK
-    );
+    client.revoke_user(user_id).await.unwrap();
 
     // try access to the root handler with the same token again
-    assert_eq!(
-        route::fetch_root(&access_token).await.unwrap(),
-        StatusCode::UNAUTHORIZED
-    );
+    let error = client.root().await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 
     // needs pause to pass authentication of next logins
     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
@@ -50,21 +45,19 @@ async fn revoke_all_test() {
     let config = config::get();
 
     // assert that revoked options are enabled
-    assert!(config.jwt_enable_revoked_tokens);
+    assert!(config.jwt.enable_revoked_tokens);
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let client = utils::client();
+    client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, _) = result.unwrap();
 
-    auth::revoke_all(&access_token).await.unwrap();
+    client.revoke_all().await.unwrap();
 
     // try access to the root handler with the same token again
-    assert_eq!(
-        route::fetch_root(&access_token).await.unwrap(),
-        StatusCode::UNAUTHORIZED
-    );
+    let error = client.root().await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 
     // needs pause to pass authentication of next logins
     tokio::time::sleep(tokio::time::Duration::from_secs(1)).await;
@@ -78,41 +71,38 @@ async fn cleanup_test() {
     let config = config::get();
 
     // assert that revoked options are enabled
-    assert!(config.jwt_enable_revoked_tokens);
+    assert!(config.jwt.enable_revoked_tokens);
 
     // login
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let client = utils::client();
+    client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, refresh_token) = result.unwrap();
 
-    let _initial_cleanup = auth::cleanup(&access_token).await.unwrap();
+    let _initial_cleanup = client.cleanup().await.unwrap();
 
     // expected 2 tokens to expire after resfresh
-    let (status, result) = auth::refresh(&refresh_token).await.unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (_, refresh_token) = result.unwrap();
+    client.refresh().await.unwrap();
 
     // expected 2 tokens to expire after logout
-    assert_eq!(auth::logout(&refresh_token).await.unwrap(), StatusCode::OK);
+    client.logout().await.unwrap();
 
     // wait to make sure that tokens expire
     tokio::time::sleep(tokio::time::Duration::from_secs(
-        (config.jwt_expire_access_token_seconds + config.jwt_validation_leeway_seconds) as u64,
+        (config.jwt.expire_access_token_seconds + config.jwt.validation_leeway_seconds) as u64,
     ))
     .await;
     tokio::time::sleep(tokio::time::Duration::from_secs(
-        (config.jwt_expire_refresh_token_seconds + config.jwt_validation_leeway_seconds) as u64,
+        (config.jwt.expire_refresh_token_seconds + config.jwt.validation_leeway_seconds) as u64,
     ))
     .await;
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, _) = result.unwrap();
 
-    let deleted_tokens = auth::cleanup(&access_token).await.unwrap();
+    let deleted_tokens = client.cleanup().await.unwrap();
     assert!(deleted_tokens >= 4);
 }
@@ -0,0 +1,96 @@
+use axum_web::{
+    application::{check, config},
+    infrastructure::database::DatabasePool,
+};
+
+pub mod common;
+use common::utils;
+
+#[tokio::test]
+async fn check_services_test() {
+    utils::load_config().await;
+    let config = config::get();
+
+    for check in check::check_services(&config).await {
+        assert!(check.result.is_ok(), "{}: {:?}", check.name, check.result);
+    }
+}
+
+#[tokio::test]
+async fn migration_status_test() {
+    utils::load_config().await;
+    let config = config::get();
+
+    let pool = DatabasePool::connect(&config).await.unwrap();
+    pool.ping().await.unwrap();
+    pool.migrate().await.unwrap();
+    let migrations = pool.migration_status().await.unwrap();
+    assert_eq!(migrations.len(), 4);
+    assert!(migrations.iter().all(|migration| migration.applied));
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn migration_undo_test() {
+    use axum_web::infrastructure::database::MigrationStatus;
+
+    // a database of its own, the reverted migrations drop the users table
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let pool = DatabasePool::Sqlite(pool);
+
+    let applied = |migrations: Vec<MigrationStatus>| {
+        migrations
+            .into_iter()
+            .filter(|migration| migration.applied)
+            .count()
+    };
+
+    assert!(pool.undo(None).await.unwrap().is_empty());
+    pool.migrate().await.unwrap();
+    assert_eq!(applied(pool.migration_status().await.unwrap()), 4);
+
+    // the last applied migration by default
+    let reverted = pool.undo(None).await.unwrap();
+    assert_eq!(reverted, vec![20241103120000]);
+    assert_eq!(applied(pool.migration_status().await.unwrap()), 3);
+
+    let reverted = pool.undo(Some(0)).await.unwrap();
+    assert_eq!(
+        reverted,
+        vec![20241027120000, 20241020120000, 20231113222801]
+    );
+    assert_eq!(applied(pool.migration_status().await.unwrap()), 0);
+
+    pool.migrate().await.unwrap();
+    assert_eq!(applied(pool.migration_status().await.unwrap()), 4);
+}
+
+#[cfg(feature = "sqlite")]
+#[tokio::test]
+async fn migration_status_read_only_test() {
+    // a database of its own, never migrated
+    let pool = sqlx::sqlite::SqlitePoolOptions::new()
+        .max_connections(1)
+        .connect("sqlite::memory:")
+        .await
+        .unwrap();
+    let pool = DatabasePool::Sqlite(pool);
+
+    let migrations = pool.migration_status().await.unwrap();
+    assert_eq!(migrations.len(), 4);
+    assert!(migrations.iter().all(|migration| !migration.applied));
+
+    // the status does not create the migrations table
+    let DatabasePool::Sqlite(sqlite) = &pool else {
+        unreachable!()
+    };
+    let tables: i64 = sqlx::query_scalar("SELECT COUNT(*) FROM sqlite_master WHERE type = 'table'")
+        .fetch_one(sqlite)
+        .await
+        .unwrap();
+    assert_eq!(tables, 0);
+}
@@ -1,8 +1,12 @@
 use bytes::{Buf, Bytes};
 use http_body_util::{BodyExt, Empty};
-use hyper::Request;
+use hyper::{Request, StatusCode};
 use hyper_util::rt::TokioIo;
+#[cfg(unix)]
+use std::path::Path;
 use tokio::net::TcpStream;
+#[cfg(unix)]
+use tokio::net::UnixStream;
 
 use super::GenericResult;
 
@@ -38,3 +42,38 @@ pub async fn fetch_url_hyper(url: &str) -> GenericResult<String> {
     let content = String::from_utf8(body.chunk().to_vec())?;
     Ok(content)
 }
+
+// fetch a path of the api served on a unix domain socket, the `reqwest` client supports tcp only
+#[cfg(unix)]
+pub async fn fetch_unix_socket(
+    socket: &Path,
+    path: &str,
+    access_token: Option<&str>,
+) -> GenericResult<(StatusCode, String)> {
+    let stream = UnixStream::connect(socket).await?;
+    let io = TokioIo::new(stream);
+
+    let (mut sender, conn) = hyper::client::conn::http1::handshake(io).await?;
+    tokio::task::spawn(async move {
+        if let Err(err) = conn.await {
+            println!("Connection failed: {:?}", err);
+        }
+    });
+
+    let mut req = Request::builder()
+        .uri(path)
+        .header(hyper::header::HOST, "localhost");
+    if let Some(access_token) = access_token {
+        req = req.header(
+            hyper::header::AUTHORIZATION,
+            format!("Bearer {}", access_token),
+        );
+    }
+    let res = sender
+        .send_request(req.body(Empty::<Bytes>::new())?)
+        .await?;
+
+    let status = res.status();
+    let body = res.collect().await?.to_bytes();
+    Ok((status, String::from_utf8(body.to_vec())?))
+}
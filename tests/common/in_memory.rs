@@ -0,0 +1,138 @@
+// runs the service routes in-process on top of the in-memory repositories,
+// no database, redis or listening socket is needed
+use axum::{
+    body::Body,
+    http::{header, HeaderMap, HeaderName, Method, Request, StatusCode},
+    Router,
+};
+use axum_web::{
+    api::router,
+    application::{config, state::SharedState},
+    domain::models::user::User,
+    infrastructure::memory::{InMemoryRevocationRepository, InMemoryUserRepository},
+};
+use http_body_util::BodyExt;
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+use super::{utils, TEST_ADMIN_PASSWORD_HASH, TEST_ADMIN_USERNAME, TEST_USER_PASSWORD_SALT};
+
+pub struct TestApp {
+    router: Router,
+}
+
+impl TestApp {
+    pub async fn start(users: Vec<User>) -> TestApp {
+        let state = utils::build_state(
+            Arc::new(InMemoryUserRepository::with_users(users)),
+            Arc::new(InMemoryRevocationRepository::new()),
+        );
+        TestApp::with_state(state)
+    }
+
+    // the routes on top of a state shared with the test
+    pub fn with_state(state: SharedState) -> TestApp {
+        config::init(utils::test_config());
+        TestApp {
+            router: router::routes(state),
+        }
+    }
+
+    pub async fn request(
+        &self,
+        method: Method,
+        uri: &str,
+        access_token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, serde_json::Value) {
+        let (status, _, json) = self
+            .request_with_headers(method, uri, access_token, body)
+            .await;
+        (status, json)
+    }
+
+    pub async fn request_with_headers(
+        &self,
+        method: Method,
+        uri: &str,
+        access_token: Option<&str>,
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, HeaderMap, serde_json::Value) {
+        self.send(method, uri, access_token, &[], body).await
+    }
+
+    // sends a request with additional headers
+    pub async fn send(
+        &self,
+        method: Method,
+        uri: &str,
+        access_token: Option<&str>,
+        headers: &[(HeaderName, &str)],
+        body: Option<serde_json::Value>,
+    ) -> (StatusCode, HeaderMap, serde_json::Value) {
+        let mut request = Request::builder().method(method).uri(uri);
+        if let Some(access_token) = access_token {
+            request = request.header(header::AUTHORIZATION, format!("Bearer {}", access_token));
+        }
+        for (name, value) in headers {
+            request = request.header(name, *value);
+        }
+        let request = match body {
+            Some(json) => request
+                .header(header::CONTENT_TYPE, "application/json")
+                .body(Body::from(json.to_string())),
+            None => request.body(Body::empty()),
+        }
+        .unwrap();
+
+        let response = self.router.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let headers = response.headers().clone();
+        let bytes = response.into_body().collect().await.unwrap().to_bytes();
+        let json = if bytes.is_empty() {
+            serde_json::Value::Null
+        } else {
+            serde_json::from_slice(&bytes)
+                .unwrap_or_else(|_| String::from_utf8_lossy(&bytes).into())
+        };
+        (status, headers, json)
+    }
+
+    pub async fn login(&self, username: &str, password_hash: &str) -> (StatusCode, Option<String>) {
+        let body = serde_json::json!({"username": username, "password_hash": password_hash});
+        let (status, json) = self
+            .request(Method::POST, "/v1/auth/login", None, Some(body))
+            .await;
+        let access_token = json["access_token"].as_str().map(|s| s.to_string());
+        (status, access_token)
+    }
+
+    pub async fn login_admin(&self) -> String {
+        let (status, access_token) = self
+            .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        access_token.unwrap()
+    }
+}
+
+pub fn test_user(username: &str, password_hash: &str, roles: &str) -> User {
+    User {
+        id: Uuid::new_v4(),
+        username: username.to_string(),
+        email: format!("{}@email.com", username),
+        password_hash: password_hash.to_string(),
+        password_salt: TEST_USER_PASSWORD_SALT.to_string(),
+        active: true,
+        roles: roles.to_string(),
+        created_at: Some(chrono::Utc::now().naive_utc()),
+        updated_at: Some(chrono::Utc::now().naive_utc()),
+        version: 1,
+        deleted_at: None,
+    }
+}
+
+pub fn test_admin() -> User {
+    test_user(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH, "admin")
+}
@@ -1,11 +1,17 @@
 pub const TEST_ADMIN_USERNAME: &str = "admin";
 pub const TEST_ADMIN_PASSWORD_HASH: &str =
     "7c44575b741f02d49c3e988ba7aa95a8fb6d90c0ef63a97236fa54bfcfbd9d51";
+// valid credentials of the users created by the tests
+pub const TEST_USER_PASSWORD_HASH: &str =
+    "0b14d501a594442a01c6859541bcb3e8164d183d32937b851835442f69d5c94e";
+pub const TEST_USER_PASSWORD_SALT: &str = "f3Kp9xQ2mV7bL4sT";
+// the signing secret of the in-process tests
+pub const TEST_JWT_SECRET: &str = "k8Rz2WqYp5Nc7Lm3Xv9Bt4Hd6Gf1Js0A";
 
 type GenericResult<T> = std::result::Result<T, Box<dyn std::error::Error + Send + Sync>>;
 
-pub mod auth;
 pub mod fetch;
-pub mod route;
+pub mod in_memory;
+pub mod redis_stub;
 pub mod users;
 pub mod utils;
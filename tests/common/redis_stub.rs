@@ -0,0 +1,209 @@
+// a minimal in-process stand-in for Redis, Redis Sentinel and Redis Cluster nodes
+// supports only the commands used by the service and the redis client handshakes
+
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+};
+use tokio::{
+    io::{AsyncBufReadExt, AsyncReadExt, AsyncWriteExt, BufReader},
+    net::{TcpListener, TcpStream},
+};
+
+#[derive(Clone)]
+pub enum StubRole {
+    Master,
+    Sentinel {
+        master_name: String,
+        master_port: u16,
+    },
+    Cluster,
+}
+
+#[derive(Clone, Default)]
+pub struct StubState {
+    strings: HashMap<String, String>,
+    hashes: HashMap<String, HashMap<String, String>>,
+    // commands received, in upper case with arguments
+    pub commands: Vec<Vec<String>>,
+}
+
+pub struct RedisStub {
+    pub port: u16,
+    pub state: Arc<Mutex<StubState>>,
+}
+
+impl RedisStub {
+    pub async fn start(role: StubRole) -> RedisStub {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let port = listener.local_addr().unwrap().port();
+        let state = Arc::new(Mutex::new(StubState::default()));
+
+        let shared_state = state.clone();
+        tokio::spawn(async move {
+            while let Ok((stream, _)) = listener.accept().await {
+                let role = role.clone();
+                let state = shared_state.clone();
+                tokio::spawn(async move { handle(stream, role, port, state).await });
+            }
+        });
+
+        RedisStub { port, state }
+    }
+
+    pub fn received(&self, command: &[&str]) -> bool {
+        self.state
+            .lock()
+            .unwrap()
+            .commands
+            .iter()
+            .any(|c| c.iter().map(|s| s.as_str()).eq(command.iter().copied()))
+    }
+}
+
+enum Reply {
+    Simple(&'static str),
+    Error(String),
+    Integer(i64),
+    Bulk(Option<String>),
+    Array(Vec<Reply>),
+}
+
+impl Reply {
+    fn encode(&self, out: &mut Vec<u8>) {
+        match self {
+            Reply::Simple(s) => out.extend_from_slice(format!("+{}\r\n", s).as_bytes()),
+            Reply::Error(e) => out.extend_from_slice(format!("-{}\r\n", e).as_bytes()),
+            Reply::Integer(i) => out.extend_from_slice(format!(":{}\r\n", i).as_bytes()),
+            Reply::Bulk(None) => out.extend_from_slice(b"$-1\r\n"),
+            Reply::Bulk(Some(s)) => {
+                out.extend_from_slice(format!("${}\r\n{}\r\n", s.len(), s).as_bytes())
+            }
+            Reply::Array(items) => {
+                out.extend_from_slice(format!("*{}\r\n", items.len()).as_bytes());
+                items.iter().for_each(|item| item.encode(out));
+            }
+        }
+    }
+}
+
+fn bulk(s: &str) -> Reply {
+    Reply::Bulk(Some(s.to_string()))
+}
+
+async fn read_command(reader: &mut BufReader<TcpStream>) -> Option<Vec<String>> {
+    let mut line = String::new();
+    reader.read_line(&mut line).await.ok()?;
+    let count: usize = line.trim_end().strip_prefix('*')?.parse().ok()?;
+    let mut args = Vec::with_capacity(count);
+    for _ in 0..count {
+        line.clear();
+        reader.read_line(&mut line).await.ok()?;
+        let len: usize = line.trim_end().strip_prefix('$')?.parse().ok()?;
+        let mut buf = vec![0; len + 2];
+        reader.read_exact(&mut buf).await.ok()?;
+        buf.truncate(len);
+        args.push(String::from_utf8(buf).ok()?);
+    }
+    Some(args)
+}
+
+async fn handle(stream: TcpStream, role: StubRole, port: u16, state: Arc<Mutex<StubState>>) {
+    let mut reader = BufReader::new(stream);
+    while let Some(mut args) = read_command(&mut reader).await {
+        args[0] = args[0].to_uppercase();
+        let reply = execute(&args, &role, port, &mut state.lock().unwrap());
+        let mut out = Vec::new();
+        reply.encode(&mut out);
+        if reader.get_mut().write_all(&out).await.is_err() {
+            break;
+        }
+    }
+}
+
+fn execute(args: &[String], role: &StubRole, port: u16, state: &mut StubState) -> Reply {
+    state.commands.push(args.to_vec());
+    let arg = |i: usize| args.get(i).cloned().unwrap_or_default();
+
+    match (args[0].as_str(), role) {
+        ("PING", _) => Reply::Simple("PONG"),
+        ("AUTH" | "SELECT" | "CLIENT" | "READONLY", _) => Reply::Simple("OK"),
+        ("ROLE", StubRole::Master) => Reply::Array(vec![
+            bulk("master"),
+            Reply::Integer(0),
+            Reply::Array(vec![]),
+        ]),
+        (
+            "SENTINEL",
+            StubRole::Sentinel {
+                master_name,
+                master_port,
+            },
+        ) => {
+            let master = [
+                ("name", master_name.clone()),
+                ("ip", "127.0.0.1".to_string()),
+                ("port", master_port.to_string()),
+                ("flags", "master".to_string()),
+            ];
+            Reply::Array(vec![Reply::Array(
+                master
+                    .iter()
+                    .flat_map(|(k, v)| [bulk(k), bulk(v)])
+                    .collect(),
+            )])
+        }
+        ("CLUSTER", StubRole::Cluster) => Reply::Array(vec![Reply::Array(vec![
+            Reply::Integer(0),
+            Reply::Integer(16383),
+            Reply::Array(vec![
+                bulk("127.0.0.1"),
+                Reply::Integer(port as i64),
+                bulk("stub"),
+            ]),
+        ])]),
+        (_, StubRole::Sentinel { .. }) => Reply::Error("ERR sentinel stub".to_string()),
+        ("GET", _) => Reply::Bulk(state.strings.get(&arg(1)).cloned()),
+        ("SET", _) => {
+            state.strings.insert(arg(1), arg(2));
+            Reply::Simple("OK")
+        }
+        ("HSET", _) => {
+            let hash = state.hashes.entry(arg(1)).or_default();
+            let added = args[2..]
+                .chunks(2)
+                .filter(|kv| hash.insert(kv[0].clone(), kv[1].clone()).is_none())
+                .count();
+            Reply::Integer(added as i64)
+        }
+        ("HGET", _) => Reply::Bulk(
+            state
+                .hashes
+                .get(&arg(1))
+                .and_then(|h| h.get(&arg(2)).cloned()),
+        ),
+        ("HEXISTS", _) => Reply::Integer(
+            state
+                .hashes
+                .get(&arg(1))
+                .is_some_and(|h| h.contains_key(&arg(2))) as i64,
+        ),
+        ("HDEL", _) => {
+            let hash = state.hashes.entry(arg(1)).or_default();
+            let deleted = args[2..]
+                .iter()
+                .filter(|k| hash.remove(*k).is_some())
+                .count();
+            Reply::Integer(deleted as i64)
+        }
+        ("HLEN", _) => Reply::Integer(state.hashes.get(&arg(1)).map_or(0, |h| h.len()) as i64),
+        ("HGETALL", _) => Reply::Array(
+            state
+                .hashes
+                .get(&arg(1))
+                .map(|h| h.iter().flat_map(|(k, v)| [bulk(k), bulk(v)]).collect())
+                .unwrap_or_default(),
+        ),
+        (command, _) => Reply::Error(format!("ERR unknown command '{}'", command)),
+    }
+}
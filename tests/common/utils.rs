@@ -1,11 +1,15 @@
-use axum_web::application::config;
-use std::time::Duration;
+use axum_web::application::{
+    config::{self, Config, ConfigArgs},
+    repository::{revocation_repo::RevocationRepository, user_repo::UserRepository},
+    state::{AppState, SharedState},
+};
+use axum_web_client::{Client, Tokens};
+use std::{sync::Arc, time::Duration};
 use tokio::sync::oneshot;
 use tokio::time::{timeout_at, Instant};
 
 pub async fn start_api() {
-    std::env::set_var("ENV_TEST", "1");
-    config::load();
+    load_config().await;
 
     let (api_ready_tx, api_ready_rx) = oneshot::channel();
 
@@ -20,6 +24,59 @@ pub async fn start_api() {
     }
 }
 
+// starts the api server by the test configuration with the given overrides, e.g. of the listeners
+pub async fn start_api_with(overrides: &[String]) {
+    std::env::set_var("ENV_TEST", "1");
+    let args = ConfigArgs {
+        file: None,
+        overrides: overrides.to_vec(),
+    };
+    config::init(config::resolve(config::layers(&args)).await.unwrap());
+    start_api().await;
+}
+
+pub async fn load_config() {
+    std::env::set_var("ENV_TEST", "1");
+    config::load().await;
+}
+
+// the configuration of the in-process tests, independent of the environment and .env_test
+pub fn test_config() -> Config {
+    Config::builder()
+        .set("jwt.secret", super::TEST_JWT_SECRET)
+        .set("jwt.expire_access_token_seconds", "2")
+        .set("jwt.expire_refresh_token_seconds", "5")
+        .set("jwt.validation_leeway_seconds", "1")
+        .set("jwt.revocation_store", "memory")
+        .build()
+        .unwrap()
+}
+
+pub fn build_state(
+    user_repo: Arc<dyn UserRepository>,
+    revocation_repo: Arc<dyn RevocationRepository>,
+) -> SharedState {
+    Arc::new(AppState {
+        user_repo,
+        revocation_repo,
+    })
+}
+
+// a client of the started api server
+pub fn client() -> Client {
+    Client::new(config::get().service_http_addr())
+}
+
+// a client holding the given tokens, e.g. revoked or malformed ones
+pub fn client_with_tokens(access_token: &str, refresh_token: &str) -> Client {
+    let client = client();
+    client.set_tokens(Some(Tokens {
+        access_token: access_token.to_string(),
+        refresh_token: refresh_token.to_string(),
+    }));
+    client
+}
+
 pub fn build_url(version: &str, path: &str, url: &str) -> reqwest::Url {
     let url = format!(
         "{}/{}/{}/{}",
@@ -30,8 +87,3 @@ pub fn build_url(version: &str, path: &str, url: &str) -> reqwest::Url {
     );
     reqwest::Url::parse(&url).unwrap()
 }
-
-pub fn build_path(version: &str, path: &str) -> reqwest::Url {
-    let url = format!("{}/{}/{}", config::get().service_http_addr(), version, path);
-    reqwest::Url::parse(&url).unwrap()
-}
@@ -0,0 +1,137 @@
+use axum_web::application::{
+    config::{self, Config, ConfigArgs, Reload},
+    security::{
+        jwt_auth,
+        jwt_claims::{self, AccessClaims},
+    },
+};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use std::{
+    path::Path,
+    time::{Duration, SystemTime},
+};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+pub mod common;
+use common::{in_memory::test_admin, utils, TEST_JWT_SECRET};
+
+// writes the configuration file with the given modification time,
+// the same time hides the change from the file watch
+fn write_file(path: &Path, content: &str, modified: SystemTime) {
+    std::fs::write(path, content).unwrap();
+    std::fs::File::options()
+        .write(true)
+        .open(path)
+        .unwrap()
+        .set_modified(modified)
+        .unwrap();
+}
+
+fn cors(origin: &str) -> String {
+    format!("[cors]\nallowed_origins = [\"{}\"]\n", origin)
+}
+
+#[tokio::test]
+async fn config_reload_test() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    recorder.install().unwrap();
+    config::init(utils::test_config());
+
+    // the reloadable settings are swapped, the others wait for a restart
+    let reload = config::swap(
+        Config::builder()
+            .set("jwt.secret", TEST_JWT_SECRET)
+            .set("jwt.expire_access_token_seconds", "4")
+            .set("jwt.expire_refresh_token_seconds", "5")
+            .set("jwt.validation_leeway_seconds", "1")
+            .set("jwt.revocation_store", "memory")
+            .set("service.port", "4000")
+            .build()
+            .unwrap(),
+    );
+    assert_eq!(
+        reload,
+        Reload {
+            changed: vec!["jwt.expire_access_token_seconds"],
+            ignored: vec!["service"],
+        }
+    );
+    let config = config::get();
+    assert_eq!(config.jwt.expire_access_token_seconds, 4);
+    assert_eq!(config.service.port, 3000);
+
+    // the tokens are generated by the reloaded lifetimes
+    let tokens = jwt_auth::generate_tokens(test_admin());
+    let claims: AccessClaims = jwt_claims::decode_token(&tokens.access_token).unwrap();
+    assert_eq!(claims.exp - claims.iat, 4);
+
+    // a change of the configuration file is reloaded
+    let file = std::env::temp_dir().join(format!("axum-web-{}.toml", Uuid::new_v4()));
+    let start = SystemTime::now() - Duration::from_secs(60);
+    write_file(&file, &cors("https://a.example.com"), start);
+    let args = ConfigArgs {
+        file: Some(file.clone()),
+        overrides: vec![format!("jwt.secret={}", TEST_JWT_SECRET)],
+    };
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    tokio::spawn(config::watch(args.clone(), move |config| {
+        tx.send(config.cors.default.allowed_origins.clone())
+            .unwrap();
+    }));
+    tokio::time::sleep(Duration::from_millis(100)).await;
+
+    let changed = start + Duration::from_secs(10);
+    write_file(&file, &cors("https://b.example.com"), changed);
+    let origins = tokio::time::timeout(Duration::from_secs(10), rx.recv()).await;
+    assert_eq!(origins.unwrap().unwrap(), vec!["https://b.example.com"]);
+    assert_eq!(
+        config::get().cors.default.allowed_origins,
+        vec!["https://b.example.com"]
+    );
+
+    // SIGHUP reloads the configuration
+    write_file(&file, &cors("https://c.example.com"), changed);
+    let status = std::process::Command::new("kill")
+        .args(["-HUP", &std::process::id().to_string()])
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let origins = tokio::time::timeout(Duration::from_secs(10), rx.recv()).await;
+    assert_eq!(origins.unwrap().unwrap(), vec!["https://c.example.com"]);
+
+    // an invalid configuration is not swapped
+    write_file(&file, &cors("c.example.com"), changed);
+    let error = config::reload(&args).await.unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec!["cors.allowed_origins: invalid origin `c.example.com`, expected *, an http(s):// origin or a wildcard subdomain like https://*.example.com"]
+    );
+    assert_eq!(
+        config::get().cors.default.allowed_origins,
+        vec!["https://c.example.com"]
+    );
+    std::fs::remove_file(&file).unwrap();
+
+    // every reload is counted by its result
+    let counts: Vec<(String, u64)> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .filter(|(key, _, _, _)| key.key().name() == "config_reloads_total")
+        .filter_map(|(key, _, _, value)| {
+            let labels: Vec<String> = key
+                .key()
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            match value {
+                DebugValue::Counter(count) => Some((labels.join(","), count)),
+                _ => None,
+            }
+        })
+        .collect();
+    assert!(counts.contains(&("result=success".to_string(), 2)));
+    assert!(counts.contains(&("result=failure".to_string(), 1)));
+}
@@ -0,0 +1,206 @@
+use axum_web::application::{
+    api_version::ApiVersion,
+    config::{Config, RedisMode, RevocationStore},
+};
+use redis::ConnectionAddr;
+use std::{collections::HashMap, path::PathBuf};
+use uuid::Uuid;
+
+pub mod common;
+use common::TEST_JWT_SECRET;
+
+fn vars(pairs: &[(&str, &str)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.to_string()))
+        .collect();
+    move |key| vars.get(key).cloned()
+}
+
+// a configuration file of the test, removed by the caller
+fn write_file(extension: &str, content: &str) -> PathBuf {
+    let path = std::env::temp_dir().join(format!("axum-web-{}.{}", Uuid::new_v4(), extension));
+    std::fs::write(&path, content).unwrap();
+    path
+}
+
+#[test]
+fn config_defaults_test() {
+    let config = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .build()
+        .unwrap();
+    assert_eq!(config.service.socket_addr(), "127.0.0.1:3000");
+    assert_eq!(config.api.version_default, ApiVersion::V1);
+    assert_eq!(config.api.version_precedence.len(), 3);
+    assert_eq!(config.database.url, None);
+    assert_eq!(
+        config.database_url(),
+        "postgresql://postgres:@127.0.0.1:5432/axum_web"
+    );
+    assert_eq!(config.redis.mode, RedisMode::Standalone);
+    assert_eq!(
+        config.redis.connection_info().addr.to_string(),
+        "127.0.0.1:6379"
+    );
+    assert_eq!(config.redis.connection_info().redis.db, 0);
+    assert_eq!(config.jwt.expire_access_token_seconds, 3600);
+    assert!(config.jwt.enable_revoked_tokens);
+    assert_eq!(config.jwt.revocation_store, RevocationStore::Redis);
+    assert_eq!(config.cors.default.allowed_origins, vec!["*"]);
+}
+
+#[test]
+fn redis_credentials_test() {
+    // the credentials are not part of a url, so the reserved characters are kept as is
+    let config = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .vars(vars(&[
+            ("REDIS_DB", "2"),
+            ("REDIS_TLS", "true"),
+            ("REDIS_USERNAME", "app:user"),
+            ("REDIS_PASSWORD", "p@ss/word#1"),
+            ("REDIS_SENTINEL_PASSWORD", "s%3Acret@"),
+        ]))
+        .build()
+        .unwrap();
+
+    let info = config.redis.connection_info();
+    assert!(
+        matches!(info.addr, ConnectionAddr::TcpTls { ref host, port: 6379, .. } if host == "127.0.0.1")
+    );
+    assert_eq!(info.redis.db, 2);
+    assert_eq!(info.redis.username.as_deref(), Some("app:user"));
+    assert_eq!(info.redis.password.as_deref(), Some("p@ss/word#1"));
+
+    let node = config.redis.node_connection_info("10.0.0.1", 7000);
+    assert_eq!(node.redis.db, 0);
+    assert_eq!(node.redis.password.as_deref(), Some("p@ss/word#1"));
+
+    let sentinel = config.redis.sentinel_connection_info("10.0.0.1", 26379);
+    assert_eq!(sentinel.redis.username, None);
+    assert_eq!(sentinel.redis.password.as_deref(), Some("s%3Acret@"));
+}
+
+#[test]
+fn config_layers_test() {
+    let toml = write_file(
+        "toml",
+        &format!(
+            r#"
+[service]
+host = "0.0.0.0"
+port = 4000
+
+[redis]
+mode = "sentinel"
+nodes = ["10.0.0.1:26379", "10.0.0.2:26379"]
+
+[jwt]
+secret = "{}"
+expire_access_token_seconds = 60
+"#,
+            TEST_JWT_SECRET
+        ),
+    );
+    let yaml = write_file(
+        "yaml",
+        r#"
+service:
+  port: 4001
+cors:
+  allowed_origins:
+    - https://app.example.com
+"#,
+    );
+
+    // the file, the environment and the overrides in the order of precedence
+    let config = Config::builder()
+        .file(&toml)
+        .file(&yaml)
+        .vars(vars(&[
+            ("SERVICE_PORT", "5000"),
+            ("REVOCATION_STORE", "memory"),
+        ]))
+        .set("service.port", "6000")
+        .build()
+        .unwrap();
+    std::fs::remove_file(toml).unwrap();
+    std::fs::remove_file(yaml).unwrap();
+
+    assert_eq!(config.service.host, "0.0.0.0");
+    assert_eq!(config.service.port, 6000);
+    assert_eq!(config.redis.mode, RedisMode::Sentinel);
+    assert_eq!(
+        config.redis.nodes(),
+        vec![
+            ("10.0.0.1".to_string(), 26379),
+            ("10.0.0.2".to_string(), 26379)
+        ]
+    );
+    assert_eq!(config.jwt.secret, TEST_JWT_SECRET);
+    assert_eq!(config.jwt.expire_access_token_seconds, 60);
+    assert_eq!(config.jwt.revocation_store, RevocationStore::Memory);
+    assert_eq!(
+        config.cors.default.allowed_origins,
+        vec!["https://app.example.com"]
+    );
+
+    let config = Config::builder()
+        .vars(vars(&[("JWT_SECRET", TEST_JWT_SECRET)]))
+        .set_arg("service.host = ::1")
+        .build()
+        .unwrap();
+    assert_eq!(config.service.socket_addr(), "[::1]:3000");
+}
+
+#[test]
+fn config_errors_test() {
+    // the missing secret is named with its environment variable
+    let error = Config::builder().build().unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec!["jwt.secret is not set, set JWT_SECRET or jwt.secret in the configuration file"]
+    );
+
+    // every problem is reported at once
+    let toml = write_file("toml", "[service]\nport = 3000\nprot = 3001\n");
+    let error = Config::builder()
+        .file(&toml)
+        .file("missing.toml")
+        .vars(vars(&[
+            ("SERVICE_PORT", "x"),
+            ("REDIS_MODE", "cluster"),
+            ("REDIS_DB", "1"),
+            ("REDIS_NODES", "10.0.0.1"),
+            ("API_VERSION_PRECEDENCE", "path,query"),
+            ("JWT_SECRET", "short"),
+            ("JWT_EXPIRE_ACCESS_TOKEN_SECONDS", "x"),
+            ("JWT_EXPIRE_REFRESH_TOKEN_SECONDS", "0"),
+            ("CORS_ALLOWED_ORIGINS", "app.example.com"),
+        ]))
+        .set("service.hots", "localhost")
+        .set_arg("service.host")
+        .build()
+        .unwrap_err();
+    std::fs::remove_file(&toml).unwrap();
+
+    let expected = [
+        format!("{}: unknown setting service.prot", toml.display()),
+        "missing.toml: No such file or directory (os error 2)".to_string(),
+        "unknown setting service.hots".to_string(),
+        "service.host: expected an override as key=value".to_string(),
+        "service.port: invalid value `x`, expected a port number".to_string(),
+        "api.version_precedence: invalid value `query`, expected path, header or media_type"
+            .to_string(),
+        "jwt.expire_access_token_seconds: invalid value `x`, expected a number".to_string(),
+        "redis.nodes: invalid node `10.0.0.1`, expected host:port".to_string(),
+        "redis.db: Redis Cluster supports only the database 0".to_string(),
+        "jwt.secret: expected at least 32 characters, e.g. by `axum-web-admin generate-jwt-secret`"
+            .to_string(),
+        "jwt.expire_refresh_token_seconds: expected to outlive the access tokens".to_string(),
+        "cors.allowed_origins: invalid origin `app.example.com`, expected *, an http(s):// origin or a wildcard subdomain like https://*.example.com"
+            .to_string(),
+    ];
+    assert_eq!(error.errors, expected);
+}
@@ -0,0 +1,198 @@
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    Router,
+};
+use axum_web::{
+    api::router,
+    application::{
+        config::{self, Config, CorsPolicy},
+        cors,
+    },
+    infrastructure::memory::{InMemoryRevocationRepository, InMemoryUserRepository},
+};
+use std::sync::Arc;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub mod common;
+use common::{utils, TEST_JWT_SECRET};
+
+const CORS_CONFIG: &str = r#"
+[jwt]
+revocation_store = "memory"
+
+[cors]
+allowed_origins = ["https://app.example.com", "https://*.example.com"]
+allowed_methods = ["GET", "POST"]
+max_age_seconds = 300
+
+[cors.auth]
+allowed_origins = ["https://app.example.com"]
+allow_credentials = true
+"#;
+
+fn cors_config() -> Config {
+    let file = std::env::temp_dir().join(format!("axum-web-{}.toml", Uuid::new_v4()));
+    std::fs::write(&file, CORS_CONFIG).unwrap();
+    let config = Config::builder()
+        .file(&file)
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .build();
+    std::fs::remove_file(file).unwrap();
+    config.unwrap()
+}
+
+fn app() -> Router {
+    config::init(cors_config());
+    let state = utils::build_state(
+        Arc::new(InMemoryUserRepository::with_users(vec![])),
+        Arc::new(InMemoryRevocationRepository::new()),
+    );
+    router::routes(state).layer(axum::middleware::from_fn(cors::cors_middleware))
+}
+
+async fn preflight(app: &Router, uri: &str, origin: &str) -> axum::response::Response {
+    let request = Request::builder()
+        .method(Method::OPTIONS)
+        .uri(uri)
+        .header(header::ORIGIN, origin)
+        .header(header::ACCESS_CONTROL_REQUEST_METHOD, "POST")
+        .body(Body::empty())
+        .unwrap();
+    app.clone().oneshot(request).await.unwrap()
+}
+
+#[test]
+fn cors_config_test() {
+    let config = cors_config();
+    let users = config.cors.policy("users");
+    assert_eq!(users, config.cors.default);
+    assert_eq!(users.allowed_methods, vec!["GET", "POST"]);
+    assert_eq!(users.max_age_seconds, 300);
+    assert!(!users.allow_credentials);
+
+    // the overrides inherit the unset settings
+    assert_eq!(
+        config.cors.policy("auth"),
+        CorsPolicy {
+            allowed_origins: vec!["https://app.example.com".to_string()],
+            allow_credentials: true,
+            ..users
+        }
+    );
+
+    assert_eq!(cors::route_group("/v1/auth/login"), "auth");
+    assert_eq!(cors::route_group("/auth/refresh"), "auth");
+    assert_eq!(cors::route_group("/v2/users/1"), "users");
+    assert_eq!(cors::route_group("/v1/heartbeat/1"), "heartbeat");
+
+    let allowed = ["https://*.example.com".to_string()];
+    assert!(cors::origin_allowed(&allowed, "https://a.example.com"));
+    assert!(cors::origin_allowed(&allowed, "https://a.b.example.com"));
+    assert!(!cors::origin_allowed(&allowed, "https://example.com"));
+    assert!(!cors::origin_allowed(&allowed, "http://a.example.com"));
+    assert!(!cors::origin_allowed(
+        &allowed,
+        "https://a.example.com.evil.com"
+    ));
+    assert!(!cors::origin_allowed(&allowed, "https://aexample.com"));
+}
+
+#[test]
+fn cors_validation_test() {
+    let error = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("cors.allow_credentials", "true")
+        .set("cors.allowed_methods", "GET,BAD METHOD")
+        .set("cors.exposed_headers", "etag,bad header")
+        .set(
+            "cors.auth.allowed_origins",
+            "https://*.*.example.com,app.example.com",
+        )
+        .set("cors.users.allowed_origins", "https://app.example.com")
+        .set("cors.users.allowed_headers", "*")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec![
+            "cors.allowed_methods: invalid method `BAD METHOD`",
+            "cors.exposed_headers: invalid header `bad header`",
+            "cors.allow_credentials: credentials are not allowed with `*`, list the allowed origins, methods and headers",
+            "cors.auth.allowed_origins: invalid origin `https://*.*.example.com`, expected *, an http(s):// origin or a wildcard subdomain like https://*.example.com",
+            "cors.auth.allowed_origins: invalid origin `app.example.com`, expected *, an http(s):// origin or a wildcard subdomain like https://*.example.com",
+            "cors.users.allow_credentials: credentials are not allowed with `*`, list the allowed origins, methods and headers",
+        ]
+    );
+
+    // the credentials of a route group with listed origins
+    let config = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("cors.auth.allowed_origins", "https://app.example.com")
+        .set("cors.auth.allow_credentials", "true")
+        .build()
+        .unwrap();
+    assert!(config.cors.policy("auth").allow_credentials);
+    assert!(!config.cors.default.allow_credentials);
+}
+
+#[tokio::test]
+async fn cors_middleware_test() {
+    let app = app();
+
+    // the preflight requests are answered by the policy of the route group
+    let response = preflight(&app, "/v1/users", "https://admin.example.com").await;
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "https://admin.example.com"
+    );
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_METHODS], "GET,POST");
+    assert_eq!(headers[header::ACCESS_CONTROL_MAX_AGE], "300");
+    assert!(!headers.contains_key(header::ACCESS_CONTROL_ALLOW_CREDENTIALS));
+
+    let response = preflight(&app, "/v1/auth/login", "https://app.example.com").await;
+    let headers = response.headers();
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "https://app.example.com"
+    );
+    assert_eq!(headers[header::ACCESS_CONTROL_ALLOW_CREDENTIALS], "true");
+
+    // the stricter auth routes reject the wildcard subdomains
+    let response = preflight(&app, "/v1/auth/login", "https://admin.example.com").await;
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+
+    // the actual requests expose the configured headers to the allowed origins
+    let request = Request::builder()
+        .uri("/v1/heartbeat/1")
+        .header(header::ORIGIN, "https://admin.example.com")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.clone().oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let headers = response.headers();
+    assert_eq!(
+        headers[header::ACCESS_CONTROL_ALLOW_ORIGIN],
+        "https://admin.example.com"
+    );
+    assert!(headers[header::ACCESS_CONTROL_EXPOSE_HEADERS]
+        .to_str()
+        .unwrap()
+        .contains("x-request-id"));
+
+    let request = Request::builder()
+        .uri("/v1/heartbeat/1")
+        .header(header::ORIGIN, "https://example.org")
+        .body(Body::empty())
+        .unwrap();
+    let response = app.oneshot(request).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response
+        .headers()
+        .contains_key(header::ACCESS_CONTROL_ALLOW_ORIGIN));
+}
@@ -0,0 +1,187 @@
+use axum::{
+    body::Body,
+    http::{header, Method, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use axum_web::application::api_version::{
+    self, ApiVersion, DeprecatedRoute, Deprecation, DeprecationPolicy,
+};
+use metrics_util::debugging::{DebugValue, DebuggingRecorder};
+use std::sync::Arc;
+use tower::ServiceExt;
+
+pub mod common;
+use common::in_memory::{test_admin, TestApp};
+
+#[tokio::test]
+async fn deprecated_version_headers_test() {
+    let app = TestApp::start(vec![test_admin()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    // no version is deprecated by default
+    for uri in ["/v1/users?limit=1", "/v2/users?limit=1"] {
+        let (status, headers, _) = app
+            .request_with_headers(Method::GET, uri, token, None)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert!(!headers.contains_key("deprecation"));
+        assert!(!headers.contains_key("sunset"));
+    }
+
+    let policy = DeprecationPolicy {
+        versions: vec![(
+            ApiVersion::V1,
+            Deprecation {
+                deprecated_at: 1790812800,
+                sunset: Some(1830211200),
+                link: None,
+                successor: Some(ApiVersion::V2),
+                gone_after_sunset: false,
+            },
+        )],
+        routes: vec![],
+        clients: vec![],
+    };
+    let router = Router::new()
+        .route("/:version/users", get(|| async { "users" }))
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(policy),
+            api_version::deprecation_middleware,
+        ));
+    let send = |uri: &str| {
+        let request = Request::builder().uri(uri).body(Body::empty()).unwrap();
+        router.clone().oneshot(request)
+    };
+
+    let response = send("/v1/users").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["deprecation"], "@1790812800");
+    assert_eq!(
+        response.headers()["sunset"],
+        "Fri, 31 Dec 2027 00:00:00 GMT"
+    );
+    assert_eq!(
+        response.headers()[header::LINK],
+        "</v2/users>; rel=\"successor-version\""
+    );
+
+    let response = send("/v2/users").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response.headers().contains_key("deprecation"));
+}
+
+#[tokio::test]
+async fn deprecated_route_sunset_test() {
+    let recorder = DebuggingRecorder::new();
+    let snapshotter = recorder.snapshotter();
+    recorder.install().unwrap();
+
+    let deprecation = Deprecation {
+        deprecated_at: 1704067200,
+        sunset: Some(1735689600),
+        link: Some("https://example.com/deprecations"),
+        successor: None,
+        gone_after_sunset: false,
+    };
+    let policy = DeprecationPolicy {
+        versions: vec![(
+            ApiVersion::V1,
+            Deprecation {
+                gone_after_sunset: true,
+                ..deprecation.clone()
+            },
+        )],
+        routes: vec![DeprecatedRoute {
+            method: Some(Method::GET),
+            path: "/:version/legacy",
+            deprecation,
+        }],
+        clients: vec!["legacy-client"],
+    };
+    let router = Router::new()
+        .route("/:version/legacy", get(|| async { "legacy" }))
+        .route("/:version/current", get(|| async { "current" }))
+        .layer(axum::middleware::from_fn_with_state(
+            Arc::new(policy),
+            api_version::deprecation_middleware,
+        ));
+    let send_as = |uri: &str, client: &str| {
+        let request = Request::builder()
+            .uri(uri)
+            .header("x-client-id", client)
+            .header(header::USER_AGENT, "curl/8.5.0")
+            .body(Body::empty())
+            .unwrap();
+        router.clone().oneshot(request)
+    };
+    let send = |uri: &str| send_as(uri, "legacy-client");
+
+    // the route deprecation is kept even after its sunset
+    let response = send("/v2/legacy").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(response.headers()["deprecation"], "@1704067200");
+    assert_eq!(
+        response.headers()["sunset"],
+        "Wed, 01 Jan 2025 00:00:00 GMT"
+    );
+    assert_eq!(
+        response.headers()[header::LINK],
+        "<https://example.com/deprecations>; rel=\"deprecation\""
+    );
+    let response = send("/v1/legacy").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // the sunset version is gone
+    let response = send("/v1/current").await.unwrap();
+    assert_eq!(response.status(), StatusCode::GONE);
+    assert_eq!(response.headers()["deprecation"], "@1704067200");
+    let response = send("/v2/current").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(!response.headers().contains_key("deprecation"));
+
+    // the unknown clients are not counted by name
+    let response = send_as("/v2/legacy", "someone-else").await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // the usage is counted per route and client
+    let counts: Vec<(String, u64)> = snapshotter
+        .snapshot()
+        .into_vec()
+        .into_iter()
+        .filter(|(key, _, _, _)| key.key().name() == "api_deprecated_requests_total")
+        .filter_map(|(key, _, _, value)| {
+            let labels: Vec<String> = key
+                .key()
+                .labels()
+                .map(|label| format!("{}={}", label.key(), label.value()))
+                .collect();
+            match value {
+                DebugValue::Counter(count) => Some((labels.join(","), count)),
+                _ => None,
+            }
+        })
+        // the other tests of the process may count too
+        .filter(|(labels, _)| {
+            labels.contains("route=/:version/legacy") || labels.ends_with("client=legacy-client")
+        })
+        .collect();
+    assert_eq!(counts.len(), 4);
+    assert!(counts.contains(&(
+        "version=v2,route=/:version/legacy,client=other".to_string(),
+        1
+    )));
+    assert!(counts.contains(&(
+        "version=v1,route=/:version/legacy,client=legacy-client".to_string(),
+        1
+    )));
+    assert!(counts.contains(&(
+        "version=v2,route=/:version/legacy,client=legacy-client".to_string(),
+        1
+    )));
+    assert!(counts.contains(&(
+        "version=v1,route=/:version/current,client=legacy-client".to_string(),
+        1
+    )));
+}
@@ -15,17 +15,15 @@ async fn heartbeat_test() {
     utils::start_api().await;
 
     let heartbeat_id = Uuid::new_v4().to_string();
-    let url = utils::build_url(API_V1, PATH_HEARTBEAT, &heartbeat_id);
 
-    // fetch using reqwest
-    let response = reqwest::get(url.as_str()).await.unwrap();
-    let body = response.text().await.unwrap();
-    let json: serde_json::Value = serde_json::from_str(&body).unwrap();
-    assert_eq!(json["service"], SERVICE_NAME);
-    assert_eq!(json["version"], SERVICE_VERSION);
-    assert_eq!(json["heartbeat-id"], heartbeat_id);
+    // fetch using the client
+    let heartbeat = utils::client().heartbeat(&heartbeat_id).await.unwrap();
+    assert_eq!(heartbeat.service, SERVICE_NAME);
+    assert_eq!(heartbeat.version, SERVICE_VERSION);
+    assert_eq!(heartbeat.heartbeat_id, heartbeat_id);
 
     // fetch using hyper
+    let url = utils::build_url(API_V1, PATH_HEARTBEAT, &heartbeat_id);
     let body = fetch::fetch_url_hyper(url.as_str()).await.unwrap();
     let json: serde_json::Value = serde_json::from_str(&body).unwrap();
     assert_eq!(json["service"], SERVICE_NAME);
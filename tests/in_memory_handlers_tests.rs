@@ -0,0 +1,925 @@
+use axum::{
+    async_trait,
+    http::{header, HeaderName, Method, StatusCode},
+};
+use axum_web::{
+    application::{
+        config,
+        repository::{
+            repository_error::RepositoryError,
+            revocation_repo::{RevocationRepository, RevocationResult},
+            user_repo::{UserListQuery, UserPage, UserRepository},
+            RepositoryResult,
+        },
+    },
+    domain::models::user::User,
+    infrastructure::memory::{InMemoryRevocationRepository, InMemoryUserRepository},
+};
+use serde_json::json;
+use std::{collections::HashMap, sync::Arc};
+use uuid::Uuid;
+
+pub mod common;
+use common::{
+    in_memory::{test_admin, test_user, TestApp},
+    utils, *,
+};
+
+// a revocation store failing to revoke the tokens of a user
+struct FailingUserRevocation(InMemoryRevocationRepository);
+
+#[async_trait]
+impl RevocationRepository for FailingUserRevocation {
+    async fn set_global_revoke_before(&self, timestamp: usize) -> RevocationResult<()> {
+        self.0.set_global_revoke_before(timestamp).await
+    }
+
+    async fn get_global_revoke_before(&self) -> RevocationResult<Option<usize>> {
+        self.0.get_global_revoke_before().await
+    }
+
+    async fn set_user_revoke_before(&self, _: &str, _: usize) -> RevocationResult<()> {
+        Err("the revocation store is down".into())
+    }
+
+    async fn get_user_revoke_before(&self, user_id: &str) -> RevocationResult<Option<usize>> {
+        self.0.get_user_revoke_before(user_id).await
+    }
+
+    async fn add_revoked_token(&self, jti: &str, exp: usize) -> RevocationResult<()> {
+        self.0.add_revoked_token(jti, exp).await
+    }
+
+    async fn is_token_revoked(&self, jti: &str) -> RevocationResult<bool> {
+        self.0.is_token_revoked(jti).await
+    }
+
+    async fn get_revoked_tokens(&self) -> RevocationResult<HashMap<String, usize>> {
+        self.0.get_revoked_tokens().await
+    }
+
+    async fn delete_revoked_token(&self, jti: &str) -> RevocationResult<()> {
+        self.0.delete_revoked_token(jti).await
+    }
+
+    async fn revoked_tokens_count(&self) -> RevocationResult<usize> {
+        self.0.revoked_tokens_count().await
+    }
+}
+
+#[tokio::test]
+async fn login_handler_test() {
+    let mut inactive = test_user("inactive", TEST_USER_PASSWORD_HASH, "guest");
+    inactive.active = false;
+    let app = TestApp::start(vec![test_admin(), inactive]).await;
+
+    let (status, _) = app.login(TEST_ADMIN_USERNAME, "wrong").await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = app.login("unknown", TEST_ADMIN_PASSWORD_HASH).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, _) = app.login("inactive", TEST_USER_PASSWORD_HASH).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, access_token) = app
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert!(access_token.is_some());
+}
+
+#[tokio::test]
+async fn users_handlers_test() {
+    let admin = test_admin();
+    let app = TestApp::start(vec![admin.clone()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    // list users
+    let (status, json) = app.request(Method::GET, "/v1/users", token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["id"], admin.id.to_string());
+
+    // add a user
+    let user = test_user("test-user", TEST_USER_PASSWORD_HASH, "guest");
+    let (status, json) = app
+        .request(Method::POST, "/v1/users", token, Some(json!(user)))
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    assert_eq!(json["username"], "test-user");
+
+    // adding a user with the same email fails
+    let mut duplicate = user.clone();
+    duplicate.id = Uuid::new_v4();
+    let (status, json) = app
+        .request(Method::POST, "/v1/users", token, Some(json!(duplicate)))
+        .await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(json["code"], "unique_violation");
+    assert_eq!(json["errors"][0]["field"], "email");
+
+    // get the user
+    let uri = format!("/v1/users/{}", user.id);
+    let (status, json) = app.request(Method::GET, &uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["email"], user.email);
+
+    // update the user
+    let mut updated = user.clone();
+    updated.username = "test-user-updated".to_string();
+    let if_match = [(header::IF_MATCH, "*")];
+    let (status, _, json) = app
+        .send(Method::PUT, &uri, token, &if_match, Some(json!(updated)))
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["username"], "test-user-updated");
+
+    // delete the user
+    let (status, _, _) = app.send(Method::DELETE, &uri, token, &if_match, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.request(Method::GET, &uri, token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = app.send(Method::DELETE, &uri, token, &if_match, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn users_handlers_require_admin_test() {
+    let guest = test_user("guest", TEST_USER_PASSWORD_HASH, "guest");
+    let app = TestApp::start(vec![test_admin(), guest.clone()]).await;
+
+    let (status, _) = app.request(Method::GET, "/v1/users", None, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let (status, access_token) = app.login("guest", TEST_USER_PASSWORD_HASH).await;
+    assert_eq!(status, StatusCode::OK);
+    let token = access_token.as_deref();
+
+    let (status, _) = app.request(Method::GET, "/v1/users", token, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let uri = format!("/v1/users/{}", guest.id);
+    let (status, _) = app.request(Method::DELETE, &uri, token, None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+}
+
+#[tokio::test]
+async fn list_users_pagination_test() {
+    let mut users = vec![test_admin()];
+    for i in 0..5 {
+        let mut user = test_user(
+            &format!("page-user-{}", i),
+            TEST_USER_PASSWORD_HASH,
+            "guest",
+        );
+        user.active = i % 2 == 0;
+        users.push(user);
+    }
+    let app = TestApp::start(users).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    // walk through the pages sorted by username
+    let mut usernames = Vec::new();
+    let mut uri =
+        "/v1/users?username_prefix=page-&sort=username&limit=2&include_total=true".to_string();
+    loop {
+        let (status, headers, json) = app
+            .request_with_headers(Method::GET, &uri, token, None)
+            .await;
+        assert_eq!(status, StatusCode::OK);
+        assert_eq!(headers["x-total-count"], "5");
+        let page = json.as_array().unwrap();
+        assert!(page.len() <= 2);
+        usernames.extend(
+            page.iter()
+                .map(|u| u["username"].as_str().unwrap().to_string()),
+        );
+        match headers.get("x-next-cursor") {
+            Some(cursor) => {
+                uri = format!(
+                    "/v1/users?username_prefix=page-&sort=username&limit=2&include_total=true&cursor={}",
+                    cursor.to_str().unwrap()
+                )
+            }
+            None => break,
+        }
+    }
+    let expected: Vec<String> = (0..5).map(|i| format!("page-user-{}", i)).collect();
+    assert_eq!(usernames, expected);
+
+    // filtering and descending order
+    let (status, json) = app
+        .request(
+            Method::GET,
+            "/v1/users?username_prefix=page-&active=false&sort=username&order=desc",
+            token,
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let usernames: Vec<&str> = json
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|u| u["username"].as_str().unwrap())
+        .collect();
+    assert_eq!(usernames, vec!["page-user-3", "page-user-1"]);
+
+    let (status, json) = app
+        .request(Method::GET, "/v1/users?role=admin", token, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    assert_eq!(json[0]["username"], TEST_ADMIN_USERNAME);
+
+    // invalid parameters
+    let (status, _) = app
+        .request(Method::GET, "/v1/users?sort=password_hash", token, None)
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app
+        .request(Method::GET, "/v1/users?limit=0", token, None)
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = app
+        .request(Method::GET, "/v1/users?cursor=xyz", token, None)
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn patch_user_test() {
+    let user = test_user("patch-user", TEST_USER_PASSWORD_HASH, "guest");
+    let app = TestApp::start(vec![test_admin(), user.clone()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+    let uri = format!("/v1/users/{}", user.id);
+    let if_match = [(header::IF_MATCH, "*")];
+
+    // only the fields in the patch are changed
+    let (status, _, json) = app
+        .send(
+            Method::PATCH,
+            &uri,
+            token,
+            &if_match,
+            Some(json!({"email": "patched@email.com", "active": false})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["id"], user.id.to_string());
+    assert_eq!(json["username"], user.username);
+    assert_eq!(json["email"], "patched@email.com");
+    assert_eq!(json["active"], false);
+    assert!(json.get("password_hash").is_none());
+
+    // fields out of the whitelist, removals and invalid values are rejected
+    for patch in [
+        json!({"id": Uuid::new_v4()}),
+        json!({"password_hash": "abc"}),
+        json!({"username": null}),
+        json!({"active": "yes"}),
+        json!({"email": "not-an-email"}),
+        json!({"username": " "}),
+        json!(["username"]),
+    ] {
+        let (status, _, _) = app
+            .send(Method::PATCH, &uri, token, &if_match, Some(patch.clone()))
+            .await;
+        assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY, "patch: {}", patch);
+    }
+    let (status, json) = app.request(Method::GET, &uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["email"], "patched@email.com");
+
+    let uri = format!("/v1/users/{}", Uuid::new_v4());
+    let (status, _, _) = app
+        .send(
+            Method::PATCH,
+            &uri,
+            token,
+            &if_match,
+            Some(json!({"active": true})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn update_user_id_mismatch_test() {
+    let user = test_user("put-user", TEST_USER_PASSWORD_HASH, "guest");
+    let app = TestApp::start(vec![test_admin(), user.clone()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    let uri = format!("/v1/users/{}", user.id);
+    let mut updated = user.clone();
+    updated.id = Uuid::new_v4();
+    let (status, _) = app
+        .request(Method::PUT, &uri, token, Some(json!(updated)))
+        .await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    // the user is still available under the original id
+    let (status, json) = app.request(Method::GET, &uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["id"], user.id.to_string());
+}
+
+#[tokio::test]
+async fn conditional_requests_test() {
+    let user = test_user("etag-user", TEST_USER_PASSWORD_HASH, "guest");
+    let app = TestApp::start(vec![test_admin(), user.clone()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+    let uri = format!("/v1/users/{}", user.id);
+
+    let (status, headers, _) = app
+        .request_with_headers(Method::GET, &uri, token, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let etag = headers[header::ETAG].to_str().unwrap().to_string();
+
+    // the representation has not changed
+    let (status, headers, json) = app
+        .send(
+            Method::GET,
+            &uri,
+            token,
+            &[(header::IF_NONE_MATCH, &etag)],
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::NOT_MODIFIED);
+    assert_eq!(headers[header::ETAG], etag.as_str());
+    assert!(json.is_null());
+
+    // the precondition is required for changes
+    let patch = json!({"active": false});
+    let (status, _) = app
+        .request(Method::PATCH, &uri, token, Some(patch.clone()))
+        .await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+    let (status, _) = app.request(Method::DELETE, &uri, token, None).await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+
+    // the first change wins, the second one is based on a stale version
+    let (status, headers, _) = app
+        .send(
+            Method::PATCH,
+            &uri,
+            token,
+            &[(header::IF_MATCH, &etag)],
+            Some(patch.clone()),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let new_etag = headers[header::ETAG].to_str().unwrap().to_string();
+    assert_ne!(new_etag, etag);
+
+    let mut updated = user.clone();
+    updated.username = "etag-user-updated".to_string();
+    let (status, _, _) = app
+        .send(
+            Method::PUT,
+            &uri,
+            token,
+            &[(header::IF_MATCH, &etag)],
+            Some(json!(updated)),
+        )
+        .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+    let (status, _, _) = app
+        .send(
+            Method::DELETE,
+            &uri,
+            token,
+            &[(header::IF_MATCH, &etag)],
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::PRECONDITION_FAILED);
+
+    // a stale If-None-Match returns the current representation
+    let (status, _, json) = app
+        .send(
+            Method::GET,
+            &uri,
+            token,
+            &[(header::IF_NONE_MATCH, &etag)],
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["active"], false);
+    assert_eq!(json["username"], user.username);
+
+    let (status, _, _) = app
+        .send(
+            Method::DELETE,
+            &uri,
+            token,
+            &[(header::IF_MATCH, &format!("\"0\", {}", new_etag))],
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn soft_delete_restore_purge_test() {
+    let user = test_user("deleted-user", TEST_USER_PASSWORD_HASH, "guest");
+    let app = TestApp::start(vec![test_admin(), user.clone()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+    let uri = format!("/v1/users/{}", user.id);
+    let if_match = [(header::IF_MATCH, "*")];
+
+    let (status, user_token) = app.login("deleted-user", TEST_USER_PASSWORD_HASH).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app
+        .request(Method::GET, "/", user_token.as_deref(), None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+
+    // the deleted user is hidden and its tokens are revoked
+    let (status, _, _) = app.send(Method::DELETE, &uri, token, &if_match, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.request(Method::GET, &uri, token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, json) = app.request(Method::GET, "/v1/users", token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json.as_array().unwrap().len(), 1);
+    let (status, _) = app
+        .request(Method::GET, "/", user_token.as_deref(), None)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let (status, _) = app.login("deleted-user", TEST_USER_PASSWORD_HASH).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    // restore the user
+    let restore_uri = format!("{}/restore", uri);
+    let (status, json) = app.request(Method::POST, &restore_uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["username"], "deleted-user");
+    let (status, _) = app.request(Method::GET, &uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.request(Method::POST, &restore_uri, token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+
+    // the email of a deleted user can be taken, its restore then conflicts
+    let (status, _, _) = app.send(Method::DELETE, &uri, token, &if_match, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let mut other = user.clone();
+    other.id = Uuid::new_v4();
+    let (status, _) = app
+        .request(Method::POST, "/v1/users", token, Some(json!(other)))
+        .await;
+    assert_eq!(status, StatusCode::CREATED);
+    let (status, json) = app.request(Method::POST, &restore_uri, token, None).await;
+    assert_eq!(status, StatusCode::CONFLICT);
+    assert_eq!(json["errors"][0]["field"], "email");
+    let other_uri = format!("/v1/users/{}", other.id);
+    let (status, _, _) = app
+        .send(Method::DELETE, &other_uri, token, &if_match, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.request(Method::POST, &restore_uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // purge works only for the deleted users
+    let purge_uri = format!("{}/purge", uri);
+    let (status, _) = app.request(Method::DELETE, &purge_uri, token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    let (status, _, _) = app.send(Method::DELETE, &uri, token, &if_match, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.request(Method::DELETE, &purge_uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.request(Method::POST, &restore_uri, token, None).await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+}
+
+// a user repository failing to read the users by id, e.g. the pool timed out
+struct UnavailableUserRepository(InMemoryUserRepository);
+
+#[async_trait]
+impl UserRepository for UnavailableUserRepository {
+    async fn get_all(&self) -> RepositoryResult<Vec<User>> {
+        self.0.get_all().await
+    }
+
+    async fn list(&self, query: &UserListQuery) -> RepositoryResult<UserPage> {
+        self.0.list(query).await
+    }
+
+    async fn get_by_id(&self, _: Uuid) -> RepositoryResult<User> {
+        Err(RepositoryError::Unavailable("pool timed out".to_string()))
+    }
+
+    async fn get_by_username(&self, username: &str) -> RepositoryResult<User> {
+        self.0.get_by_username(username).await
+    }
+
+    async fn get_by_email(&self, email: &str) -> RepositoryResult<User> {
+        self.0.get_by_email(email).await
+    }
+
+    async fn add(&self, user: User) -> RepositoryResult<User> {
+        self.0.add(user).await
+    }
+
+    async fn update(&self, id: Uuid, user: User, version: i64) -> RepositoryResult<User> {
+        self.0.update(id, user, version).await
+    }
+
+    async fn delete(&self, id: Uuid, version: i64) -> RepositoryResult<bool> {
+        self.0.delete(id, version).await
+    }
+
+    async fn restore(&self, id: Uuid) -> RepositoryResult<User> {
+        self.0.restore(id).await
+    }
+
+    async fn purge(&self, id: Uuid) -> RepositoryResult<bool> {
+        self.0.purge(id).await
+    }
+}
+
+// a refresh token of the subject signed by the test secret
+fn refresh_token(sub: &str) -> String {
+    let now = chrono::Utc::now().timestamp() as usize;
+    let claims = json!({
+        "sub": sub,
+        "jti": Uuid::new_v4(),
+        "iat": now,
+        "exp": now + 3600,
+        "prf": Uuid::new_v4(),
+        "pex": now + 600,
+        "typ": 1,
+        "roles": "guest"
+    });
+    jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(config::get().jwt.secret.as_ref()),
+    )
+    .unwrap()
+}
+
+#[tokio::test]
+async fn refresh_errors_test() {
+    let user = test_user("refresh-user", TEST_USER_PASSWORD_HASH, "guest");
+    let state = utils::build_state(
+        Arc::new(UnavailableUserRepository(
+            InMemoryUserRepository::with_users(vec![user.clone()]),
+        )),
+        Arc::new(InMemoryRevocationRepository::new()),
+    );
+    let app = TestApp::with_state(state);
+
+    // a storage failure is not a missing user
+    let token = refresh_token(&user.id.to_string());
+    let (status, headers, json) = app
+        .request_with_headers(Method::POST, "/v1/auth/refresh", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(json["code"], "storage_unavailable");
+    assert_eq!(headers[header::RETRY_AFTER], "5");
+
+    // a subject that is not a user id
+    let token = refresh_token("not-a-uuid");
+    let (status, json) = app
+        .request(Method::POST, "/v1/auth/refresh", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["code"], "invalid_token");
+
+    // a missing user
+    let app = TestApp::start(vec![]).await;
+    let token = refresh_token(&Uuid::new_v4().to_string());
+    let (status, json) = app
+        .request(Method::POST, "/v1/auth/refresh", Some(&token), None)
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json["code"], "not_found");
+}
+
+#[tokio::test]
+async fn delete_user_revocation_failed_test() {
+    let user = test_user("deleted-user", TEST_USER_PASSWORD_HASH, "guest");
+    let state = utils::build_state(
+        Arc::new(InMemoryUserRepository::with_users(vec![
+            test_admin(),
+            user.clone(),
+        ])),
+        Arc::new(FailingUserRevocation(InMemoryRevocationRepository::new())),
+    );
+    let app = TestApp::with_state(state);
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+    let uri = format!("/v1/users/{}", user.id);
+
+    // the user is not deleted if its tokens could not be revoked
+    let (status, _, json) = app
+        .send(
+            Method::DELETE,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(json["code"], "revocation_failed");
+    let (status, _) = app.request(Method::GET, &uri, token, None).await;
+    assert_eq!(status, StatusCode::OK);
+}
+
+#[tokio::test]
+async fn problem_details_test() {
+    let user = test_user("problem-user", TEST_USER_PASSWORD_HASH, "guest");
+    let app = TestApp::start(vec![test_admin(), user.clone()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    // unknown routes, the request id is generated and echoed
+    let (status, headers, json) = app
+        .request_with_headers(Method::GET, "/unknown", None, None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_FOUND);
+    assert_eq!(headers[header::CONTENT_TYPE], "application/problem+json");
+    assert_eq!(json["status"], 404);
+    assert_eq!(json["code"], "route_not_found");
+    assert_eq!(json["type"], "urn:axum-web:problem:route_not_found");
+    assert_eq!(json["title"], "Not Found");
+    assert_eq!(json["instance"], "/unknown");
+    assert_eq!(
+        json["request_id"],
+        headers["x-request-id"].to_str().unwrap()
+    );
+
+    // the request id of the client is kept
+    let (_, headers, json) = app
+        .send(
+            Method::GET,
+            "/v3/users",
+            token,
+            &[(HeaderName::from_static("x-request-id"), "req-123")],
+            None,
+        )
+        .await;
+    assert_eq!(headers["x-request-id"], "req-123");
+    assert_eq!(json["request_id"], "req-123");
+    assert_eq!(json["code"], "invalid_api_version");
+
+    // authentication errors are distinguishable
+    let (status, json) = app
+        .request(
+            Method::POST,
+            "/v1/auth/login",
+            None,
+            Some(json!({"username": "problem-user", "password_hash": "wrong"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["code"], "wrong_credentials");
+
+    let config = config::get();
+    let now = chrono::Utc::now().timestamp() as usize;
+    let claims = json!({
+        "sub": user.id,
+        "jti": Uuid::new_v4(),
+        "iat": now - 7200,
+        "exp": now - 3600,
+        "typ": 0,
+        "roles": "guest"
+    });
+    let expired = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(config.jwt.secret.as_ref()),
+    )
+    .unwrap();
+    let (status, json) = app.request(Method::GET, "/", Some(&expired), None).await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["code"], "expired_token");
+
+    // the malformed tokens and the tokens signed by another secret are invalid
+    let mut claims = claims;
+    claims["exp"] = json!(now + 3600);
+    let foreign = jsonwebtoken::encode(
+        &jsonwebtoken::Header::default(),
+        &claims,
+        &jsonwebtoken::EncodingKey::from_secret(b"another-secret"),
+    )
+    .unwrap();
+    for invalid in ["not-a-token", foreign.as_str()] {
+        let (status, json) = app.request(Method::GET, "/", Some(invalid), None).await;
+        assert_eq!(status, StatusCode::UNAUTHORIZED);
+        assert_eq!(json["code"], "invalid_token");
+    }
+
+    let (_, user_token) = app.login("problem-user", TEST_USER_PASSWORD_HASH).await;
+    let uri = format!("/v1/users/{}", user.id);
+    let (status, _, _) = app
+        .send(
+            Method::DELETE,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, json) = app
+        .request(Method::GET, "/", user_token.as_deref(), None)
+        .await;
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    assert_eq!(json["code"], "revoked_token");
+
+    // precondition errors
+    let uri = format!("/v1/users/{}", Uuid::new_v4());
+    let (status, json) = app
+        .request(Method::PATCH, &uri, token, Some(json!({"active": false})))
+        .await;
+    assert_eq!(status, StatusCode::PRECONDITION_REQUIRED);
+    assert_eq!(json["code"], "precondition_required");
+}
+
+#[tokio::test]
+async fn request_validation_test() {
+    let user = test_user("valid-user", TEST_USER_PASSWORD_HASH, "guest");
+    let app = TestApp::start(vec![test_admin(), user.clone()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    // every invalid field is reported at once
+    let (status, json) = app
+        .request(
+            Method::POST,
+            "/v1/users",
+            token,
+            Some(json!({
+                "username": " ",
+                "email": "not-an-email",
+                "password_hash": "xyz123",
+                "password_salt": "short"
+            })),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json["code"], "validation_failed");
+    let fields: Vec<(&str, &str)> = json["errors"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|e| (e["field"].as_str().unwrap(), e["code"].as_str().unwrap()))
+        .collect();
+    assert_eq!(
+        fields,
+        vec![
+            ("email", "email"),
+            ("password_hash", "password_hash"),
+            ("password_salt", "length"),
+            ("username", "not_blank"),
+        ]
+    );
+
+    // a missing field is a validation failure too
+    let (status, json) = app
+        .request(
+            Method::POST,
+            "/v1/users",
+            token,
+            Some(json!({"username": "no-email"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json["code"], "validation_failed");
+
+    // oversized strings
+    let (status, json) = app
+        .request(
+            Method::POST,
+            "/v1/auth/login",
+            None,
+            Some(json!({"username": "x".repeat(65), "password_hash": "wrong"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json["errors"][0]["field"], "username");
+    assert_eq!(
+        json["errors"][0]["message"],
+        "length must be between 1 and 64"
+    );
+
+    // unknown roles
+    let uri = format!("/v1/users/{}", user.id);
+    let (status, _, json) = app
+        .send(
+            Method::PATCH,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            Some(json!({"roles": "guest,root"})),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json["errors"][0]["field"], "roles");
+    assert_eq!(json["errors"][0]["code"], "roles");
+
+    let mut update = user.clone();
+    update.roles = "admin,guest".to_string();
+    let (status, _, json) = app
+        .send(
+            Method::PUT,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            Some(json!(update)),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["roles"], "admin,guest");
+}
+
+#[tokio::test]
+async fn user_dto_test() {
+    let app = TestApp::start(vec![test_admin()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    // the id and the timestamps are optional on create
+    for version in ["v1", "v2"] {
+        let username = format!("dto-user-{}", version);
+        let (status, json) = app
+            .request(
+                Method::POST,
+                &format!("/{}/users", version),
+                token,
+                Some(json!({
+                    "username": username,
+                    "email": format!("{}@email.com", username),
+                    "password_hash": TEST_USER_PASSWORD_HASH,
+                    "password_salt": TEST_USER_PASSWORD_SALT
+                })),
+            )
+            .await;
+        assert_eq!(status, StatusCode::CREATED);
+        assert!(json["id"].is_string());
+        assert_eq!(json["active"], true);
+    }
+
+    // the password hash and salt are never returned
+    let (status, json) = app.request(Method::GET, "/v1/users", token, None).await;
+    assert_eq!(status, StatusCode::OK);
+    for user in json.as_array().unwrap() {
+        assert!(user.get("password_hash").is_none());
+        assert!(user.get("password_salt").is_none());
+    }
+
+    // the password hash and salt are changed together
+    let users = json.as_array().unwrap();
+    let user = users
+        .iter()
+        .find(|user| user["username"] == "dto-user-v1")
+        .unwrap()
+        .clone();
+    let uri = format!("/v1/users/{}", user["id"].as_str().unwrap());
+    let mut update = user.clone();
+    update["password_hash"] = json!(TEST_ADMIN_PASSWORD_HASH);
+    let (status, _, json) = app
+        .send(
+            Method::PUT,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            Some(update),
+        )
+        .await;
+    assert_eq!(status, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(json["errors"][0]["field"], "password_salt");
+    assert_eq!(json["errors"][0]["code"], "required");
+
+    // the body without the password keeps the current one
+    let (status, _, _) = app
+        .send(
+            Method::PUT,
+            &uri,
+            token,
+            &[(header::IF_MATCH, "*")],
+            Some(user),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    let (status, _) = app.login("dto-user-v1", TEST_USER_PASSWORD_HASH).await;
+    assert_eq!(status, StatusCode::OK);
+
+    // unknown versions are still rejected
+    let (status, json) = app.request(Method::GET, "/v3/users", token, None).await;
+    assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+    assert_eq!(json["code"], "invalid_api_version");
+}
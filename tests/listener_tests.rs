@@ -0,0 +1,177 @@
+// the unix domain sockets and the sockets of systemd socket activation
+#![cfg(unix)]
+
+use axum::{http::StatusCode, routing::get, Router};
+use axum_web::application::{
+    config::{Config, SocketMode},
+    listener::{self, Listener},
+    security::jwt_auth,
+};
+use std::{
+    collections::HashMap,
+    os::unix::{
+        fs::{FileTypeExt, PermissionsExt},
+        io::IntoRawFd,
+    },
+    path::Path,
+};
+use tokio::sync::broadcast;
+use uuid::Uuid;
+
+pub mod common;
+use common::{fetch, in_memory::test_admin, utils, TEST_JWT_SECRET};
+
+fn vars(pairs: &[(&str, String)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect();
+    move |key| vars.get(key).cloned()
+}
+
+fn socket_path() -> String {
+    std::env::temp_dir()
+        .join(format!("axum-web-{}.sock", Uuid::new_v4()))
+        .display()
+        .to_string()
+}
+
+#[test]
+fn listener_config_test() {
+    let config = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .build()
+        .unwrap();
+    assert!(config.service.tcp);
+    assert_eq!(config.service.unix_socket, None);
+    assert_eq!(config.service.unix_socket_mode, SocketMode(0o660));
+
+    let config = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("service.tcp", "false")
+        .set("service.unix_socket", "/run/axum-web/axum-web.sock")
+        .set("service.unix_socket_mode", "600")
+        .build()
+        .unwrap();
+    assert_eq!(
+        config.service.unix_socket.as_deref(),
+        Some("/run/axum-web/axum-web.sock")
+    );
+    assert_eq!(config.service.unix_socket_mode, SocketMode(0o600));
+    assert!(config.masked().contains("SERVICE_UNIX_SOCKET_MODE = 600\n"));
+
+    let error = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("service.unix_socket_mode", "1777")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec![
+            "service.unix_socket_mode: invalid value `1777`, expected an octal file mode like 660"
+        ]
+    );
+
+    // the descriptors of systemd are used by the process they are passed to only
+    let pid = std::process::id().to_string();
+    let fds = listener::listen_fds(vars(&[
+        ("LISTEN_PID", pid.clone()),
+        ("LISTEN_FDS", "2".into()),
+    ]));
+    assert_eq!(fds, 3..5);
+    let fds = listener::listen_fds(vars(&[
+        ("LISTEN_PID", "1".into()),
+        ("LISTEN_FDS", "2".into()),
+    ]));
+    assert!(fds.is_empty());
+    let fds = listener::listen_fds(vars(&[("LISTEN_PID", pid), ("LISTEN_FDS", "none".into())]));
+    assert!(fds.is_empty());
+    assert!(listener::listen_fds(vars(&[])).is_empty());
+}
+
+#[tokio::test]
+async fn inherited_listeners_test() {
+    let app = Router::new().route("/", get(|| async { "inherited" }));
+
+    // the sockets opened by systemd are served by their descriptors,
+    // the descriptors of the test sockets are owned by the listeners
+    let tcp = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = tcp.local_addr().unwrap();
+    let tcp = unsafe { listener::from_fd(tcp.into_raw_fd()) }.unwrap();
+    assert!(matches!(tcp, Listener::Tcp(_)));
+    assert_eq!(tcp.url(false), format!("http://{}", addr));
+
+    let path = socket_path();
+    let unix = std::os::unix::net::UnixListener::bind(&path).unwrap();
+    let unix = unsafe { listener::from_fd(unix.into_raw_fd()) }.unwrap();
+    assert!(matches!(unix, Listener::Unix(_)));
+    assert_eq!(unix.url(false), format!("unix:{}", path));
+
+    let (shutdown, _) = broadcast::channel::<()>(1);
+    let stopped = |mut shutdown: broadcast::Receiver<()>| async move {
+        let _ = shutdown.recv().await;
+    };
+    let tcp_server = tokio::spawn(listener::serve(
+        tcp,
+        app.clone(),
+        None,
+        stopped(shutdown.subscribe()),
+        std::future::pending(),
+    ));
+    let unix_server = tokio::spawn(listener::serve(
+        unix,
+        app,
+        None,
+        stopped(shutdown.subscribe()),
+        std::future::pending(),
+    ));
+
+    let body = fetch::fetch_url_hyper(&format!("http://{}/", addr))
+        .await
+        .unwrap();
+    assert_eq!(body, "inherited");
+    let (status, body) = fetch::fetch_unix_socket(Path::new(&path), "/", None)
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body, "inherited");
+
+    shutdown.send(()).unwrap();
+    tcp_server.await.unwrap();
+    unix_server.await.unwrap();
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn unix_socket_api_test() {
+    // the api is served by the unix socket only
+    let path = socket_path();
+    utils::start_api_with(&[
+        "service.tcp=false".to_string(),
+        format!("service.unix_socket={}", path),
+        "service.unix_socket_mode=600".to_string(),
+    ])
+    .await;
+
+    let metadata = std::fs::metadata(&path).unwrap();
+    assert!(metadata.file_type().is_socket());
+    assert_eq!(metadata.permissions().mode() & 0o777, 0o600);
+
+    let socket = Path::new(&path);
+    let (status, body) = fetch::fetch_unix_socket(socket, "/v1/heartbeat/socket", None)
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::OK);
+    assert!(body.contains("\"heartbeat-id\":\"socket\""));
+
+    let (status, _) = fetch::fetch_unix_socket(socket, "/v1/users", None)
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::UNAUTHORIZED);
+
+    let tokens = jwt_auth::generate_tokens(test_admin());
+    let (status, _) = fetch::fetch_unix_socket(socket, "/v1/users", Some(&tokens.access_token))
+        .await
+        .unwrap();
+    assert_eq!(status, StatusCode::OK);
+}
@@ -0,0 +1,135 @@
+use axum::http::{Method, StatusCode};
+use axum_web::{api::openapi, application::api_version::ApiVersion};
+use serde_json::json;
+
+pub mod common;
+use common::in_memory::{test_admin, TestApp};
+
+#[tokio::test]
+async fn openapi_document_test() {
+    let app = TestApp::start(vec![test_admin()]).await;
+
+    // the documents are public
+    let (status, json) = app
+        .request(Method::GET, "/v2/openapi.json", None, None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(json["openapi"], "3.1.0");
+    assert_eq!(
+        json["components"]["securitySchemes"]["bearer_auth"],
+        json!({"type": "http", "scheme": "bearer", "bearerFormat": "JWT"})
+    );
+    for path in [
+        "/{version}/auth/login",
+        "/{version}/auth/revoke-user",
+        "/{version}/users",
+        "/{version}/users/{id}",
+        "/{version}/users/{id}/restore",
+    ] {
+        assert!(json["paths"][path].is_object(), "missing path {}", path);
+    }
+
+    // the version parameter is bound to the version of the document
+    let list = &json["paths"]["/{version}/users"]["get"];
+    let version = list["parameters"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|parameter| parameter["name"] == "version")
+        .unwrap();
+    assert_eq!(version["in"], "path");
+    assert_eq!(version["schema"]["enum"], json!(["v2"]));
+    assert_eq!(list["security"], json!([{"bearer_auth": []}]));
+    assert!(list.get("deprecated").is_none());
+
+    // the errors are problem details
+    let not_found = &json["paths"]["/{version}/users/{id}"]["get"]["responses"]["404"];
+    assert_eq!(
+        not_found["content"]["application/problem+json"]["schema"]["$ref"],
+        "#/components/schemas/ProblemDetails"
+    );
+    let schemas = &json["components"]["schemas"];
+    assert!(schemas["ProblemDetails"].is_object());
+    let error_codes = schemas["ErrorCode"]["enum"].as_array().unwrap();
+    assert!(error_codes.contains(&json!("validation_failed")));
+    assert!(error_codes.contains(&json!("invalid_api_version")));
+    // only the codes the service emits
+    assert!(!error_codes.contains(&json!("api_version_missing")));
+
+    // the dtos of the version
+    assert_eq!(
+        schemas["UserResponse"]["properties"]["roles"]["type"],
+        "array"
+    );
+    assert!(schemas["UserListResponse"]["properties"]["items"].is_object());
+
+    // the merge patch is documented by the patchable fields of the version
+    let patch = &json["paths"]["/{version}/users/{id}"]["patch"]["requestBody"]["content"];
+    assert_eq!(
+        patch["application/merge-patch+json"]["schema"]["$ref"],
+        "#/components/schemas/UserPatchRequest"
+    );
+    assert_eq!(
+        schemas["UserPatchRequest"]["properties"]["roles"]["type"],
+        "array"
+    );
+
+    // the unversioned document is negotiated
+    let (status, json) = app.request(Method::GET, "/openapi.json", None, None).await;
+    assert_eq!(status, StatusCode::OK);
+    let schemas = &json["components"]["schemas"];
+    assert_eq!(
+        schemas["UserResponse"]["properties"]["roles"]["type"],
+        "string"
+    );
+    assert!(json["paths"]["/{version}/users"]["get"]
+        .get("deprecated")
+        .is_none());
+
+    let (status, _) = app
+        .request(Method::GET, "/v9/openapi.json", None, None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+}
+
+#[test]
+fn openapi_spec_test() {
+    // every route of the users api is documented in both versions
+    for version in [ApiVersion::V1, ApiVersion::V2] {
+        let spec = openapi::spec(version);
+        let operations: usize = spec
+            .paths
+            .paths
+            .values()
+            .map(|item| {
+                [&item.get, &item.put, &item.post, &item.delete, &item.patch]
+                    .iter()
+                    .filter(|operation| operation.is_some())
+                    .count()
+            })
+            .sum();
+        assert_eq!(operations, 14);
+    }
+}
+
+#[cfg(feature = "swagger-ui")]
+#[tokio::test]
+async fn swagger_ui_test() {
+    let app = TestApp::start(vec![test_admin()]).await;
+
+    // the page and its assets are served from the binary
+    for (path, content_type) in [
+        ("/docs/", "text/html"),
+        ("/docs/swagger-ui.css", "text/css"),
+        ("/docs/swagger-initializer.js", "text/javascript"),
+    ] {
+        let (status, headers, _) = app
+            .request_with_headers(Method::GET, path, None, None)
+            .await;
+        assert_eq!(status, StatusCode::OK, "{}", path);
+        assert!(headers[axum::http::header::CONTENT_TYPE]
+            .to_str()
+            .unwrap()
+            .starts_with(content_type));
+    }
+}
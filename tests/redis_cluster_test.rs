@@ -0,0 +1,52 @@
+use axum_web::{
+    application::{config, redis_service, security::jwt_claims::AccessClaims},
+    infrastructure::{
+        memory::InMemoryUserRepository,
+        redis::{self, RedisConnection, RedisRevocationRepository},
+    },
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub mod common;
+use common::{
+    redis_stub::{RedisStub, StubRole},
+    utils,
+};
+
+#[tokio::test]
+async fn cluster_test() {
+    let node = RedisStub::start(StubRole::Cluster).await;
+
+    // the environment variables take precedence over the test configuration file
+    std::env::set_var("REDIS_MODE", "cluster");
+    std::env::set_var("REDIS_NODES", format!("127.0.0.1:{}", node.port));
+    std::env::set_var("REDIS_PASSWORD", "pswd1234");
+    utils::load_config().await;
+
+    let connection = redis::open(&config::get()).await;
+    assert!(matches!(connection, RedisConnection::Cluster(_)));
+
+    // the revocation works on top of the cluster connection
+    let state = utils::build_state(
+        Arc::new(InMemoryUserRepository::new()),
+        Arc::new(RedisRevocationRepository::new(connection)),
+    );
+    let user_id = Uuid::new_v4().to_string();
+    let claims = AccessClaims {
+        sub: user_id.clone(),
+        jti: Uuid::new_v4().to_string(),
+        iat: chrono::Utc::now().timestamp() as usize,
+        exp: chrono::Utc::now().timestamp() as usize + 60,
+        typ: 0,
+        roles: "guest".to_string(),
+    };
+    assert_eq!(
+        redis_service::is_revoked(&claims, &state).await,
+        Some(false)
+    );
+    assert!(redis_service::revoke_global(&state).await);
+    assert_eq!(redis_service::is_revoked(&claims, &state).await, Some(true));
+    assert!(node.received(&["CLUSTER", "SLOTS"]));
+    assert!(node.received(&["AUTH", "pswd1234"]));
+}
@@ -0,0 +1,60 @@
+use axum_web::{
+    application::{config, redis_service, security::jwt_claims::AccessClaims},
+    infrastructure::{
+        memory::InMemoryUserRepository,
+        redis::{self, RedisRevocationRepository},
+    },
+};
+use std::sync::Arc;
+use uuid::Uuid;
+
+pub mod common;
+use common::{
+    redis_stub::{RedisStub, StubRole},
+    utils,
+};
+
+#[tokio::test]
+async fn sentinel_master_discovery_test() {
+    let master = RedisStub::start(StubRole::Master).await;
+    let sentinel = RedisStub::start(StubRole::Sentinel {
+        master_name: "axum-web-master".to_string(),
+        master_port: master.port,
+    })
+    .await;
+
+    // the environment variables take precedence over the test configuration file
+    std::env::set_var("REDIS_MODE", "sentinel");
+    std::env::set_var("REDIS_NODES", format!("127.0.0.1:{}", sentinel.port));
+    std::env::set_var("REDIS_SENTINEL_MASTER", "axum-web-master");
+    std::env::set_var("REDIS_USERNAME", "axum-web");
+    std::env::set_var("REDIS_PASSWORD", "pswd1234");
+    std::env::set_var("REDIS_DB", "2");
+    utils::load_config().await;
+
+    let connection = redis::open(&config::get()).await;
+    assert!(sentinel.received(&["SENTINEL", "MASTERS"]));
+    assert!(master.received(&["AUTH", "axum-web", "pswd1234"]));
+    assert!(master.received(&["SELECT", "2"]));
+
+    // the revocation works on top of the discovered master
+    let state = utils::build_state(
+        Arc::new(InMemoryUserRepository::new()),
+        Arc::new(RedisRevocationRepository::new(connection)),
+    );
+    let user_id = Uuid::new_v4().to_string();
+    let claims = AccessClaims {
+        sub: user_id.clone(),
+        jti: Uuid::new_v4().to_string(),
+        iat: chrono::Utc::now().timestamp() as usize,
+        exp: chrono::Utc::now().timestamp() as usize + 60,
+        typ: 0,
+        roles: "guest".to_string(),
+    };
+    assert_eq!(
+        redis_service::is_revoked(&claims, &state).await,
+        Some(false)
+    );
+    assert!(redis_service::revoke_user_tokens(&user_id, &state).await);
+    assert_eq!(redis_service::is_revoked(&claims, &state).await, Some(true));
+}
@@ -0,0 +1,37 @@
+use axum::{
+    http::{header, StatusCode},
+    response::IntoResponse,
+};
+use axum_web::application::{
+    api_error::{ApiError, ErrorCode},
+    repository::repository_error::RepositoryError,
+};
+
+fn api_error(err: sqlx::Error) -> ApiError {
+    RepositoryError::from(err).into()
+}
+
+#[test]
+fn repository_error_mapping_test() {
+    let error = api_error(sqlx::Error::RowNotFound);
+    assert_eq!(error.status_code, StatusCode::NOT_FOUND);
+    assert_eq!(error.error_code, ErrorCode::NotFound);
+
+    let error = api_error(sqlx::Error::PoolTimedOut);
+    assert_eq!(error.status_code, StatusCode::SERVICE_UNAVAILABLE);
+    assert_eq!(error.error_code, ErrorCode::StorageUnavailable);
+    let response = error.into_response();
+    assert_eq!(response.headers()[header::RETRY_AFTER], "5");
+
+    let error = api_error(sqlx::Error::Protocol("unexpected".to_string()));
+    assert_eq!(error.status_code, StatusCode::INTERNAL_SERVER_ERROR);
+    assert_eq!(error.error_code, ErrorCode::StorageError);
+
+    let error: ApiError = RepositoryError::UniqueViolation(Some("email".to_string())).into();
+    assert_eq!(error.status_code, StatusCode::CONFLICT);
+    assert_eq!(error.field_errors[0].field, "email");
+
+    let error: ApiError = RepositoryError::CheckViolation("roles".to_string()).into();
+    assert_eq!(error.status_code, StatusCode::UNPROCESSABLE_ENTITY);
+    assert_eq!(error.error_code, ErrorCode::ConstraintViolation);
+}
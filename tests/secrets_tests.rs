@@ -0,0 +1,220 @@
+use axum::{
+    extract::State,
+    http::{HeaderMap, StatusCode},
+    routing::get,
+    Json, Router,
+};
+use axum_web::{
+    application::{
+        config::{self, Config, ConfigArgs, SecretProvider},
+        security::{
+            jwt_auth,
+            jwt_claims::{self, AccessClaims},
+        },
+    },
+    infrastructure::vault::VaultSecretProvider,
+};
+use serde_json::{json, Value};
+use std::{
+    collections::HashMap,
+    sync::{Arc, Mutex},
+    time::Duration,
+};
+use tokio::sync::mpsc;
+use uuid::Uuid;
+
+pub mod common;
+use common::{in_memory::test_admin, TEST_JWT_SECRET};
+
+const VAULT_TOKEN: &str = "s.test-token";
+const ROTATED_JWT_SECRET: &str = "Zq3Wm8Rt1Yp6Nc4Lk9Xv2Bh7Gf5Jd0Sa";
+
+// the secret of a vault stub, changed by the tests
+type VaultSecret = Arc<Mutex<Value>>;
+
+async fn vault_secret(
+    State(secret): State<VaultSecret>,
+    headers: HeaderMap,
+) -> (StatusCode, Json<Value>) {
+    if headers.get("x-vault-token").and_then(|v| v.to_str().ok()) != Some(VAULT_TOKEN) {
+        return (
+            StatusCode::FORBIDDEN,
+            Json(json!({"errors": ["permission denied"]})),
+        );
+    }
+    let data = secret.lock().unwrap().clone();
+    (
+        StatusCode::OK,
+        Json(json!({"data": {"data": data, "metadata": {"version": 1}}})),
+    )
+}
+
+// a KV version 2 engine serving the secret/data/axum-web secret, returns its address
+async fn start_vault(secret: VaultSecret) -> String {
+    let app = Router::new()
+        .route("/v1/secret/data/axum-web", get(vault_secret))
+        .with_state(secret);
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    tokio::spawn(async move { axum::serve(listener, app).await.unwrap() });
+    format!("http://{}", addr)
+}
+
+fn vars(pairs: &[(&str, String)]) -> impl Fn(&str) -> Option<String> {
+    let vars: HashMap<String, String> = pairs
+        .iter()
+        .map(|(key, value)| (key.to_string(), value.clone()))
+        .collect();
+    move |key| vars.get(key).cloned()
+}
+
+#[test]
+fn secret_files_test() {
+    let dir = std::env::temp_dir();
+    let jwt_secret = dir.join(format!("axum-web-{}", Uuid::new_v4()));
+    let postgres_password = dir.join(format!("axum-web-{}", Uuid::new_v4()));
+    std::fs::write(&jwt_secret, format!("{}\n", TEST_JWT_SECRET)).unwrap();
+    std::fs::write(&postgres_password, "pswd5678").unwrap();
+
+    // the files take precedence over the variables
+    let config = Config::builder()
+        .vars(vars(&[
+            ("JWT_SECRET_FILE", jwt_secret.display().to_string()),
+            ("POSTGRES_PASSWORD", "pswd1234".to_string()),
+            (
+                "POSTGRES_PASSWORD_FILE",
+                postgres_password.display().to_string(),
+            ),
+            ("SERVICE_PORT_FILE", "ignored".to_string()),
+        ]))
+        .build()
+        .unwrap();
+    assert_eq!(config.jwt.secret, TEST_JWT_SECRET);
+    assert_eq!(config.postgres.password, "pswd5678");
+    assert_eq!(config.service.port, 3000);
+    std::fs::remove_file(jwt_secret).unwrap();
+    std::fs::remove_file(postgres_password).unwrap();
+
+    let error = Config::builder()
+        .vars(vars(&[("JWT_SECRET_FILE", "missing".to_string())]))
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec![
+            "JWT_SECRET_FILE: missing: No such file or directory (os error 2)",
+            "jwt.secret is not set, set JWT_SECRET or jwt.secret in the configuration file",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn vault_provider_test() {
+    let secret = Arc::new(Mutex::new(json!({
+        "JWT_SECRET": TEST_JWT_SECRET,
+        "POSTGRES_PASSWORD": "pswd5678",
+        "SERVICE_PORT": "4000",
+    })));
+    let addr = start_vault(secret).await;
+
+    let provider = VaultSecretProvider::new(&addr, VAULT_TOKEN, "secret/data/axum-web");
+    let secrets = provider.secrets().await.unwrap();
+    assert_eq!(secrets["JWT_SECRET"], TEST_JWT_SECRET);
+    assert_eq!(secrets["POSTGRES_PASSWORD"], "pswd5678");
+
+    let provider = VaultSecretProvider::new(&addr, "s.wrong-token", "secret/data/axum-web");
+    let error = provider.secrets().await.unwrap_err();
+    assert_eq!(
+        error.to_string(),
+        format!(
+            "secrets unavailable: {}/v1/secret/data/axum-web responded 403 Forbidden",
+            addr
+        )
+    );
+
+    // the provided secrets complete the configuration, the other provided values are ignored
+    let builder = Config::builder()
+        .set("secrets.provider", "vault")
+        .set("secrets.vault_addr", &addr)
+        .set("secrets.vault_token", VAULT_TOKEN);
+    let config = config::resolve(builder).await.unwrap();
+    assert_eq!(config.jwt.secret, TEST_JWT_SECRET);
+    assert_eq!(config.postgres.password, "pswd5678");
+    assert_eq!(config.service.port, 3000);
+    // the logged configuration hides the token and the provided secrets
+    let masked = config.masked();
+    assert!(masked.contains("VAULT_TOKEN = ********\n"));
+    for secret in [VAULT_TOKEN, TEST_JWT_SECRET, "pswd5678"] {
+        assert!(!masked.contains(secret));
+    }
+
+    let error = config::resolve(
+        Config::builder()
+            .set("secrets.provider", "vault")
+            .set("secrets.vault_addr", &addr)
+            .set("secrets.vault_token", "s.wrong-token"),
+    )
+    .await
+    .unwrap_err();
+    assert_eq!(error.errors.len(), 1);
+    assert!(error.errors[0].starts_with("secrets.provider: secrets unavailable:"));
+
+    let error = config::resolve(Config::builder().set("secrets.provider", "vault"))
+        .await
+        .unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec![
+            "jwt.secret is not set, set JWT_SECRET or jwt.secret in the configuration file",
+            "secrets.vault_token: expected the token of the vault, set VAULT_TOKEN or VAULT_TOKEN_FILE",
+        ]
+    );
+}
+
+#[tokio::test]
+async fn secrets_rotation_test() {
+    let secret = Arc::new(Mutex::new(json!({
+        "JWT_SECRET": TEST_JWT_SECRET,
+        "POSTGRES_PASSWORD": "pswd5678",
+    })));
+    let addr = start_vault(secret.clone()).await;
+    let overrides = vec![
+        "secrets.provider=vault".to_string(),
+        format!("secrets.vault_addr={}", addr),
+        format!("secrets.vault_token={}", VAULT_TOKEN),
+        "secrets.refresh_seconds=1".to_string(),
+    ];
+    let builder = overrides
+        .iter()
+        .fold(Config::builder(), |builder, arg| builder.set_arg(arg));
+    config::init(config::resolve(builder).await.unwrap());
+    let admin = test_admin();
+    let tokens = jwt_auth::generate_tokens(admin.clone());
+
+    // the rotated secrets are read periodically
+    *secret.lock().unwrap() = json!({
+        "JWT_SECRET": ROTATED_JWT_SECRET,
+        "POSTGRES_PASSWORD": "pswd9012",
+    });
+    let reloads = config::subscribe();
+    let (tx, mut rx) = mpsc::unbounded_channel();
+    let args = ConfigArgs {
+        file: None,
+        overrides,
+    };
+    tokio::spawn(config::watch(args, move |config| {
+        tx.send(config.jwt.secret.clone()).unwrap();
+    }));
+    let jwt_secret = tokio::time::timeout(Duration::from_secs(10), rx.recv()).await;
+    assert_eq!(jwt_secret.unwrap().unwrap(), ROTATED_JWT_SECRET);
+    assert!(reloads.has_changed().unwrap());
+    assert_eq!(config::get().postgres.password, "pswd9012");
+
+    // the tokens signed by the previous secret are still valid, the new ones are signed by the rotated one
+    let claims: AccessClaims = jwt_claims::decode_token(&tokens.access_token).unwrap();
+    assert_eq!(claims.sub, admin.id.to_string());
+    let tokens = jwt_auth::generate_tokens(admin);
+    let key = jsonwebtoken::DecodingKey::from_secret(ROTATED_JWT_SECRET.as_bytes());
+    let validation = jsonwebtoken::Validation::default();
+    assert!(jsonwebtoken::decode::<AccessClaims>(&tokens.access_token, &key, &validation).is_ok());
+}
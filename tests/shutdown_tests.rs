@@ -0,0 +1,210 @@
+use axum::{
+    body::Body,
+    http::{Request, StatusCode},
+    routing::get,
+    Router,
+};
+use axum_web::{
+    api::router::{self, Readiness},
+    application::{
+        config::{self, Config, ShutdownConfig},
+        listener::{self, Listener},
+        shutdown::{self, Shutdown},
+        state::SharedState,
+    },
+    infrastructure::memory::{InMemoryRevocationRepository, InMemoryUserRepository},
+};
+use serial_test::serial;
+use std::{net::SocketAddr, sync::Arc, time::Duration};
+use tokio::{sync::Notify, task::JoinHandle};
+use tower::ServiceExt;
+
+pub mod common;
+use common::{utils, TEST_JWT_SECRET};
+
+fn state() -> SharedState {
+    utils::build_state(
+        Arc::new(InMemoryUserRepository::with_users(vec![])),
+        Arc::new(InMemoryRevocationRepository::new()),
+    )
+}
+
+// a fast route and a route answering after the given delay, notifying its start
+async fn start(
+    shutdown: &Shutdown,
+    delay: Duration,
+    entered: Arc<Notify>,
+) -> (SocketAddr, JoinHandle<usize>) {
+    let app = Router::new()
+        .route("/fast", get(|| async { "fast" }))
+        .route(
+            "/slow",
+            get(move || async move {
+                entered.notify_one();
+                tokio::time::sleep(delay).await;
+                "slow"
+            }),
+        );
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let server = tokio::spawn(listener::serve(
+        Listener::Tcp(listener),
+        app,
+        None,
+        shutdown.stopped(),
+        shutdown.deadline(),
+    ));
+    (addr, server)
+}
+
+async fn get_text(
+    client: reqwest::Client,
+    addr: SocketAddr,
+    path: &str,
+) -> reqwest::Result<String> {
+    client
+        .get(format!("http://{}{}", addr, path))
+        .send()
+        .await?
+        .text()
+        .await
+}
+
+#[test]
+fn shutdown_config_test() {
+    let config = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .build()
+        .unwrap();
+    assert_eq!(
+        config.shutdown,
+        ShutdownConfig {
+            pre_stop_delay_seconds: 0,
+            drain_timeout_seconds: 30,
+        }
+    );
+
+    let config = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("shutdown.pre_stop_delay_seconds", "10")
+        .set("shutdown.drain_timeout_seconds", "60")
+        .build()
+        .unwrap();
+    assert_eq!(config.shutdown.pre_stop_delay_seconds, 10);
+    assert!(config
+        .masked()
+        .contains("SHUTDOWN_DRAIN_TIMEOUT_SECONDS = 60\n"));
+
+    let error = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("shutdown.drain_timeout_seconds", "-1")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec![
+            "shutdown.drain_timeout_seconds: invalid value `-1`, expected zero or a positive number"
+        ]
+    );
+}
+
+#[tokio::test]
+#[serial]
+async fn readiness_test() {
+    config::init(utils::test_config());
+    let app = router::routes(state());
+    let ready = || async {
+        let request = Request::builder()
+            .uri("/ready")
+            .body(Body::empty())
+            .unwrap();
+        let response = app.clone().oneshot(request).await.unwrap();
+        let status = response.status();
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        let readiness: Readiness = serde_json::from_slice(&body).unwrap();
+        (status, readiness.ready)
+    };
+
+    // not ready until the listeners are bound
+    shutdown::set_ready(false);
+    assert_eq!(ready().await, (StatusCode::SERVICE_UNAVAILABLE, false));
+    shutdown::set_ready(true);
+    assert_eq!(ready().await, (StatusCode::OK, true));
+}
+
+#[tokio::test]
+#[serial]
+async fn shutdown_drain_test() {
+    let shutdown = Shutdown::new();
+    let entered = Arc::new(Notify::new());
+    let (addr, server) = start(&shutdown, Duration::from_millis(1500), entered.clone()).await;
+    shutdown::spawn_job("test job", std::future::pending());
+    shutdown::set_ready(true);
+
+    // the shutdown starts once the slow request is in flight
+    let client = reqwest::Client::new();
+    let slow = tokio::spawn(get_text(client.clone(), addr, "/slow"));
+    entered.notified().await;
+    let config = ShutdownConfig {
+        pre_stop_delay_seconds: 1,
+        drain_timeout_seconds: 5,
+    };
+    let state = state();
+    let shutdown = tokio::spawn(async move { shutdown.run(&config, vec![server], &state).await });
+
+    // not ready during the pre-stop delay, the new requests are still served
+    tokio::time::sleep(Duration::from_millis(200)).await;
+    assert!(!shutdown::is_ready());
+    assert_eq!(
+        get_text(client.clone(), addr, "/fast").await.unwrap(),
+        "fast"
+    );
+
+    // the in-flight request completes, then the jobs are stopped
+    let summary = shutdown.await.unwrap();
+    assert_eq!(slow.await.unwrap().unwrap(), "slow");
+    assert!(summary.connections.unwrap() >= 1);
+    assert_eq!(summary.jobs, vec!["test job"]);
+    assert!(summary.elapsed >= Duration::from_secs(1));
+    assert!(summary
+        .to_string()
+        .contains("1 background jobs stopped (test job)"));
+    assert!(shutdown::stop_jobs().await.is_empty());
+
+    // the listener is closed
+    assert!(get_text(client, addr, "/fast").await.is_err());
+}
+
+#[tokio::test]
+#[serial]
+async fn shutdown_drain_timeout_test() {
+    let shutdown = Shutdown::new();
+    let entered = Arc::new(Notify::new());
+    let (addr, server) = start(&shutdown, Duration::from_secs(30), entered.clone()).await;
+    let slow = tokio::spawn(get_text(reqwest::Client::new(), addr, "/slow"));
+    entered.notified().await;
+
+    // the request outlasting the drain timeout is aborted, not waited for
+    let config = ShutdownConfig {
+        pre_stop_delay_seconds: 0,
+        drain_timeout_seconds: 1,
+    };
+    let summary = tokio::time::timeout(
+        Duration::from_secs(5),
+        shutdown.run(&config, vec![server], &state()),
+    )
+    .await
+    .unwrap();
+    assert_eq!(summary.connections, None);
+    assert!(summary.elapsed < Duration::from_secs(5));
+    assert!(summary.to_string().contains("the drain timed out"));
+
+    // its connection is closed by the shutdown, not by the end of the request
+    let response = tokio::time::timeout(Duration::from_secs(1), slow)
+        .await
+        .unwrap()
+        .unwrap();
+    assert!(response.is_err());
+}
@@ -0,0 +1,488 @@
+use axum::{
+    body::Body,
+    extract::Extension,
+    http::{header, Request, StatusCode},
+    routing::get,
+    Router,
+};
+use axum_web::{
+    api::router,
+    application::{
+        config::{self, ClientAuth, Config, PrincipalUser, TlsConfig},
+        listener::{self, Listener},
+        tls::{self, ClientPrincipal, TlsServer},
+    },
+    infrastructure::memory::{InMemoryRevocationRepository, InMemoryUserRepository},
+};
+use rcgen::{
+    BasicConstraints, Certificate, CertificateParams, DistinguishedName, DnType,
+    ExtendedKeyUsagePurpose, IsCa, KeyPair,
+};
+use std::{
+    net::SocketAddr,
+    path::{Path, PathBuf},
+    sync::Arc,
+    time::Duration,
+};
+use tokio::sync::oneshot;
+use tower::ServiceExt;
+use uuid::Uuid;
+
+pub mod common;
+use common::{
+    in_memory::{test_admin, test_user},
+    utils, TEST_ADMIN_USERNAME, TEST_JWT_SECRET, TEST_USER_PASSWORD_HASH,
+};
+
+struct Issuer {
+    cert: Certificate,
+    key: KeyPair,
+}
+
+fn issuer(name: &str) -> Issuer {
+    let key = KeyPair::generate().unwrap();
+    let mut params = CertificateParams::default();
+    params.is_ca = IsCa::Ca(BasicConstraints::Unconstrained);
+    params.distinguished_name.push(DnType::CommonName, name);
+    Issuer {
+        cert: params.self_signed(&key).unwrap(),
+        key,
+    }
+}
+
+// a certificate and its key in the PEM format
+fn issue(
+    issuer: &Issuer,
+    common_name: Option<&str>,
+    names: &[&str],
+    usage: ExtendedKeyUsagePurpose,
+) -> (String, String, Certificate) {
+    let key = KeyPair::generate().unwrap();
+    let names: Vec<String> = names.iter().map(|name| name.to_string()).collect();
+    let mut params = CertificateParams::new(names).unwrap();
+    params.distinguished_name = DistinguishedName::new();
+    if let Some(common_name) = common_name {
+        params
+            .distinguished_name
+            .push(DnType::CommonName, common_name);
+    }
+    params.extended_key_usages = vec![usage];
+    let cert = params.signed_by(&key, &issuer.cert, &issuer.key).unwrap();
+    (cert.pem(), key.serialize_pem(), cert)
+}
+
+fn temp_file(content: &str) -> PathBuf {
+    let file = std::env::temp_dir().join(format!("axum-web-{}.pem", Uuid::new_v4()));
+    std::fs::write(&file, content).unwrap();
+    file
+}
+
+fn tls_config(cert: &Path, key: &Path, ca: &Path, client_auth: ClientAuth) -> TlsConfig {
+    TlsConfig {
+        enabled: true,
+        cert_path: Some(cert.display().to_string()),
+        key_path: Some(key.display().to_string()),
+        client_ca_path: Some(ca.display().to_string()),
+        client_auth,
+        client_principals: Vec::new(),
+        redirect_port: None,
+    }
+}
+
+// the principal of the client certificate or anonymous, with the http version
+fn principal_routes() -> Router {
+    Router::new().route(
+        "/principal",
+        get(
+            |principal: Option<Extension<ClientPrincipal>>, request: Request<Body>| async move {
+                let principal = principal
+                    .map(|Extension(ClientPrincipal(principal))| principal)
+                    .unwrap_or_else(|| "anonymous".to_string());
+                format!("{} {:?}", principal, request.version())
+            },
+        ),
+    )
+}
+
+// serves the routes over https until the returned sender is dropped or used
+async fn start(
+    server: TlsServer,
+    routes: Router,
+) -> (
+    SocketAddr,
+    oneshot::Sender<()>,
+    tokio::task::JoinHandle<usize>,
+) {
+    let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = oneshot::channel::<()>();
+    let handle = tokio::spawn(listener::serve(
+        Listener::Tcp(listener),
+        routes,
+        Some(server),
+        async {
+            let _ = rx.await;
+        },
+        std::future::pending(),
+    ));
+    (addr, tx, handle)
+}
+
+fn client(addr: SocketAddr, ca: &str, identity: Option<String>) -> reqwest::Client {
+    let builder = reqwest::Client::builder()
+        .use_rustls_tls()
+        .tls_built_in_root_certs(false)
+        .add_root_certificate(reqwest::Certificate::from_pem(ca.as_bytes()).unwrap())
+        .resolve("localhost", addr);
+    let builder = match identity {
+        Some(identity) => {
+            builder.identity(reqwest::Identity::from_pem(identity.as_bytes()).unwrap())
+        }
+        None => builder,
+    };
+    builder.build().unwrap()
+}
+
+async fn principal(client: &reqwest::Client, addr: SocketAddr) -> reqwest::Result<String> {
+    let url = format!("https://localhost:{}/principal", addr.port());
+    client.get(url).send().await?.text().await
+}
+
+#[test]
+fn tls_config_test() {
+    let config = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("tls.enabled", "true")
+        .set("tls.cert_path", "/etc/axum-web/cert.pem")
+        .set("tls.key_path", "/etc/axum-web/key.pem")
+        .set("tls.redirect_port", "3080")
+        .build()
+        .unwrap();
+    assert_eq!(config.service_http_addr(), "https://127.0.0.1:3000");
+    assert_eq!(config.tls.client_auth, ClientAuth::None);
+    assert_eq!(config.tls.redirect_port, Some(3080));
+
+    let error = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("tls.enabled", "true")
+        .set("tls.client_auth", "required")
+        .set("tls.redirect_port", "3000")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec![
+            "tls.cert_path: expected the PEM file of the certificate chain, set TLS_CERT_PATH",
+            "tls.key_path: expected the PEM file of the private key, set TLS_KEY_PATH",
+            "tls.client_ca_path: expected the PEM file of the CA certificates verifying the clients, set TLS_CLIENT_CA_PATH",
+            "tls.redirect_port: expected another port than the service port 3000",
+        ]
+    );
+
+    let error = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("tls.client_auth", "mutual")
+        .set("tls.redirect_port", "3080")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec![
+            "tls.client_auth: invalid value `mutual`, expected none, optional or required",
+            "tls.redirect_port: expected tls.enabled, the redirects target the https listener",
+        ]
+    );
+
+    // the principals of the client certificates authenticate users
+    let config = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("tls.client_auth", "optional")
+        .set("tls.client_ca_path", "/etc/axum-web/ca.pem")
+        .set(
+            "tls.client_principals",
+            "billing-service=ops, worker.example.com=worker",
+        )
+        .build()
+        .unwrap();
+    assert_eq!(
+        config.tls.client_principals,
+        vec![
+            PrincipalUser {
+                principal: "billing-service".to_string(),
+                username: "ops".to_string(),
+            },
+            PrincipalUser {
+                principal: "worker.example.com".to_string(),
+                username: "worker".to_string(),
+            },
+        ]
+    );
+    assert!(config
+        .masked()
+        .contains("TLS_CLIENT_PRINCIPALS = billing-service=ops,worker.example.com=worker\n"));
+
+    let error = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("tls.client_principals", "billing-service")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec!["tls.client_principals: invalid value `billing-service`, expected a principal=username pair"]
+    );
+    let error = Config::builder()
+        .set("jwt.secret", TEST_JWT_SECRET)
+        .set("tls.client_principals", "billing-service=ops")
+        .build()
+        .unwrap_err();
+    assert_eq!(
+        error.errors,
+        vec!["tls.client_principals: expected tls.client_auth optional or required, the principals are verified client certificates"]
+    );
+
+    let error = TlsServer::load(&TlsConfig {
+        enabled: true,
+        cert_path: Some("missing.pem".to_string()),
+        key_path: Some("missing.pem".to_string()),
+        client_ca_path: None,
+        client_auth: ClientAuth::None,
+        client_principals: Vec::new(),
+        redirect_port: None,
+    })
+    .err()
+    .unwrap();
+    assert_eq!(
+        error.to_string(),
+        "TLS file error: missing.pem: No such file or directory (os error 2)"
+    );
+
+    // the alternative names stand in for a missing common name
+    let ca = issuer("axum-web test ca");
+    let (_, _, cert) = issue(
+        &ca,
+        None,
+        &["worker.example.com"],
+        ExtendedKeyUsagePurpose::ClientAuth,
+    );
+    assert_eq!(
+        tls::client_principal(cert.der()),
+        Some(ClientPrincipal("worker.example.com".to_string()))
+    );
+}
+
+#[tokio::test]
+async fn tls_serve_test() {
+    let ca = issuer("axum-web test ca");
+    let (cert_pem, key_pem, _) = issue(
+        &ca,
+        Some("localhost"),
+        &["localhost"],
+        ExtendedKeyUsagePurpose::ServerAuth,
+    );
+    let (client_pem, client_key, _) = issue(
+        &ca,
+        Some("billing-service"),
+        &[],
+        ExtendedKeyUsagePurpose::ClientAuth,
+    );
+    let ca_pem = ca.cert.pem();
+    let identity = format!("{}{}", client_pem, client_key);
+    let (cert, key, ca_file) = (
+        temp_file(&cert_pem),
+        temp_file(&key_pem),
+        temp_file(&ca_pem),
+    );
+
+    // the client certificates are optional, HTTP/2 is negotiated by ALPN
+    let config = tls_config(&cert, &key, &ca_file, ClientAuth::Optional);
+    let server = TlsServer::load(&config).unwrap();
+    let (addr, shutdown, handle) = start(server.clone(), principal_routes()).await;
+    tokio::spawn(tls::watch(server, config));
+
+    let anonymous = client(addr, &ca_pem, None);
+    assert_eq!(
+        principal(&anonymous, addr).await.unwrap(),
+        "anonymous HTTP/2.0"
+    );
+    let authenticated = client(addr, &ca_pem, Some(identity.clone()));
+    assert_eq!(
+        principal(&authenticated, addr).await.unwrap(),
+        "billing-service HTTP/2.0"
+    );
+    let http1 = reqwest::Client::builder()
+        .use_rustls_tls()
+        .tls_built_in_root_certs(false)
+        .add_root_certificate(reqwest::Certificate::from_pem(ca_pem.as_bytes()).unwrap())
+        .resolve("localhost", addr)
+        .http1_only()
+        .build()
+        .unwrap();
+    assert_eq!(principal(&http1, addr).await.unwrap(), "anonymous HTTP/1.1");
+
+    // a client certificate of an unknown CA is rejected
+    let other_ca = issuer("other ca");
+    let (other_pem, other_key, _) = issue(
+        &other_ca,
+        Some("intruder"),
+        &[],
+        ExtendedKeyUsagePurpose::ClientAuth,
+    );
+    let intruder = client(addr, &ca_pem, Some(format!("{}{}", other_pem, other_key)));
+    assert!(principal(&intruder, addr).await.is_err());
+
+    // the changed certificates are reloaded, the new connections use them
+    tokio::time::sleep(Duration::from_millis(100)).await;
+    let (renewed_pem, renewed_key, _) = issue(
+        &other_ca,
+        Some("localhost"),
+        &["localhost"],
+        ExtendedKeyUsagePurpose::ServerAuth,
+    );
+    std::fs::write(&key, renewed_key).unwrap();
+    std::fs::write(&cert, renewed_pem).unwrap();
+    let renewed = client(addr, &other_ca.cert.pem(), None);
+    let mut reloaded = false;
+    for _ in 0..50 {
+        if principal(&renewed, addr).await.is_ok() {
+            reloaded = true;
+            break;
+        }
+        tokio::time::sleep(Duration::from_millis(200)).await;
+    }
+    assert!(reloaded);
+    assert!(principal(&client(addr, &ca_pem, None), addr).await.is_err());
+
+    shutdown.send(()).unwrap();
+    tokio::time::timeout(Duration::from_secs(5), handle)
+        .await
+        .unwrap()
+        .unwrap();
+
+    // the required client certificates reject the anonymous clients
+    std::fs::write(&key, key_pem).unwrap();
+    std::fs::write(&cert, cert_pem).unwrap();
+    let config = tls_config(&cert, &key, &ca_file, ClientAuth::Required);
+    let (addr, _shutdown, _) = start(TlsServer::load(&config).unwrap(), principal_routes()).await;
+    assert!(principal(&client(addr, &ca_pem, None), addr).await.is_err());
+    assert_eq!(
+        principal(&client(addr, &ca_pem, Some(identity)), addr)
+            .await
+            .unwrap(),
+        "billing-service HTTP/2.0"
+    );
+
+    for file in [cert, key, ca_file] {
+        std::fs::remove_file(file).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn tls_principal_auth_test() {
+    let ca = issuer("axum-web test ca");
+    let (cert_pem, key_pem, _) = issue(
+        &ca,
+        Some("localhost"),
+        &["localhost"],
+        ExtendedKeyUsagePurpose::ServerAuth,
+    );
+    let identity = |name: &str| {
+        let (pem, key, _) = issue(&ca, Some(name), &[], ExtendedKeyUsagePurpose::ClientAuth);
+        Some(format!("{}{}", pem, key))
+    };
+    let ca_pem = ca.cert.pem();
+    let (cert, key, ca_file) = (
+        temp_file(&cert_pem),
+        temp_file(&key_pem),
+        temp_file(&ca_pem),
+    );
+
+    // the only configuration of this test binary, read by the extractors of the claims
+    let mut tls = tls_config(&cert, &key, &ca_file, ClientAuth::Optional);
+    tls.client_principals = vec![
+        PrincipalUser {
+            principal: "billing-service".to_string(),
+            username: TEST_ADMIN_USERNAME.to_string(),
+        },
+        PrincipalUser {
+            principal: "reporting-service".to_string(),
+            username: "reporting".to_string(),
+        },
+    ];
+    config::init(Config {
+        tls: tls.clone(),
+        ..utils::test_config()
+    });
+    let state = utils::build_state(
+        Arc::new(InMemoryUserRepository::with_users(vec![
+            test_admin(),
+            test_user("reporting", TEST_USER_PASSWORD_HASH, "guest"),
+        ])),
+        Arc::new(InMemoryRevocationRepository::new()),
+    );
+    let (addr, _shutdown, _) = start(TlsServer::load(&tls).unwrap(), router::routes(state)).await;
+
+    let get = |path: &'static str, identity: Option<String>| {
+        let client = client(addr, &ca_pem, identity);
+        async move {
+            let url = format!("https://localhost:{}{}", addr.port(), path);
+            client.get(url).send().await.unwrap().status()
+        }
+    };
+    // the mapped certificates authenticate their users with the roles of the users, without a token
+    assert_eq!(
+        get("/v1/users", identity("billing-service")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        get("/", identity("reporting-service")).await,
+        StatusCode::OK
+    );
+    assert_eq!(
+        get("/v1/users", identity("reporting-service")).await,
+        StatusCode::UNAUTHORIZED
+    );
+    // the certificates not mapped to a user and the anonymous clients need a token
+    assert_eq!(
+        get("/", identity("other-service")).await,
+        StatusCode::UNAUTHORIZED
+    );
+    assert_eq!(get("/", None).await, StatusCode::UNAUTHORIZED);
+
+    for file in [cert, key, ca_file] {
+        std::fs::remove_file(file).unwrap();
+    }
+}
+
+#[tokio::test]
+async fn tls_redirect_test() {
+    let redirect = |port: u16, host: Option<&str>, uri: &str| {
+        let mut request = Request::builder().uri(uri);
+        if let Some(host) = host {
+            request = request.header(header::HOST, host);
+        }
+        tls::redirect_routes(port).oneshot(request.body(Body::empty()).unwrap())
+    };
+
+    let response = redirect(8443, Some("example.com:8080"), "/v1/users?page=2")
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::PERMANENT_REDIRECT);
+    assert_eq!(
+        response.headers()[header::LOCATION],
+        "https://example.com:8443/v1/users?page=2"
+    );
+
+    let response = redirect(443, Some("example.com"), "/").await.unwrap();
+    assert_eq!(response.headers()[header::LOCATION], "https://example.com/");
+
+    let response = redirect(8443, Some("[::1]:8080"), "/v1/heartbeat/1")
+        .await
+        .unwrap();
+    assert_eq!(
+        response.headers()[header::LOCATION],
+        "https://[::1]:8443/v1/heartbeat/1"
+    );
+
+    let response = redirect(8443, None, "/").await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
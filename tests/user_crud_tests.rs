@@ -1,9 +1,17 @@
 pub mod common;
 use axum_web::{
-    application::security::jwt_claims::{self, AccessClaims},
+    api::users::v1::UserResponse,
+    application::{
+        repository::user_repo::{SortOrder, UserSortField},
+        security::{
+            jwt_claims::{self, AccessClaims},
+            roles,
+        },
+    },
     domain::models::user::User,
 };
-use common::{auth, utils, *};
+use axum_web_client::{v2, ErrorCode, ListUsersParams};
+use common::{users, utils, *};
 use reqwest::StatusCode;
 use serial_test::serial;
 
@@ -16,26 +24,110 @@ async fn list_users_test() {
     utils::start_api().await;
 
     // try unauthorized access to the users handler
-    let (status, _) = users::list("xyz").await.unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let client = utils::client_with_tokens("xyz", "xyz");
+    let error = client
+        .list_users(&ListUsersParams::default())
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let tokens = client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, _) = result.unwrap();
 
-    let access_claims = jwt_claims::decode_token::<AccessClaims>(&access_token).unwrap();
-    let user_id = access_claims.sub.parse().unwrap();
+    let access_claims = jwt_claims::decode_token::<AccessClaims>(&tokens.access_token).unwrap();
+    let user_id: Uuid = access_claims.sub.parse().unwrap();
 
     // try authorized access to the users handler
-    let (status, result) = users::list(&access_token).await.unwrap();
-    assert_eq!(status, reqwest::StatusCode::OK);
-    assert!(result.is_some());
-
-    let users = result.unwrap();
+    let page = client
+        .list_users(&ListUsersParams::default())
+        .await
+        .unwrap();
+    let users = page.body.0;
     assert!(!users.is_empty());
     assert!(users.iter().any(|u| u.id == user_id));
+
+    // the same users in the v2 contract
+    let client = client.with_contract::<v2::V2>();
+    let page = client
+        .list_users(&ListUsersParams::default())
+        .await
+        .unwrap();
+    assert!(page.body.items.iter().any(|u| u.id == user_id));
+}
+
+#[tokio::test]
+#[serial]
+async fn list_users_pagination_test() {
+    // load the test configuration and start the api server
+    utils::start_api().await;
+
+    let client = utils::client();
+    client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+        .await
+        .unwrap();
+
+    // add users with a common prefix
+    let prefix = format!("page-{}-", Uuid::new_v4());
+    let mut added = Vec::new();
+    for i in 0..3 {
+        let username = format!("{}{}", prefix, i);
+        let user = User {
+            id: Uuid::new_v4(),
+            username: username.clone(),
+            email: format!("{}@email.com", username),
+            password_hash: TEST_USER_PASSWORD_HASH.to_string(),
+            password_salt: TEST_USER_PASSWORD_SALT.to_string(),
+            active: true,
+            roles: "guest".to_string(),
+            created_at: None,
+            updated_at: None,
+            version: 0,
+            deleted_at: None,
+        };
+        client
+            .add_user(&users::create_request(&user))
+            .await
+            .unwrap();
+        added.push(user);
+    }
+
+    // walk through the pages sorted by username in descending order
+    let mut usernames = Vec::new();
+    let mut params = ListUsersParams {
+        username_prefix: Some(prefix.clone()),
+        sort: UserSortField::Username,
+        order: SortOrder::Desc,
+        limit: Some(2),
+        include_total: true,
+        ..Default::default()
+    };
+    loop {
+        let page = client.list_users(&params).await.unwrap();
+        assert_eq!(page.total_count, Some(3));
+        usernames.extend(page.body.0.into_iter().map(|u| u.username));
+        match page.next_cursor {
+            Some(next_cursor) => params.cursor = Some(next_cursor),
+            None => break,
+        }
+    }
+    let expected: Vec<String> = (0..3).rev().map(|i| format!("{}{}", prefix, i)).collect();
+    assert_eq!(usernames, expected);
+
+    // filter by role and creation time
+    let params = ListUsersParams {
+        role: Some("admin".to_string()),
+        created_before: Some("2100-01-01T00:00:00".parse().unwrap()),
+        ..Default::default()
+    };
+    let page = client.list_users(&params).await.unwrap();
+    assert!(page.body.0.iter().all(|u| roles::is_role_admin(&u.roles)));
+
+    for user in added {
+        client.delete_user(user.id, "*").await.unwrap();
+    }
 }
 
 #[tokio::test]
@@ -45,25 +137,22 @@ async fn get_user_test() {
     utils::start_api().await;
 
     // try unauthorized access to the get user handler
-    let (status, _) = users::get(uuid::Uuid::new_v4(), "").await.unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let client = utils::client();
+    let error = client.get_user(Uuid::new_v4()).await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNAUTHORIZED));
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    let tokens = client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, _) = result.unwrap();
 
-    let access_claims = jwt_claims::decode_token::<AccessClaims>(&access_token).unwrap();
-    let user_id = access_claims.sub.parse().unwrap();
+    let access_claims = jwt_claims::decode_token::<AccessClaims>(&tokens.access_token).unwrap();
+    let user_id: Uuid = access_claims.sub.parse().unwrap();
 
     // get the user
-    let (status, result) = users::get(user_id, &access_token).await.unwrap();
-    assert_eq!(status, reqwest::StatusCode::OK);
-    assert!(result.is_some());
-
-    let user = result.unwrap();
-    assert_eq!(user.id, user_id);
+    let user = client.get_user(user_id).await.unwrap();
+    assert_eq!(user.value.id, user_id);
+    assert!(user.etag.is_some());
 }
 
 #[tokio::test]
@@ -77,68 +166,141 @@ async fn add_get_update_delete_user_test() {
         id: Uuid::new_v4(),
         username: username.clone(),
         email: format!("{}@email.com", username),
-        password_hash: "xyz123".to_string(),
-        password_salt: "xyz123".to_string(),
+        password_hash: TEST_USER_PASSWORD_HASH.to_string(),
+        password_salt: TEST_USER_PASSWORD_SALT.to_string(),
         active: true,
         roles: "guest".to_string(),
         created_at: None,
         updated_at: None,
+        version: 0,
+        deleted_at: None,
     };
 
     // try unauthorized access to user handlers
-    let access_token = "xyz".to_string();
-    let (status, _) = users::get(user.id, &access_token).await.unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let client = utils::client_with_tokens("xyz", "xyz");
+    let unauthorized = Some(StatusCode::UNAUTHORIZED);
+    let error = client.get_user(user.id).await.unwrap_err();
+    assert_eq!(error.status(), unauthorized);
 
-    let (status, _) = users::add(user.clone(), &access_token).await.unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let error = client
+        .add_user(&users::create_request(&user))
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), unauthorized);
 
-    let (status, _) = users::update(user.clone(), &access_token).await.unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let error = client
+        .update_user(user.id, &users::update_request(&user), "*")
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), unauthorized);
 
-    let status = users::delete(user.id, &access_token).await.unwrap();
-    assert_eq!(status, StatusCode::UNAUTHORIZED);
+    let error = client.delete_user(user.id, "*").await.unwrap_err();
+    assert_eq!(error.status(), unauthorized);
 
-    let (status, result) = auth::login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
+    client
+        .login(TEST_ADMIN_USERNAME, TEST_ADMIN_PASSWORD_HASH)
         .await
         .unwrap();
-    assert_eq!(status, StatusCode::OK);
-    let (access_token, _) = result.unwrap();
 
     // add the user
-    let (status, result) = users::add(user.clone(), &access_token).await.unwrap();
-    assert_eq!(status, StatusCode::CREATED);
-    assert!(result.is_some());
-    let user_result = result.unwrap();
+    let user_result = client
+        .add_user(&users::create_request(&user))
+        .await
+        .unwrap();
     assert!(user_result.updated_at.is_some());
     assert!(user_result.created_at.is_some());
 
     user.created_at = user_result.created_at;
     user.updated_at = user_result.updated_at;
-    assert_eq!(user_result, user);
+    assert_eq!(user_result, UserResponse::from(user.clone()));
+
+    // the unique constraints are reported with the conflicting field
+    let mut duplicate = user.clone();
+    duplicate.id = Uuid::new_v4();
+    let error = client
+        .add_user(&users::create_request(&duplicate))
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::CONFLICT));
+    assert_eq!(error.code(), Some(ErrorCode::UniqueViolation));
+    assert_eq!(error.problem().unwrap().errors[0].field, "email");
+
+    let mut duplicate = user.clone();
+    duplicate.email = format!("{}-duplicate@email.com", user.username);
+    let error = client
+        .add_user(&users::create_request(&duplicate))
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::CONFLICT));
+    assert_eq!(error.problem().unwrap().errors[0].field, "id");
 
     // get the added user
-    let (status, result) = users::get(user.id, &access_token).await.unwrap();
-    assert_eq!(status, reqwest::StatusCode::OK);
-    assert!(result.is_some());
-    let user_result = result.unwrap();
-    assert_eq!(user_result, user);
+    let user_result = client.get_user(user.id).await.unwrap();
+    assert_eq!(user_result.value, UserResponse::from(user.clone()));
+    let etag = user_result.etag.unwrap();
 
     // update user
     user.username = format!("test-{}", chrono::Utc::now().timestamp() as usize);
-    let (status, result) = users::update(user.clone(), &access_token).await.unwrap();
-    assert_eq!(status, reqwest::StatusCode::OK);
-    assert!(result.is_some());
-    let user_result = result.unwrap();
-    assert_ne!(user_result.updated_at, user.updated_at);
-    user.updated_at = user_result.updated_at;
-    assert_eq!(user_result, user);
+    let user_result = client
+        .update_user(user.id, &users::update_request(&user), &etag)
+        .await
+        .unwrap();
+    assert_ne!(user_result.value.updated_at, user.updated_at);
+    assert_ne!(user_result.etag, Some(etag.clone()));
+    user.updated_at = user_result.value.updated_at;
+    assert_eq!(user_result.value, UserResponse::from(user.clone()));
+
+    // the old entity tag is stale after the update
+    let error = client
+        .update_user(user.id, &users::update_request(&user), &etag)
+        .await
+        .unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::PRECONDITION_FAILED));
+    let etag = client.get_user(user.id).await.unwrap().etag.unwrap();
+
+    // patch user
+    user.email = format!("{}-patched@email.com", user.username);
+    let patch = serde_json::json!({"email": user.email});
+    let user_result = client.patch_user(user.id, &patch, &etag).await.unwrap();
+    user.updated_at = user_result.value.updated_at;
+    assert_eq!(user_result.value, UserResponse::from(user.clone()));
+
+    let patch = serde_json::json!({"id": Uuid::new_v4()});
+    let error = client.patch_user(user.id, &patch, "*").await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::UNPROCESSABLE_ENTITY));
 
     // delete user
-    let status = users::delete(user.id, &access_token).await.unwrap();
-    assert_eq!(status, StatusCode::OK);
+    let error = client.delete_user(user.id, &etag).await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::PRECONDITION_FAILED));
+    client.delete_user(user.id, "*").await.unwrap();
 
     // check the user
-    let (status, _) = users::get(user.id, &access_token).await.unwrap();
-    assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+    let error = client.get_user(user.id).await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+
+    // the email of the deleted user can be taken, its restore then conflicts
+    let mut other = user.clone();
+    other.id = Uuid::new_v4();
+    client
+        .add_user(&users::create_request(&other))
+        .await
+        .unwrap();
+    let error = client.restore_user(user.id).await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::CONFLICT));
+    assert_eq!(error.problem().unwrap().errors[0].field, "email");
+    client.delete_user(other.id, "*").await.unwrap();
+    client.purge_user(other.id).await.unwrap();
+
+    // restore the soft deleted user
+    let user_result = client.restore_user(user.id).await.unwrap();
+    assert_eq!(user_result.value.email, user.email);
+    client.get_user(user.id).await.unwrap();
+
+    // only deleted users can be purged
+    let error = client.purge_user(user.id).await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
+    client.delete_user(user.id, "*").await.unwrap();
+    client.purge_user(user.id).await.unwrap();
+    let error = client.restore_user(user.id).await.unwrap_err();
+    assert_eq!(error.status(), Some(StatusCode::NOT_FOUND));
 }
@@ -0,0 +1,122 @@
+use axum::http::{header, HeaderName, Method, Request, StatusCode};
+use axum_web::application::api_version::{self, ApiVersion, ApiVersionSource};
+
+pub mod common;
+use common::{
+    in_memory::{test_admin, TestApp},
+    *,
+};
+
+const API_VERSION: HeaderName = HeaderName::from_static("api-version");
+
+#[tokio::test]
+async fn version_negotiation_test() {
+    let app = TestApp::start(vec![test_admin()]).await;
+    let access_token = app.login_admin().await;
+    let token = Some(access_token.as_str());
+
+    // the unversioned routes fall back to the default version
+    let (status, headers, json) = app.send(Method::GET, "/users", token, &[], None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[&API_VERSION], "v1");
+    assert!(json.is_array());
+
+    let (status, headers, _) = app.send(Method::GET, "/", token, &[], None).await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[&API_VERSION], "v1");
+
+    // the version header
+    let (status, headers, json) = app
+        .send(Method::GET, "/users", token, &[(API_VERSION, "v2")], None)
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[&API_VERSION], "v2");
+    assert!(json["items"].is_array());
+
+    // the vendor media type
+    let accept = "text/html, application/vnd.axum-web.v2+json; q=0.9";
+    let (status, headers, json) = app
+        .send(
+            Method::GET,
+            "/users",
+            token,
+            &[(header::ACCEPT, accept)],
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[&API_VERSION], "v2");
+    assert!(json["items"].is_array());
+
+    // the path segment takes precedence over the headers by default
+    let (status, headers, json) = app
+        .send(
+            Method::GET,
+            "/v1/users",
+            token,
+            &[(API_VERSION, "v2")],
+            None,
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[&API_VERSION], "v1");
+    assert!(json.is_array());
+
+    // the unknown versions are rejected
+    let (status, _, json) = app
+        .send(Method::GET, "/users", token, &[(API_VERSION, "v9")], None)
+        .await;
+    assert_eq!(status, StatusCode::NOT_ACCEPTABLE);
+    assert_eq!(json["code"], "invalid_api_version");
+
+    // the unversioned auth routes
+    let body = serde_json::json!({
+        "username": TEST_ADMIN_USERNAME,
+        "password_hash": TEST_ADMIN_PASSWORD_HASH
+    });
+    let (status, headers, json) = app
+        .send(
+            Method::POST,
+            "/auth/login",
+            None,
+            &[(API_VERSION, "v2")],
+            Some(body),
+        )
+        .await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(headers[&API_VERSION], "v2");
+    assert!(json["access_token"].is_string());
+}
+
+#[tokio::test]
+async fn version_precedence_test() {
+    let (mut parts, _) = Request::builder()
+        .uri("/users")
+        .header("api-version", "v2")
+        .header(header::ACCEPT, "application/vnd.axum-web.v1+json")
+        .body(())
+        .unwrap()
+        .into_parts();
+
+    use ApiVersionSource::*;
+    let negotiate = |precedence: &'static [ApiVersionSource]| {
+        let mut parts = parts.clone();
+        async move { api_version::negotiate_version(&mut parts, precedence, ApiVersion::V1).await }
+    };
+    assert_eq!(
+        negotiate(&[Path, Header, MediaType]).await.ok(),
+        Some(ApiVersion::V2)
+    );
+    assert_eq!(
+        negotiate(&[MediaType, Header]).await.ok(),
+        Some(ApiVersion::V1)
+    );
+    // the sources out of the precedence are ignored
+    assert_eq!(negotiate(&[Path]).await.ok(), Some(ApiVersion::V1));
+
+    parts.headers.clear();
+    let version = api_version::negotiate_version(&mut parts, &[Header, MediaType], ApiVersion::V2)
+        .await
+        .ok();
+    assert_eq!(version, Some(ApiVersion::V2));
+}
@@ -0,0 +1,97 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+
+// stable machine readable error codes, clients must rely on these instead of the messages
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum ErrorCode {
+    // authentication
+    WrongCredentials,
+    MissingCredentials,
+    TokenCreation,
+    InvalidToken,
+    ExpiredToken,
+    RevokedToken,
+    RevocationFailed,
+    RevocationUnavailable,
+    // api versioning
+    InvalidApiVersion,
+    Sunset,
+    // repositories
+    NotFound,
+    UniqueViolation,
+    ConstraintViolation,
+    StorageUnavailable,
+    StorageError,
+    // requests
+    BadRequest,
+    Unauthorized,
+    ValidationFailed,
+    PreconditionFailed,
+    PreconditionRequired,
+    NotAcceptable,
+    RouteNotFound,
+    InternalError,
+}
+
+impl ErrorCode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCode::WrongCredentials => "wrong_credentials",
+            ErrorCode::MissingCredentials => "missing_credentials",
+            ErrorCode::TokenCreation => "token_creation",
+            ErrorCode::InvalidToken => "invalid_token",
+            ErrorCode::ExpiredToken => "expired_token",
+            ErrorCode::RevokedToken => "revoked_token",
+            ErrorCode::RevocationFailed => "revocation_failed",
+            ErrorCode::RevocationUnavailable => "revocation_unavailable",
+            ErrorCode::InvalidApiVersion => "invalid_api_version",
+            ErrorCode::Sunset => "sunset",
+            ErrorCode::NotFound => "not_found",
+            ErrorCode::UniqueViolation => "unique_violation",
+            ErrorCode::ConstraintViolation => "constraint_violation",
+            ErrorCode::StorageUnavailable => "storage_unavailable",
+            ErrorCode::StorageError => "storage_error",
+            ErrorCode::BadRequest => "bad_request",
+            ErrorCode::Unauthorized => "unauthorized",
+            ErrorCode::ValidationFailed => "validation_failed",
+            ErrorCode::PreconditionFailed => "precondition_failed",
+            ErrorCode::PreconditionRequired => "precondition_required",
+            ErrorCode::NotAcceptable => "not_acceptable",
+            ErrorCode::RouteNotFound => "route_not_found",
+            ErrorCode::InternalError => "internal_error",
+        }
+    }
+}
+
+impl std::fmt::Display for ErrorCode {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}", self.as_str())
+    }
+}
+
+// a validation error of a single request field
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct FieldError {
+    pub field: String,
+    // the violated rule, e.g. length, email, unique
+    pub code: String,
+    pub message: String,
+}
+
+// RFC 7807 problem details, the instance and the request id are added by the problem middleware
+#[derive(Debug, Clone, Serialize, Deserialize, ToSchema)]
+pub struct ProblemDetails {
+    #[serde(rename = "type")]
+    pub problem_type: String,
+    pub title: String,
+    pub status: u16,
+    pub detail: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub instance: Option<String>,
+    pub code: ErrorCode,
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub errors: Vec<FieldError>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub request_id: Option<String>,
+}
@@ -0,0 +1,16 @@
+// user roles
+pub const USER_ROLE_ADMIN: &str = "admin";
+pub const USER_ROLE_GUEST: &str = "guest";
+
+// user input validation
+pub const USER_USERNAME_MAX_LENGTH: u64 = 64;
+pub const USER_EMAIL_MAX_LENGTH: u64 = 254;
+pub const USER_SALT_MIN_LENGTH: u64 = 16;
+pub const USER_SALT_MAX_LENGTH: u64 = 64;
+
+// users list pagination
+pub const HEADER_NEXT_CURSOR: &str = "x-next-cursor";
+pub const HEADER_TOTAL_COUNT: &str = "x-total-count";
+
+// api versioning
+pub const HEADER_API_VERSION: &str = "api-version";
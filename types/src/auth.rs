@@ -0,0 +1,32 @@
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::app_const::USER_USERNAME_MAX_LENGTH;
+
+// only the shape is validated, wrong credentials must still be answered with 401
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct LoginUser {
+    #[validate(length(min = 1, max = USER_USERNAME_MAX_LENGTH))]
+    pub username: String,
+    #[validate(length(min = 1, max = 128))]
+    pub password_hash: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, Validate, ToSchema)]
+pub struct RevokeUser {
+    pub user_id: Uuid,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct TokensResponse {
+    pub access_token: String,
+    pub refresh_token: String,
+    pub token_type: String,
+}
+
+#[derive(Debug, Serialize, Deserialize, ToSchema)]
+pub struct CleanupResponse {
+    pub deleted_tokens: usize,
+}
@@ -0,0 +1,7 @@
+// the wire format of the axum-web api, shared by the server and the client without the server dependencies
+pub mod api_error;
+pub mod app_const;
+pub mod auth;
+pub mod service;
+pub mod users;
+pub mod validation;
@@ -0,0 +1,20 @@
+use serde::{Deserialize, Serialize};
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Heartbeat {
+    pub service: String,
+    pub version: String,
+    #[serde(rename = "heartbeat-id")]
+    pub heartbeat_id: String,
+}
+
+// the readiness of the service for the load balancers, not ready once the shutdown starts
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Readiness {
+    pub ready: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Greeting {
+    pub message: String,
+}
@@ -0,0 +1,78 @@
+use chrono::NaiveDateTime;
+use serde::{de::DeserializeOwned, Deserialize, Serialize};
+use utoipa::{IntoParams, ToSchema};
+use uuid::Uuid;
+use validator::Validate;
+
+use crate::{app_const::*, validation};
+
+pub mod v1;
+pub mod v2;
+
+// the wire format of the users api, implemented once per api version;
+// v1 is frozen, incompatible changes go to a new version
+pub trait UserContract: Send + Sync + 'static {
+    // the path segment of the version, e.g. v1
+    const VERSION: &'static str;
+    type CreateUserRequest: Serialize + DeserializeOwned;
+    type UpdateUserRequest: Serialize + DeserializeOwned;
+    type UserResponse: Serialize + DeserializeOwned;
+    type UserListResponse: Serialize + DeserializeOwned;
+}
+
+// new users are always active guests, the request is the same in every version
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+pub struct CreateUserRequest {
+    #[serde(default = "Uuid::new_v4")]
+    pub id: Uuid,
+    #[validate(
+        length(min = 1, max = USER_USERNAME_MAX_LENGTH),
+        custom(function = "validation::not_blank")
+    )]
+    pub username: String,
+    #[validate(email, length(max = USER_EMAIL_MAX_LENGTH))]
+    pub email: String,
+    #[validate(custom(function = "validation::password_hash"))]
+    pub password_hash: String,
+    #[validate(length(min = USER_SALT_MIN_LENGTH, max = USER_SALT_MAX_LENGTH))]
+    pub password_salt: String,
+}
+
+// the query of the users list, shared with the client
+#[derive(Debug, Clone, Default, Serialize, Deserialize, IntoParams)]
+#[into_params(parameter_in = Query)]
+pub struct ListUsersParams {
+    pub limit: Option<u32>,
+    pub cursor: Option<String>,
+    #[serde(default)]
+    pub sort: UserSortField,
+    #[serde(default)]
+    pub order: SortOrder,
+    pub active: Option<bool>,
+    pub role: Option<String>,
+    pub username_prefix: Option<String>,
+    pub email_prefix: Option<String>,
+    pub created_after: Option<NaiveDateTime>,
+    pub created_before: Option<NaiveDateTime>,
+    #[serde(default)]
+    pub include_total: bool,
+}
+
+// whitelisted columns for sorting the users
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum UserSortField {
+    Username,
+    Email,
+    #[default]
+    CreatedAt,
+    UpdatedAt,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Default, Serialize, Deserialize, ToSchema)]
+#[serde(rename_all = "snake_case")]
+pub enum SortOrder {
+    #[default]
+    Asc,
+    Desc,
+}
@@ -0,0 +1,80 @@
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use utoipa::ToSchema;
+use uuid::Uuid;
+use validator::{Validate, ValidationError};
+
+use super::{CreateUserRequest, UserContract};
+use crate::{app_const::*, validation};
+
+pub struct V1;
+
+impl UserContract for V1 {
+    const VERSION: &'static str = "v1";
+    type CreateUserRequest = CreateUserRequest;
+    type UpdateUserRequest = UpdateUserRequest;
+    type UserResponse = UserResponse;
+    type UserListResponse = UserListResponse;
+}
+
+// the password is changed only if both the hash and the salt are sent
+#[derive(Debug, Clone, Serialize, Deserialize, Validate, ToSchema)]
+#[validate(schema(function = "validate_password_change"))]
+pub struct UpdateUserRequest {
+    pub id: Uuid,
+    #[validate(
+        length(min = 1, max = USER_USERNAME_MAX_LENGTH),
+        custom(function = "validation::not_blank")
+    )]
+    pub username: String,
+    #[validate(email, length(max = USER_EMAIL_MAX_LENGTH))]
+    pub email: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(custom(function = "validation::password_hash"))]
+    pub password_hash: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[validate(length(min = USER_SALT_MIN_LENGTH, max = USER_SALT_MAX_LENGTH))]
+    pub password_salt: Option<String>,
+    pub active: bool,
+    #[validate(custom(function = "validation::roles"))]
+    pub roles: String,
+}
+
+fn validate_password_change(request: &UpdateUserRequest) -> Result<(), ValidationError> {
+    validation::password_change(&request.password_hash, &request.password_salt)
+}
+
+// the fields of a merge patch, the absent fields are kept, none can be removed by null
+#[derive(Debug, Clone, Default, Serialize, Deserialize, ToSchema)]
+pub struct UserPatchRequest {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(nullable = false)]
+    pub username: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(nullable = false)]
+    pub email: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(nullable = false)]
+    pub active: Option<bool>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    #[schema(nullable = false)]
+    pub roles: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+pub struct UserResponse {
+    pub id: Uuid,
+    pub username: String,
+    pub email: String,
+    pub active: bool,
+    pub roles: String,
+    pub created_at: Option<NaiveDateTime>,
+    pub updated_at: Option<NaiveDateTime>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub deleted_at: Option<NaiveDateTime>,
+}
+
+// a bare array, the cursor and the total count are sent in headers
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize, ToSchema)]
+#[serde(transparent)]
+pub struct UserListResponse(pub Vec<UserResponse>);
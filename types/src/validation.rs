@@ -0,0 +1,53 @@
+use validator::ValidationError;
+
+use crate::app_const::{USER_ROLE_ADMIN, USER_ROLE_GUEST};
+
+pub fn not_blank(value: &str) -> Result<(), ValidationError> {
+    if value.trim().is_empty() {
+        return Err(ValidationError::new("not_blank").with_message("must not be blank".into()));
+    }
+    Ok(())
+}
+
+// the clients send the SHA-256 hex digest of the password and the salt, never the password
+pub fn password_hash(value: &str) -> Result<(), ValidationError> {
+    if value.len() != 64 || !value.bytes().all(|b| b.is_ascii_hexdigit()) {
+        return Err(ValidationError::new("password_hash")
+            .with_message("must be a SHA-256 hex digest".into()));
+    }
+    Ok(())
+}
+
+// a new password hash is useless without its salt and vice versa
+pub fn password_change(
+    password_hash: &Option<String>,
+    password_salt: &Option<String>,
+) -> Result<(), ValidationError> {
+    let missing = match (password_hash, password_salt) {
+        (Some(_), None) => "password_salt",
+        (None, Some(_)) => "password_hash",
+        _ => return Ok(()),
+    };
+    let mut error = ValidationError::new("required")
+        .with_message("password_hash and password_salt must be changed together".into());
+    error.add_param("field".into(), &missing);
+    Err(error)
+}
+
+// a comma separated list of the known roles
+pub fn roles(value: &str) -> Result<(), ValidationError> {
+    role_names(value.split(','))
+}
+
+pub fn role_list(value: &[String]) -> Result<(), ValidationError> {
+    role_names(value.iter().map(String::as_str))
+}
+
+fn role_names<'a>(mut roles: impl Iterator<Item = &'a str>) -> Result<(), ValidationError> {
+    let known = [USER_ROLE_ADMIN, USER_ROLE_GUEST];
+    if roles.any(|role| !known.contains(&role.trim())) {
+        return Err(ValidationError::new("roles")
+            .with_message(format!("allowed roles are: {}", known.join(", ")).into()));
+    }
+    Ok(())
+}